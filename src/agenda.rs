@@ -0,0 +1,351 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::{Action, EngineError, Fact, LogicalOperator, Solution};
+
+/// Selects which of the activations on the agenda fires first when their rules have the same stratum and salience.
+///
+/// Whatever the strategy, activations of rules in earlier strata fire first, so that the facts a rule negates are
+/// complete before it fires. Within a stratum, activations of rules with a higher salience fire before those of rules
+/// with a lower one, and activations the strategy does not tell apart fire in definition order.
+///
+/// Variants:
+/// - `Definition`: Activations fire in the order their rules were defined, and the activations of a rule in the order
+///   its matches were found. This is the default.
+/// - `Recency`: Activations matching the most recently asserted fact fire first.
+/// - `Specificity`: Activations of rules whose premise tests more conditions fire first.
+/// - `Lex`: Activations are compared by the time tags of the facts they matched, most recent first, as in OPS5: the
+///   activation matching the most recent fact fires first, ties are broken by the next most recent fact, and an
+///   activation matching further facts fires before one that does not. Remaining ties are broken by specificity.
+/// - `Mea`: Activations whose first condition matched the most recent fact fire first, with ties broken as by `Lex`.
+///   This suits rule sets whose first condition selects the goal currently being worked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictStrategy {
+    #[default]
+    Definition,
+    Recency,
+    Specificity,
+    Lex,
+    Mea,
+}
+
+/// A rule waiting on the agenda to fire, together with the instantiated actions it will execute and the matches of
+/// its premise producing them.
+///
+/// The facts matched by an activation are identified by their time tags, which increase every time a fact is added to
+/// the knowledge base, so that a fact retracted and asserted again counts as a new fact.
+#[derive(Debug, Clone)]
+pub struct Activation {
+    pub(crate) rule: usize,
+    pub(crate) actions: Vec<Action>,
+    pub(crate) matches: Vec<Solution>,
+    /// The time tags of the facts each match relied on, in the order of the premise's conditions.
+    tags: Vec<Vec<u64>>,
+    /// The position of each match among the matches of the rule, as the tags of its token in the match network, by
+    /// which the matches are sorted.
+    positions: Vec<Vec<u64>>,
+    stratum: usize,
+    salience: i32,
+    specificity: usize,
+    /// The position of the activation's first match when the activation was added to the agenda, which orders it among
+    /// the activations of its rule.
+    position: Vec<u64>,
+}
+
+impl Activation {
+    pub(crate) fn new(rule: usize, actions: Vec<Action>, stratum: usize, salience: i32, specificity: usize) -> Self {
+        Self { rule, actions, matches: Vec::new(), tags: Vec::new(), positions: Vec::new(), stratum, salience, specificity, position: Vec::new() }
+    }
+
+    pub(crate) fn add_match(&mut self, solution: Solution, tags: Vec<u64>, position: Vec<u64>) {
+        let index = self.positions.partition_point(|existing| *existing < position);
+        self.matches.insert(index, solution);
+        self.tags.insert(index, tags);
+        self.positions.insert(index, position);
+    }
+
+    /// Removes the matches at the given positions.
+    fn drop_matches(&mut self, dropped: &HashSet<&[u64]>) {
+        let mut index = 0;
+        while index < self.positions.len() {
+            if dropped.contains(self.positions[index].as_slice()) {
+                self.matches.remove(index);
+                self.tags.remove(index);
+                self.positions.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Returns the index of the rule the activation fires.
+    pub fn rule(&self) -> usize {
+        self.rule
+    }
+
+    /// Returns the stratum of the activation's rule.
+    pub fn stratum(&self) -> usize {
+        self.stratum
+    }
+
+    /// Returns the salience of the activation's rule.
+    pub fn salience(&self) -> i32 {
+        self.salience
+    }
+
+    /// Returns the instantiated actions executed when the activation fires, in execution order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the facts relied on by the most recent match of the activation, in the order of the premise's
+    /// conditions.
+    pub fn antecedents(&self) -> &[Fact] {
+        self.most_recent().map_or(&[], |index| &self.matches[index].antecedents)
+    }
+
+    /// Returns the index of the match whose facts are the most recent, compared as by `ConflictStrategy::Lex`.
+    fn most_recent(&self) -> Option<usize> {
+        (0..self.tags.len()).max_by(|&left, &right| recency(&self.tags[left]).cmp(&recency(&self.tags[right])).then(right.cmp(&left)))
+    }
+
+    /// Returns the time tags of the most recent match, most recent first.
+    fn lex_key(&self) -> Vec<u64> {
+        self.most_recent().map_or_else(Vec::new, |index| recency(&self.tags[index]))
+    }
+
+    /// Returns the time tag of the fact matched by the first condition of the most recent match.
+    fn mea_key(&self) -> u64 {
+        self.most_recent().and_then(|index| self.tags[index].first().copied()).unwrap_or(0)
+    }
+}
+
+/// The activations waiting to fire, in firing order, along with the matches that have already fired.
+///
+/// Refraction ensures that every match of a rule fires at most once: once an activation fires, its matches are
+/// remembered until they no longer hold, and only activations with new matches are put back on the agenda.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Agenda {
+    activations: Vec<Activation>,
+    fired: HashMap<usize, HashSet<Vec<u64>>>,
+    strategy: ConflictStrategy,
+}
+
+impl Agenda {
+    pub(crate) fn activations(&self) -> &[Activation] {
+        &self.activations
+    }
+
+    pub(crate) fn strategy(&self) -> ConflictStrategy {
+        self.strategy
+    }
+
+    /// Changes the conflict resolution strategy, reordering the whole agenda.
+    pub(crate) fn set_strategy(&mut self, strategy: ConflictStrategy) {
+        self.strategy = strategy;
+        self.activations.sort_by(|left, right| compare(strategy, left, right));
+    }
+
+    /// Changes the salience of a rule's activations, moving them to their new place on the agenda.
+    pub(crate) fn set_salience(&mut self, rule: usize, salience: i32) {
+        let (mut moved, kept): (Vec<Activation>, _) = std::mem::take(&mut self.activations).into_iter().partition(|activation| activation.rule == rule);
+        self.activations = kept;
+        moved.iter_mut().for_each(|activation| activation.salience = salience);
+        self.insert(moved);
+    }
+
+    /// Updates the strata of the activations after the rules changed, reordering the whole agenda.
+    pub(crate) fn set_strata(&mut self, strata: &[usize]) {
+        for activation in &mut self.activations {
+            activation.stratum = strata[activation.rule];
+        }
+        self.set_strategy(self.strategy);
+    }
+
+    /// Checks whether a match of a rule, identified by the time tags of its facts, has already fired.
+    pub(crate) fn has_fired(&self, rule: usize, tags: &[u64]) -> bool {
+        self.fired.get(&rule).is_some_and(|fired| fired.contains(tags))
+    }
+
+    /// Updates the activations of a rule after its matches changed.
+    ///
+    /// # Arguments
+    /// * `rule` - The index of the rule.
+    /// * `dropped` - The positions of the matches of the rule that no longer hold.
+    /// * `unmatched` - The time tags of the facts of matches that no longer hold and may fire again once matched.
+    /// * `activations` - The activations for the new matches that have not fired yet, in the order they were found.
+    ///
+    /// Activations that were already on the agenda keep their place, even if it was changed by `move_activation`, and
+    /// gain the new matches with the same actions, while new ones are inserted where the conflict resolution strategy
+    /// puts them. Activations left without matches are removed.
+    pub(crate) fn update(&mut self, rule: usize, dropped: &HashSet<&[u64]>, unmatched: &[&[u64]], activations: Vec<Activation>) {
+        if let Some(fired) = self.fired.get_mut(&rule) {
+            for tags in unmatched {
+                fired.remove(*tags);
+            }
+        }
+        if !dropped.is_empty() {
+            self.activations.iter_mut()
+                .filter(|existing| existing.rule == rule)
+                .for_each(|existing| existing.drop_matches(dropped));
+        }
+
+        let found: HashMap<Vec<Action>, usize> = activations.iter().enumerate().map(|(index, activation)| (activation.actions.clone(), index)).collect();
+        let mut activations: Vec<Option<Activation>> = activations.into_iter().map(Some).collect();
+        for existing in self.activations.iter_mut().filter(|existing| existing.rule == rule) {
+            let Some(activation) = found.get(&existing.actions).and_then(|&index| activations[index].take()) else {
+                continue;
+            };
+            let matches = activation.matches.into_iter().zip(activation.tags).zip(activation.positions);
+            for ((solution, tags), position) in matches {
+                existing.add_match(solution, tags, position);
+            }
+        }
+
+        let added = activations.into_iter().flatten().map(|mut activation| {
+            activation.position = activation.positions.first().cloned().unwrap_or_default();
+            activation
+        });
+        self.insert(added.collect());
+
+        if !dropped.is_empty() {
+            self.activations.retain(|existing| existing.rule != rule || !existing.matches.is_empty());
+        }
+    }
+
+    /// Removes the first activation from the agenda, recording its matches as fired.
+    pub(crate) fn pop(&mut self) -> Option<Activation> {
+        if self.activations.is_empty() {
+            return None;
+        }
+        let activation = self.activations.remove(0);
+        self.refract(&activation);
+        Some(activation)
+    }
+
+    /// Returns the lowest stratum of the activations on the agenda that is not below `minimum`.
+    pub(crate) fn next_stratum(&self, minimum: usize) -> Option<usize> {
+        self.activations.iter().map(|activation| activation.stratum).filter(|stratum| *stratum >= minimum).min()
+    }
+
+    /// Removes every activation of a stratum from the agenda, in firing order, recording their matches as fired.
+    pub(crate) fn take_stratum(&mut self, stratum: usize) -> Vec<Activation> {
+        let (taken, kept) = std::mem::take(&mut self.activations).into_iter().partition(|activation| activation.stratum == stratum);
+        self.activations = kept;
+        taken.iter().for_each(|activation| self.refract(activation));
+        taken
+    }
+
+    /// Moves the activation at index `from` so that it ends up at index `to`.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownActivation` if either index is not on the agenda.
+    pub(crate) fn move_activation(&mut self, from: usize, to: usize) -> Result<(), EngineError> {
+        for index in [from, to] {
+            if index >= self.activations.len() {
+                return Err(EngineError::UnknownActivation { index });
+            }
+        }
+        let activation = self.activations.remove(from);
+        self.activations.insert(to, activation);
+        Ok(())
+    }
+
+    /// Inserts activations, each before the first one on the agenda the strategy would fire after it, and after the
+    /// ones inserted with it that fire first.
+    fn insert(&mut self, mut activations: Vec<Activation>) {
+        if activations.is_empty() {
+            return;
+        }
+        activations.sort_by(|left, right| compare(self.strategy, left, right));
+
+        let mut inserted = activations.into_iter().peekable();
+        let mut merged = Vec::with_capacity(self.activations.len() + inserted.len());
+        for existing in std::mem::take(&mut self.activations) {
+            while let Some(activation) = inserted.next_if(|activation| compare(self.strategy, activation, &existing) == Ordering::Less) {
+                merged.push(activation);
+            }
+            merged.push(existing);
+        }
+        merged.extend(inserted);
+        self.activations = merged;
+    }
+
+    fn refract(&mut self, activation: &Activation) {
+        self.fired.entry(activation.rule).or_default().extend(activation.tags.iter().cloned());
+    }
+}
+
+/// Orders two activations by firing order under a strategy, the activation firing first being the lesser.
+fn compare(strategy: ConflictStrategy, left: &Activation, right: &Activation) -> Ordering {
+    let lex = |left: &Activation, right: &Activation| {
+        right.lex_key().cmp(&left.lex_key()).then(right.specificity.cmp(&left.specificity))
+    };
+
+    left.stratum.cmp(&right.stratum)
+        .then(right.salience.cmp(&left.salience))
+        .then_with(|| match strategy {
+            ConflictStrategy::Definition => Ordering::Equal,
+            ConflictStrategy::Recency => right.lex_key().first().cmp(&left.lex_key().first()),
+            ConflictStrategy::Specificity => right.specificity.cmp(&left.specificity),
+            ConflictStrategy::Lex => lex(left, right),
+            ConflictStrategy::Mea => right.mea_key().cmp(&left.mea_key()).then_with(|| lex(left, right)),
+        })
+        .then_with(|| (left.rule, &left.position).cmp(&(right.rule, &right.position)))
+}
+
+/// Returns time tags sorted most recent first, so that comparing them lexicographically compares their recency.
+fn recency(tags: &[u64]) -> Vec<u64> {
+    let mut sorted = tags.to_vec();
+    sorted.sort_unstable_by(|left, right| right.cmp(left));
+    sorted
+}
+
+/// Counts the fact patterns and comparisons tested by a premise.
+pub(crate) fn specificity(premise: &LogicalOperator) -> usize {
+    match premise {
+        LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => expressions.iter().map(specificity).sum(),
+        LogicalOperator::Not(expression) => specificity(expression),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activation(rule: usize, salience: i32, specificity: usize, tags: Vec<u64>) -> Activation {
+        let mut activation = Activation::new(rule, Vec::new(), 0, salience, specificity);
+        activation.add_match(Solution::new(HashMap::new()), tags, Vec::new());
+        activation
+    }
+
+    #[test]
+    fn strategies_order_activations_of_equal_salience() {
+        let older = activation(0, 0, 1, vec![3]);
+        let newer = activation(1, 0, 1, vec![5]);
+        let joined = activation(2, 0, 3, vec![5, 1]);
+        let goal = activation(3, 0, 2, vec![6, 2]);
+        let urgent = activation(4, 10, 1, vec![1]);
+        let order = |strategy| {
+            let mut agenda = Agenda { strategy, ..Agenda::default() };
+            for activation in [&older, &newer, &joined, &goal, &urgent] {
+                agenda.insert(vec![activation.clone()]);
+            }
+            agenda.activations.iter().map(Activation::rule).collect::<Vec<_>>()
+        };
+
+        assert_eq!(order(ConflictStrategy::Definition), vec![4, 0, 1, 2, 3]);
+        assert_eq!(order(ConflictStrategy::Recency), vec![4, 3, 1, 2, 0]);
+        assert_eq!(order(ConflictStrategy::Specificity), vec![4, 2, 3, 0, 1]);
+        assert_eq!(order(ConflictStrategy::Lex), vec![4, 3, 2, 1, 0]);
+        assert_eq!(order(ConflictStrategy::Mea), vec![4, 3, 2, 1, 0]);
+
+        // Under MEA the first condition's fact decides, however recent the others are
+        let late = activation(5, 0, 2, vec![2, 9]);
+        let mut agenda = Agenda { strategy: ConflictStrategy::Mea, ..Agenda::default() };
+        agenda.insert(vec![late]);
+        agenda.insert(vec![newer.clone()]);
+        assert_eq!(agenda.activations.iter().map(Activation::rule).collect::<Vec<_>>(), vec![1, 5]);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::datalog::Clause;
+use crate::working_memory::WorkingMemory;
+use crate::{ComparableValue, EngineError, Fact, LogicalOperator, Solution, SymbolicReasoningEngine};
+
+/// A ground instance of a rule: the facts it concludes once its fact patterns are matched by `positives` and every
+/// one of its `checks` holds.
+#[derive(Debug, Clone)]
+struct Instance {
+    conclusions: Vec<Fact>,
+    positives: Vec<Fact>,
+    checks: Vec<usize>,
+}
+
+/// A condition of a ground instance whose truth depends on the whole model rather than on single facts: a negation,
+/// or a comparison reading the value of a symbol.
+#[derive(Debug, Clone)]
+struct Check {
+    rule: usize,
+    condition: LogicalOperator,
+    solution: Solution,
+    /// Whether the condition negates an expression that only matches more facts as the model grows, so that its
+    /// truth can be decided from bounds on the model.
+    monotone: bool,
+}
+
+/// A stable model solver over the engine's rules and facts.
+///
+/// The rules are grounded against the facts that may hold in some model, and every check of the ground instances is
+/// then guessed to hold or not. Each guess leaves a lower bound on the model, derived by the instances whose checks
+/// are all guessed to hold, and an upper bound, derived by the instances with no check guessed not to hold. Negations
+/// whose truth is decided by the bounds are propagated before guessing further, and a complete guess is a stable
+/// model if the model it derives agrees with every guess.
+pub(crate) struct Solver {
+    /// An engine sharing the symbols and variables of the solved engine, whose facts are swapped for the models the
+    /// checks are evaluated against.
+    scratch: SymbolicReasoningEngine,
+    base: WorkingMemory,
+    instances: Vec<Instance>,
+    checks: Vec<Check>,
+}
+
+impl Solver {
+    /// Grounds the rules of an engine.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` wrapping `EngineError::NonDatalogRule` if a rule does more than asserting
+    /// facts built from the values it matches, and wrapping the errors raised while matching a rule's premise or
+    /// instantiating its conclusions.
+    pub(crate) fn new(engine: &SymbolicReasoningEngine) -> Result<Self, EngineError> {
+        let mut clauses = Vec::new();
+        for (index, rule) in engine.rules.iter().enumerate() {
+            clauses.extend(Clause::from_rule(index, rule)?);
+        }
+
+        let mut scratch = SymbolicReasoningEngine::new();
+        scratch.symbols = engine.symbols.clone();
+        scratch.variable_bindings = engine.variable_bindings.clone();
+        scratch.facts = engine.facts.clone();
+        let mut solver = Self { scratch, base: engine.facts.clone(), instances: Vec::new(), checks: Vec::new() };
+
+        // Ground the rules against the facts derived when every check holds, until no more facts can be derived
+        loop {
+            solver.instances.clear();
+            solver.checks.clear();
+            for clause in &clauses {
+                solver.instantiate(clause).map_err(|error| error.in_rule(clause.rule))?;
+            }
+            let upper = solver.least_model(|_| true);
+            if upper.len() == solver.scratch.facts.len() {
+                return Ok(solver);
+            }
+            solver.scratch.facts = upper;
+        }
+    }
+
+    /// Enumerates the stable models, each as the facts it adds to the knowledge base in the order they are derived.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` wrapping the errors raised while evaluating the negations and comparisons
+    /// of a rule.
+    pub(crate) fn stable_models(&mut self) -> Result<Vec<Vec<Fact>>, EngineError> {
+        let mut models = Vec::new();
+        self.search(vec![None; self.checks.len()], &mut models)?;
+        Ok(models)
+    }
+
+    /// Adds the ground instances of a clause matched against the scratch engine's facts.
+    fn instantiate(&mut self, clause: &Clause) -> Result<(), EngineError> {
+        let mut partial = vec![(Solution::new(self.scratch.variable_bindings.clone()), Vec::new())];
+        for condition in &clause.conditions {
+            let mut extended = Vec::new();
+            for (solution, mut checks) in partial {
+                match check(condition) {
+                    Some(monotone) => {
+                        checks.push(Check { rule: clause.rule, condition: condition.clone(), solution: solution.clone(), monotone });
+                        extended.push((solution, checks));
+                    },
+                    None => {
+                        let solutions = self.scratch.evaluate_logical_expression(condition, &solution, None)?;
+                        extended.extend(solutions.into_iter().map(|solution| (solution, checks.clone())));
+                    },
+                }
+            }
+            partial = extended;
+        }
+
+        for (solution, checks) in partial {
+            let conclusions = clause.conclusions.iter()
+                .map(|conclusion| self.scratch.apply_rule_conclusion(conclusion, &solution.bindings))
+                .collect::<Result<_, _>>()?;
+            let first = self.checks.len();
+            self.checks.extend(checks);
+            self.instances.push(Instance { conclusions, positives: solution.antecedents, checks: (first..self.checks.len()).collect() });
+        }
+        Ok(())
+    }
+
+    /// Returns the knowledge base extended with the facts derived by the enabled instances.
+    fn least_model(&self, enabled: impl Fn(&Instance) -> bool) -> WorkingMemory {
+        let mut model = self.base.clone();
+        let mut pending: Vec<&Instance> = self.instances.iter().filter(|instance| enabled(instance)).collect();
+        loop {
+            let before = pending.len();
+            pending.retain(|instance| {
+                if !instance.positives.iter().all(|fact| model.contains(fact)) {
+                    return true;
+                }
+                for conclusion in &instance.conclusions {
+                    model.push(conclusion.clone());
+                }
+                false
+            });
+            if pending.len() == before {
+                return model;
+            }
+        }
+    }
+
+    /// Checks whether an expression of a check holds in a model under the bindings of the check.
+    fn holds(&mut self, check: &Check, expression: &LogicalOperator, model: &mut WorkingMemory) -> Result<bool, EngineError> {
+        std::mem::swap(&mut self.scratch.facts, model);
+        let solutions = self.scratch.evaluate_logical_expression(expression, &check.solution, None);
+        std::mem::swap(&mut self.scratch.facts, model);
+        Ok(!solutions.map_err(|error| error.in_rule(check.rule))?.is_empty())
+    }
+
+    fn search(&mut self, mut assignment: Vec<Option<bool>>, models: &mut Vec<Vec<Fact>>) -> Result<(), EngineError> {
+        let mut lower;
+        loop {
+            lower = self.least_model(|instance| instance.checks.iter().all(|&check| assignment[check] == Some(true)));
+            let mut upper = self.least_model(|instance| instance.checks.iter().all(|&check| assignment[check] != Some(false)));
+
+            // A negation holds in every model between the bounds if the negated expression is not matched by the upper
+            // bound, and in none if it is matched by the lower bound
+            let mut propagated = false;
+            for (index, guess) in assignment.iter_mut().enumerate() {
+                let check = self.checks[index].clone();
+                let (LogicalOperator::Not(negated), true) = (&check.condition, check.monotone) else {
+                    continue;
+                };
+                let decided = if self.holds(&check, negated, &mut lower)? {
+                    false
+                } else if !self.holds(&check, negated, &mut upper)? {
+                    true
+                } else {
+                    continue;
+                };
+                match *guess {
+                    None => {
+                        *guess = Some(decided);
+                        propagated = true;
+                    },
+                    Some(guessed) if guessed != decided => return Ok(()),
+                    Some(_) => {},
+                }
+            }
+            if !propagated {
+                break;
+            }
+        }
+
+        let Some(unassigned) = assignment.iter().position(Option::is_none) else {
+            // Every check is guessed, so both bounds are the model, which must agree with every guess
+            for (index, guess) in assignment.iter().enumerate() {
+                let check = self.checks[index].clone();
+                if self.holds(&check, &check.condition, &mut lower)? != guess.unwrap_or_default() {
+                    return Ok(());
+                }
+            }
+            models.push(lower.iter().skip(self.base.len()).cloned().collect());
+            return Ok(());
+        };
+
+        for guess in [true, false] {
+            let mut guessed = assignment.clone();
+            guessed[unassigned] = Some(guess);
+            self.search(guessed, models)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether a condition is a check, and if so whether it is a monotone negation.
+fn check(condition: &LogicalOperator) -> Option<bool> {
+    match condition {
+        LogicalOperator::Not(negated) => Some(monotone(negated)),
+        LogicalOperator::AtomicFact(_) => None,
+        condition => reads_symbols(condition).then_some(false),
+    }
+}
+
+/// Checks whether an expression only matches more facts as the knowledge base grows.
+fn monotone(expression: &LogicalOperator) -> bool {
+    match expression {
+        LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => expressions.iter().all(monotone),
+        LogicalOperator::Not(_) => false,
+        LogicalOperator::AtomicFact(_) => true,
+        comparison => !reads_symbols(comparison),
+    }
+}
+
+/// Checks whether a comparison reads the value of a symbol.
+fn reads_symbols(comparison: &LogicalOperator) -> bool {
+    match comparison {
+        LogicalOperator::GreaterThan(left, right)
+        | LogicalOperator::LessThan(left, right)
+        | LogicalOperator::EqualTo(left, right)
+        | LogicalOperator::NotEqualTo(left, right)
+        | LogicalOperator::GreaterThanOrEqualTo(left, right)
+        | LogicalOperator::LessThanOrEqualTo(left, right) => {
+            [left, right].into_iter().flat_map(|value| value.leaves()).any(|value| !matches!(value, ComparableValue::Direct(_)))
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::atom;
+    use crate::FactValue;
+
+    #[test]
+    fn only_negations_of_growing_expressions_are_monotone() {
+        let atom = atom("A");
+        let reading = LogicalOperator::GreaterThan(
+            Box::new(ComparableValue::SymbolName("Level".into())),
+            Box::new(ComparableValue::Direct(FactValue::Integer(3))),
+        );
+        let constant = LogicalOperator::LessThan(
+            Box::new(ComparableValue::Direct(FactValue::Integer(1))),
+            Box::new(ComparableValue::Direct(FactValue::Integer(3))),
+        );
+        let not = |expression: LogicalOperator| LogicalOperator::Not(Box::new(expression));
+
+        assert_eq!(check(&atom), None);
+        assert_eq!(check(&constant), None);
+        assert_eq!(check(&reading), Some(false));
+        assert_eq!(check(&not(LogicalOperator::And(vec![atom.clone(), constant]))), Some(true));
+        assert_eq!(check(&not(LogicalOperator::Or(vec![atom.clone(), reading]))), Some(false));
+        assert_eq!(check(&not(not(atom))), Some(false));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::{ComparableValue, EngineError, SymbolicReasoningEngine, Variable};
+
+/// Represents an arithmetic expression over comparison operands, such as `Temperature - Dewpoint` or
+/// `Price * Quantity`.
+///
+/// Arithmetic expressions are comparison operands themselves, wrapped in `ComparableValue::Arithmetic`, so they may be
+/// compared with any of the comparison operators and nest inside one another. Their operands are resolved to numbers
+/// like the operands of ordering comparisons: values of symbols, numeric literals and bound pattern variables. Operands
+/// of a non-numeric type are rejected with `EngineError::NonNumericOperand` when the comparison is defined.
+/// Evaluation is carried out on `f64`, so an expression always evaluates to a float.
+///
+/// Variants:
+/// - `Add`, `Subtract`, `Multiply`: The sum, difference and product of two operands.
+/// - `Divide`: The quotient of two operands. A divisor of zero raises `EngineError::DivisionByZero`.
+/// - `Modulo`: The remainder of the division of two operands, which has the sign of the dividend. A divisor of zero
+///   raises `EngineError::DivisionByZero`.
+/// - `Abs`: The absolute value of an operand.
+/// - `Min`, `Max`: The smallest and largest of one or more operands. Expressions without operands are rejected with
+///   `EngineError::MissingOperand`.
+/// - `Call`: The result of the host function registered under `function` through `register_function`, applied to the
+///   values of the `arguments`.
+///
+/// # Examples
+/// ```
+/// use symbolic_reasoning_engine::{Arithmetic, ComparableValue, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+///
+/// let mut engine = SymbolicReasoningEngine::new();
+/// let price = engine.define_symbol("Price", SymbolType::Float)?;
+/// let quantity = engine.define_symbol("Quantity", SymbolType::Integer)?;
+/// engine.assert_fact(price.clone(), FactValue::Float(12.5))?;
+/// engine.assert_fact(quantity.clone(), FactValue::Integer(100))?;
+///
+/// let total = Arithmetic::Multiply(Box::new(ComparableValue::Symbol(price)), Box::new(ComparableValue::Symbol(quantity)));
+/// let large_order = LogicalOperator::GreaterThan(
+///     Box::new(ComparableValue::Arithmetic(Box::new(total))),
+///     Box::new(ComparableValue::Direct(FactValue::Integer(1000))),
+/// );
+/// assert_eq!(large_order.to_string(), "Price * Quantity > 1000");
+/// assert!(engine.is_premise_true(&large_order)?);
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arithmetic {
+    Add(Box<ComparableValue>, Box<ComparableValue>),
+    Subtract(Box<ComparableValue>, Box<ComparableValue>),
+    Multiply(Box<ComparableValue>, Box<ComparableValue>),
+    Divide(Box<ComparableValue>, Box<ComparableValue>),
+    Modulo(Box<ComparableValue>, Box<ComparableValue>),
+    Abs(Box<ComparableValue>),
+    Min(Vec<ComparableValue>),
+    Max(Vec<ComparableValue>),
+    Call { function: String, arguments: Vec<ComparableValue> },
+}
+
+/// The constructor of a binary arithmetic operation between two operands.
+pub(crate) type Operation = fn(Box<ComparableValue>, Box<ComparableValue>) -> Arithmetic;
+
+/// A host function invoked by `Arithmetic::Call` with the values of its arguments.
+pub type Function = Box<dyn Fn(&[f64]) -> Result<f64, EngineError>>;
+
+impl Arithmetic {
+    /// Returns the operands of the expression, in the order they are written.
+    pub(crate) fn operands(&self) -> Vec<&ComparableValue> {
+        match self {
+            Arithmetic::Add(left, right)
+            | Arithmetic::Subtract(left, right)
+            | Arithmetic::Multiply(left, right)
+            | Arithmetic::Divide(left, right)
+            | Arithmetic::Modulo(left, right) => vec![left, right],
+            Arithmetic::Abs(operand) => vec![operand],
+            Arithmetic::Min(operands) | Arithmetic::Max(operands) | Arithmetic::Call { arguments: operands, .. } => operands.iter().collect(),
+        }
+    }
+
+    /// Returns the operator or function the expression applies, as written in the rule language.
+    pub(crate) fn operator(&self) -> &str {
+        match self {
+            Arithmetic::Add(..) => "+",
+            Arithmetic::Subtract(..) => "-",
+            Arithmetic::Multiply(..) => "*",
+            Arithmetic::Divide(..) => "/",
+            Arithmetic::Modulo(..) => "%",
+            Arithmetic::Abs(_) => "abs",
+            Arithmetic::Min(_) => "min",
+            Arithmetic::Max(_) => "max",
+            Arithmetic::Call { function, .. } => function,
+        }
+    }
+
+    /// Returns a copy of the expression with every operand replaced by the result of `map`.
+    pub(crate) fn map_operands(&self, mut map: impl FnMut(&ComparableValue) -> ComparableValue) -> Arithmetic {
+        let mut binary = |left: &ComparableValue, right: &ComparableValue| (Box::new(map(left)), Box::new(map(right)));
+        match self {
+            Arithmetic::Add(left, right) => {
+                let (left, right) = binary(left, right);
+                Arithmetic::Add(left, right)
+            },
+            Arithmetic::Subtract(left, right) => {
+                let (left, right) = binary(left, right);
+                Arithmetic::Subtract(left, right)
+            },
+            Arithmetic::Multiply(left, right) => {
+                let (left, right) = binary(left, right);
+                Arithmetic::Multiply(left, right)
+            },
+            Arithmetic::Divide(left, right) => {
+                let (left, right) = binary(left, right);
+                Arithmetic::Divide(left, right)
+            },
+            Arithmetic::Modulo(left, right) => {
+                let (left, right) = binary(left, right);
+                Arithmetic::Modulo(left, right)
+            },
+            Arithmetic::Abs(operand) => Arithmetic::Abs(Box::new(map(operand))),
+            Arithmetic::Min(operands) => Arithmetic::Min(operands.iter().map(map).collect()),
+            Arithmetic::Max(operands) => Arithmetic::Max(operands.iter().map(map).collect()),
+            Arithmetic::Call { function, arguments } => Arithmetic::Call { function: function.clone(), arguments: arguments.iter().map(map).collect() },
+        }
+    }
+}
+
+impl ComparableValue {
+    /// Returns the operands that are not arithmetic expressions: the operand itself, or the operands of the
+    /// expression and of the expressions nested in it, in the order they are written.
+    pub(crate) fn leaves(&self) -> Vec<&ComparableValue> {
+        match self {
+            ComparableValue::Arithmetic(expression) => expression.operands().into_iter().flat_map(ComparableValue::leaves).collect(),
+            value => vec![value],
+        }
+    }
+}
+
+impl SymbolicReasoningEngine {
+    /// Evaluates an arithmetic expression against the knowledge base, resolving pattern variables against `bindings`.
+    ///
+    /// # Errors
+    /// Returns `EngineError::DivisionByZero` if a division or remainder has a divisor of zero,
+    /// `EngineError::MissingOperand` if a `min` or `max` has no operand, `EngineError::UnknownFunction` if a called
+    /// function has not been registered, the errors returned by called functions, and the errors raised while resolving
+    /// the operands.
+    pub(crate) fn evaluate_arithmetic(&self, expression: &Arithmetic, bindings: &HashMap<String, Variable>) -> Result<f64, EngineError> {
+        let value = |operand: &ComparableValue| self.resolve_comparable_value(operand, bindings);
+        let values = |operands: &[ComparableValue]| operands.iter().map(value).collect::<Result<Vec<_>, _>>();
+        match expression {
+            Arithmetic::Add(left, right) => Ok(value(left)? + value(right)?),
+            Arithmetic::Subtract(left, right) => Ok(value(left)? - value(right)?),
+            Arithmetic::Multiply(left, right) => Ok(value(left)? * value(right)?),
+            Arithmetic::Divide(left, right) | Arithmetic::Modulo(left, right) => {
+                let (dividend, divisor) = (value(left)?, value(right)?);
+                if divisor == 0.0 {
+                    return Err(EngineError::DivisionByZero { expression: expression.to_string() });
+                }
+                Ok(match expression {
+                    Arithmetic::Divide(..) => dividend / divisor,
+                    _ => dividend % divisor,
+                })
+            },
+            Arithmetic::Abs(operand) => Ok(value(operand)?.abs()),
+            Arithmetic::Min(operands) | Arithmetic::Max(operands) => {
+                let extremum = if matches!(expression, Arithmetic::Min(_)) { f64::min } else { f64::max };
+                values(operands)?.into_iter()
+                    .reduce(extremum)
+                    .ok_or_else(|| EngineError::MissingOperand { operator: expression.operator().to_string() })
+            },
+            Arithmetic::Call { function, arguments } => {
+                let function = self.functions.get(function).ok_or_else(|| EngineError::UnknownFunction { name: function.clone() })?;
+                function(&values(arguments)?)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactValue, Symbol, SymbolType};
+
+    fn direct(value: i32) -> Box<ComparableValue> {
+        Box::new(ComparableValue::Direct(FactValue::Integer(value)))
+    }
+
+    #[test]
+    fn expressions_evaluate_to_floats() {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.register_function("double", |arguments| Ok(arguments.iter().sum::<f64>() * 2.0));
+        let evaluate = |expression: Arithmetic| engine.evaluate_arithmetic(&expression, &HashMap::new());
+
+        assert_eq!(evaluate(Arithmetic::Modulo(direct(-7), direct(3))), Ok(-1.0));
+        assert_eq!(evaluate(Arithmetic::Divide(direct(7), direct(2))), Ok(3.5));
+        assert_eq!(evaluate(Arithmetic::Min(vec![*direct(4), *direct(-3)])), Ok(-3.0));
+        assert_eq!(evaluate(Arithmetic::Min(Vec::new())), Err(EngineError::MissingOperand { operator: "min".into() }));
+        assert_eq!(evaluate(Arithmetic::Max(vec![*direct(-2), ComparableValue::Arithmetic(Box::new(Arithmetic::Abs(direct(-5))))])), Ok(5.0));
+        assert_eq!(evaluate(Arithmetic::Call { function: "double".into(), arguments: vec![*direct(1), *direct(2)] }), Ok(6.0));
+        assert_eq!(evaluate(Arithmetic::Call { function: "halve".into(), arguments: Vec::new() }), Err(EngineError::UnknownFunction { name: "halve".into() }));
+        assert_eq!(evaluate(Arithmetic::Modulo(direct(1), direct(0))), Err(EngineError::DivisionByZero { expression: "1 % 0".into() }));
+    }
+
+    #[test]
+    fn leaves_flatten_nested_expressions() {
+        let level = ComparableValue::Symbol(Symbol::new("Level", SymbolType::Integer));
+        let nested = Arithmetic::Add(Box::new(level.clone()), Box::new(ComparableValue::Arithmetic(Box::new(Arithmetic::Abs(direct(2))))));
+        let value = ComparableValue::Arithmetic(Box::new(nested));
+        assert_eq!(value.leaves(), vec![&level, direct(2).as_ref()]);
+    }
+}
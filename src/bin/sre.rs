@@ -0,0 +1,202 @@
+//! `sre`: an interactive shell for exploring a knowledge base of the symbolic reasoning engine.
+//!
+//! Statements of the rule language are loaded as they are typed, and commands run the engine and inspect its
+//! knowledge base. Knowledge base files given as arguments are loaded before the first prompt. Type `help` for the
+//! list of commands.
+
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+
+use symbolic_reasoning_engine::SymbolicReasoningEngine;
+
+const HELP: &str = "\
+Statements:
+  symbol Name(Types): Type      define a symbol, such as `symbol Temperature: Float`
+  variable name = value         bind a variable, optionally followed by `unstable`
+  fact Symbol(args) = value     assert a fact
+  rule [name] [salience N]: premise => action, ...
+                                add a rule
+  End a line with `\\` to continue a statement on the next line.
+
+Commands:
+  run                           run forward chaining and list the facts it derived
+  ask Symbol(args) = value      prove a goal by backward chaining
+  facts                         list the facts of the knowledge base
+  rules                         list the rules with their indices
+  debug on|off                  toggle the engine's debug output
+  load PATH                     replace the knowledge base with a saved one
+  save PATH                     save the knowledge base
+  help                          show this help
+  quit                          leave the shell";
+
+/// What the shell does after a line has been executed.
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Output(String),
+    Quit,
+}
+
+/// The state of a shell session: the engine whose knowledge base is explored.
+struct Session {
+    engine: SymbolicReasoningEngine,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self { engine: SymbolicReasoningEngine::new() }
+    }
+
+    /// Executes a line of input, which is a statement of the rule language, a command, or blank.
+    ///
+    /// # Errors
+    /// Returns the errors raised by the engine, and a message for unknown or malformed commands.
+    fn execute(&mut self, line: &str) -> Result<Reply, Box<dyn Error>> {
+        let line = line.trim();
+        // The command is the leading word, which a `:` also ends, as in the anonymous `rule: A => B`
+        let end = line.find(|c: char| c.is_whitespace() || c == ':').unwrap_or(line.len());
+        let (command, argument) = (&line[..end], line[end..].trim());
+        let output = match command {
+            "" => String::new(),
+            _ if command.starts_with('#') => String::new(),
+            "symbol" | "variable" | "fact" | "rule" => {
+                self.engine.load_program(line)?;
+                String::new()
+            },
+            "run" => {
+                let before: Vec<_> = self.engine.facts().cloned().collect();
+                self.engine.forward_chaining()?;
+                let derived: Vec<String> = self.engine.facts().filter(|fact| !before.contains(fact)).map(ToString::to_string).collect();
+                match derived.is_empty() {
+                    true => "no new facts".to_string(),
+                    false => derived.join("\n"),
+                }
+            },
+            "ask" => {
+                let goal = self.engine.parse_fact(argument)?;
+                match self.engine.specify_goal(&goal)? {
+                    true => "yes".to_string(),
+                    false => "no".to_string(),
+                }
+            },
+            "facts" => self.engine.facts().map(ToString::to_string).collect::<Vec<_>>().join("\n"),
+            "rules" => {
+                let rules = self.engine.rules().iter().enumerate().map(|(index, rule)| format!("[{}] {}", index, rule));
+                rules.collect::<Vec<_>>().join("\n")
+            },
+            "debug" => match argument {
+                "on" => {
+                    self.engine.enable_debug();
+                    "debug output on".to_string()
+                },
+                "off" => {
+                    self.engine.disable_debug();
+                    "debug output off".to_string()
+                },
+                _ => return Err("expected 'debug on' or 'debug off'".into()),
+            },
+            "load" => {
+                self.engine = SymbolicReasoningEngine::load_from(path(argument)?)?;
+                format!("loaded {}", argument)
+            },
+            "save" => {
+                self.engine.save_to(path(argument)?)?;
+                format!("saved {}", argument)
+            },
+            "help" => HELP.to_string(),
+            "quit" | "exit" => return Ok(Reply::Quit),
+            command => return Err(format!("unknown command '{}', type 'help' for the list of commands", command).into()),
+        };
+        Ok(Reply::Output(output))
+    }
+}
+
+fn path(argument: &str) -> Result<&str, Box<dyn Error>> {
+    match argument {
+        "" => Err("expected a file path".into()),
+        path => Ok(path),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut session = Session::new();
+    for path in env::args().skip(1) {
+        if let Err(error) = session.execute(&format!("load {}", path)) {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("sre> ");
+            io::stdout().flush().ok();
+        }
+        let mut statement = String::new();
+        loop {
+            match lines.next() {
+                Some(Ok(line)) => match line.strip_suffix('\\') {
+                    Some(continued) => {
+                        statement.push_str(continued);
+                        statement.push('\n');
+                        if interactive {
+                            print!("...> ");
+                            io::stdout().flush().ok();
+                        }
+                    },
+                    None => {
+                        statement.push_str(&line);
+                        break;
+                    },
+                },
+                Some(Err(error)) => {
+                    eprintln!("error: {}", error);
+                    return ExitCode::FAILURE;
+                },
+                None if statement.is_empty() => return ExitCode::SUCCESS,
+                None => break,
+            }
+        }
+        match session.execute(&statement) {
+            Ok(Reply::Output(output)) if output.is_empty() => {},
+            Ok(Reply::Output(output)) => println!("{}", output),
+            Ok(Reply::Quit) => return ExitCode::SUCCESS,
+            Err(error) => eprintln!("error: {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_explore_the_knowledge_base() {
+        let mut session = Session::new();
+        let mut output = |line: &str| match session.execute(line) {
+            Ok(Reply::Output(output)) => output,
+            reply => panic!("unexpected reply {:?} to {:?}", reply, line),
+        };
+        output("symbol Rain: Boolean");
+        output("symbol Wet: Boolean");
+        output("rule wet: Rain => Wet");
+        output("rule: Wet => Rain");
+        output("fact Rain");
+        assert_eq!(output("ask Wet"), "yes");
+        assert_eq!(output("run"), "Wet");
+        assert_eq!(output("run"), "no new facts");
+        assert_eq!(output("facts"), "Rain\nWet");
+        assert_eq!(output("rules"), "[0] rule wet: Rain => Wet\n[1] rule: Wet => Rain");
+        assert_eq!(output("debug off"), "debug output off");
+
+        assert_eq!(session.execute("  quit ").unwrap(), Reply::Quit);
+        let error = session.execute("ask Snow").unwrap_err();
+        assert_eq!(error.to_string(), "at line 1, column 1: symbol 'Snow' is not defined");
+        let error = session.execute("retract Rain").unwrap_err();
+        assert_eq!(error.to_string(), "unknown command 'retract', type 'help' for the list of commands");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Action, EngineError, Fact, FactValue, LogicalOperator, Rule, Solution, SymbolicReasoningEngine};
+
+/// A Datalog clause: one disjunct of a rule's premise, as a conjunction of conditions, and the facts it asserts.
+///
+/// A premise holding disjunctions is split into several clauses, one per way of choosing an alternative of every
+/// disjunction, so that each fact pattern of a clause must be matched by a known fact.
+#[derive(Debug, Clone)]
+pub(crate) struct Clause {
+    pub(crate) rule: usize,
+    pub(crate) conditions: Vec<LogicalOperator>,
+    pub(crate) conclusions: Vec<Fact>,
+}
+
+impl Clause {
+    /// Splits a rule into clauses.
+    ///
+    /// # Errors
+    /// Returns `EngineError::NonDatalogRule` if the rule retracts or modifies facts, calls a callback, or computes
+    /// values through `${...}` templates, any of which could keep the evaluation from terminating.
+    pub(crate) fn from_rule(index: usize, rule: &Rule) -> Result<Vec<Clause>, EngineError> {
+        let mut conclusions = Vec::new();
+        for action in &rule.actions {
+            let reason = match action {
+                Action::Assert(fact) if computes_values(fact) => "it computes values through templates",
+                Action::Assert(fact) => {
+                    conclusions.push(fact.clone());
+                    continue;
+                },
+                Action::Retract(_) => "it retracts facts",
+                Action::Modify(_) => "it modifies facts",
+                Action::Call { .. } => "it calls a callback",
+            };
+            return Err(EngineError::NonDatalogRule { reason: reason.to_string() }.in_rule(index));
+        }
+
+        Ok(disjuncts(&rule.premise).into_iter()
+            .map(|conditions| Clause { rule: index, conditions, conclusions: conclusions.clone() })
+            .collect())
+    }
+
+    /// Returns the positions of the fact patterns among the clause's conditions.
+    pub(crate) fn patterns(&self) -> impl Iterator<Item = (usize, &Fact)> {
+        self.conditions.iter().enumerate().filter_map(|(position, condition)| match condition {
+            LogicalOperator::AtomicFact(fact) => Some((position, fact)),
+            _ => None,
+        })
+    }
+
+    /// Derives the conclusions of the clause, each with the match of the conditions it was derived from.
+    ///
+    /// # Arguments
+    /// * `engine` - The engine whose knowledge base the conditions are matched against.
+    /// * `delta` - For semi-naive evaluation, the position of a fact pattern and the facts it is restricted to,
+    ///   so that only the derivations relying on one of these facts are found.
+    ///
+    /// # Errors
+    /// Returns the errors raised while evaluating the conditions or instantiating the conclusions.
+    pub(crate) fn derive(&self, engine: &SymbolicReasoningEngine, delta: Option<(usize, &[Fact])>) -> Result<Vec<(Fact, Solution)>, EngineError> {
+        let mut solutions = vec![Solution::new(engine.variable_bindings.clone())];
+        for (position, condition) in self.conditions.iter().enumerate() {
+            let mut extended = Vec::new();
+            for solution in &solutions {
+                match (condition, delta) {
+                    (LogicalOperator::AtomicFact(fact), Some((restricted, facts))) if restricted == position => {
+                        let pattern = engine.substitute_bindings(&engine.interpolate_fact(fact, false), &solution.bindings);
+                        extended.extend(facts.iter().filter_map(|known_fact| {
+                            let bindings = engine.unify_fact(&pattern, known_fact, &solution.bindings)?;
+                            Some(Solution { bindings, ..solution.supported_by([known_fact.clone()]) })
+                        }));
+                    },
+                    _ => extended.extend(engine.evaluate_logical_expression(condition, solution, None)?),
+                }
+            }
+            solutions = extended;
+        }
+
+        let mut derived = Vec::new();
+        for solution in solutions {
+            for conclusion in &self.conclusions {
+                derived.push((engine.apply_rule_conclusion(conclusion, &solution.bindings)?, solution.clone()));
+            }
+        }
+        Ok(derived)
+    }
+}
+
+/// Expands a premise into its disjunctive normal form, as a list of conjunctions of conditions.
+fn disjuncts(expression: &LogicalOperator) -> Vec<Vec<LogicalOperator>> {
+    match expression {
+        LogicalOperator::And(expressions) => expressions.iter().fold(vec![Vec::new()], |conjunctions, expression| {
+            let alternatives = disjuncts(expression);
+            conjunctions.iter()
+                .flat_map(|conjunction| alternatives.iter().map(move |alternative| [conjunction.clone(), alternative.clone()].concat()))
+                .collect()
+        }),
+        LogicalOperator::Or(expressions) => expressions.iter().flat_map(disjuncts).collect(),
+        condition => vec![vec![condition.clone()]],
+    }
+}
+
+/// Checks whether a conclusion holds `${...}` placeholders, which may compute values not found in any known fact.
+fn computes_values(fact: &Fact) -> bool {
+    fact.arguments.iter().chain(std::iter::once(&fact.value)).any(|term| matches!(term, FactValue::Text(text) if text.contains("${")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::atom;
+
+    #[test]
+    fn disjunctions_split_into_one_clause_per_alternative() {
+        let premise = LogicalOperator::And(vec![
+            LogicalOperator::Or(vec![atom("A"), atom("B")]),
+            atom("C"),
+            LogicalOperator::Or(vec![atom("D"), LogicalOperator::And(vec![atom("E"), atom("F")])]),
+        ]);
+
+        let expected = vec![
+            vec![atom("A"), atom("C"), atom("D")],
+            vec![atom("A"), atom("C"), atom("E"), atom("F")],
+            vec![atom("B"), atom("C"), atom("D")],
+            vec![atom("B"), atom("C"), atom("E"), atom("F")],
+        ];
+        assert_eq!(disjuncts(&premise), expected);
+    }
+}
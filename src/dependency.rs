@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::{ComparableValue, EngineError, LogicalOperator, Rule};
+
+/// The predicate dependency graph of a rule set: which symbols the conclusions of the rules depend on.
+///
+/// Every symbol asserted or modified by a rule depends on the symbols its premise reads. The dependency is positive
+/// for fact patterns, which only ever match more facts as the knowledge base grows, and negative for symbols read
+/// under `LogicalOperator::Not` or through a comparison, whose outcome may change when facts are added. Conclusions of
+/// the same rule are made to depend on each other, so that they end up in the same stratum.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DependencyGraph {
+    symbols: Vec<String>,
+    indices: HashMap<String, usize>,
+    /// For every symbol, the symbols it depends on and whether the dependency is negative.
+    edges: Vec<Vec<(usize, bool)>>,
+}
+
+impl DependencyGraph {
+    pub(crate) fn new(rules: &[Rule]) -> Self {
+        let mut graph = Self::default();
+        for rule in rules {
+            let heads: Vec<usize> = rule.conclusions().map(|fact| graph.node(&fact.symbol.name)).collect();
+            let mut body = Vec::new();
+            dependencies(&rule.premise, false, &mut body);
+            for &head in &heads {
+                for (name, negative) in &body {
+                    let dependency = graph.node(name);
+                    graph.edges[head].push((dependency, *negative));
+                }
+            }
+            for (head, next) in heads.iter().zip(heads.iter().cycle().skip(1)) {
+                graph.edges[*head].push((*next, false));
+            }
+        }
+        graph
+    }
+
+    /// Assigns every symbol to a stratum, so that a symbol's positive dependencies are in the same or an earlier
+    /// stratum, and its negative dependencies in an earlier one.
+    ///
+    /// # Returns
+    /// * `Result<HashMap<String, usize>, EngineError>` - The stratum of every symbol, numbered from 0.
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if a symbol depends negatively on a symbol depending on it in turn.
+    pub(crate) fn strata(&self) -> Result<HashMap<String, usize>, EngineError> {
+        let components = self.components();
+        let mut component_of = vec![0; self.symbols.len()];
+        for (index, component) in components.iter().enumerate() {
+            for &node in component {
+                component_of[node] = index;
+            }
+        }
+
+        // Components are listed after the components they depend on
+        let mut strata = vec![0; components.len()];
+        for (index, component) in components.iter().enumerate() {
+            for &node in component {
+                for &(dependency, negative) in &self.edges[node] {
+                    if component_of[dependency] == index {
+                        if negative {
+                            return Err(EngineError::Unstratifiable { symbol: self.symbols[dependency].clone() });
+                        }
+                        continue;
+                    }
+                    strata[index] = strata[index].max(strata[component_of[dependency]] + usize::from(negative));
+                }
+            }
+        }
+
+        Ok(self.symbols.iter().enumerate().map(|(node, name)| (name.clone(), strata[component_of[node]])).collect())
+    }
+
+    /// Assigns every rule to the stratum of its conclusions, or for rules that assert nothing, to the first stratum
+    /// in which every symbol its premise reads is complete.
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if the rules cannot be stratified.
+    pub(crate) fn rule_strata(&self, rules: &[Rule]) -> Result<Vec<usize>, EngineError> {
+        let strata = self.strata()?;
+        Ok(rules.iter().map(|rule| {
+            let mut body = Vec::new();
+            dependencies(&rule.premise, false, &mut body);
+            let heads = rule.conclusions().map(|fact| strata[&fact.symbol.name]);
+            let requirements = body.iter().map(|(name, negative)| strata.get(name).map_or(0, |stratum| stratum + usize::from(*negative)));
+            heads.chain(requirements).max().unwrap_or(0)
+        }).collect())
+    }
+
+    fn node(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        self.symbols.push(name.to_string());
+        self.edges.push(Vec::new());
+        self.indices.insert(name.to_string(), self.symbols.len() - 1);
+        self.symbols.len() - 1
+    }
+
+    /// Returns the strongly connected components of the graph, each listed after the components it depends on.
+    fn components(&self) -> Vec<Vec<usize>> {
+        let count = self.symbols.len();
+        let mut tarjan = Tarjan { index: vec![None; count], low: vec![0; count], on_stack: vec![false; count], ..Tarjan::default() };
+        for node in 0..self.symbols.len() {
+            if tarjan.index[node].is_none() {
+                tarjan.visit(self, node);
+            }
+        }
+        tarjan.components
+    }
+}
+
+/// The state of Tarjan's strongly connected components algorithm.
+#[derive(Default)]
+struct Tarjan {
+    next: usize,
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan {
+    fn visit(&mut self, graph: &DependencyGraph, node: usize) {
+        self.index[node] = Some(self.next);
+        self.low[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &(dependency, _) in &graph.edges[node] {
+            match self.index[dependency] {
+                None => {
+                    self.visit(graph, dependency);
+                    self.low[node] = self.low[node].min(self.low[dependency]);
+                },
+                Some(index) if self.on_stack[dependency] => self.low[node] = self.low[node].min(index),
+                Some(_) => {},
+            }
+        }
+
+        if Some(self.low[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+/// Collects the symbols a premise reads, and whether each is read negatively.
+fn dependencies(expression: &LogicalOperator, negative: bool, found: &mut Vec<(String, bool)>) {
+    let mut add = |name: &str, negative: bool| {
+        if !found.iter().any(|(known, known_negative)| known == name && *known_negative == negative) {
+            found.push((name.to_string(), negative));
+        }
+    };
+    match expression {
+        LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => {
+            expressions.iter().for_each(|expression| dependencies(expression, negative, found));
+        },
+        LogicalOperator::Not(expression) => dependencies(expression, true, found),
+        LogicalOperator::AtomicFact(fact) => add(&fact.symbol.name, negative),
+        LogicalOperator::GreaterThan(left, right)
+        | LogicalOperator::LessThan(left, right)
+        | LogicalOperator::EqualTo(left, right)
+        | LogicalOperator::NotEqualTo(left, right)
+        | LogicalOperator::GreaterThanOrEqualTo(left, right)
+        | LogicalOperator::LessThanOrEqualTo(left, right) => {
+            for value in [left, right].into_iter().flat_map(|value| value.leaves()) {
+                match value {
+                    ComparableValue::Direct(_) | ComparableValue::Arithmetic(_) => {},
+                    ComparableValue::Symbol(symbol) => add(&symbol.name, true),
+                    ComparableValue::SymbolName(name) => add(name, true),
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::atom;
+    use crate::{Fact, FactValue, Symbol, SymbolType};
+
+    fn rule(premise: LogicalOperator, conclusion: &str) -> Rule {
+        Rule::new(premise, Fact::new(Symbol::new(conclusion, SymbolType::Boolean), FactValue::Boolean(true)))
+    }
+
+    #[test]
+    fn negation_raises_the_stratum_unless_it_is_recursive() {
+        let rules = vec![
+            rule(atom("Edge"), "Path"),
+            rule(LogicalOperator::And(vec![atom("Path"), atom("Edge")]), "Path"),
+            rule(LogicalOperator::And(vec![atom("Node"), LogicalOperator::Not(Box::new(atom("Path")))]), "Unreachable"),
+            rule(atom("Unreachable"), "Report"),
+        ];
+        let strata = DependencyGraph::new(&rules).strata().unwrap();
+        assert_eq!((strata["Edge"], strata["Path"], strata["Unreachable"], strata["Report"]), (0, 0, 1, 1));
+
+        let cyclic = vec![
+            rule(LogicalOperator::Not(Box::new(atom("B"))), "A"),
+            rule(atom("A"), "B"),
+        ];
+        assert!(matches!(DependencyGraph::new(&cyclic).strata(), Err(EngineError::Unstratifiable { .. })));
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+
+use crate::parser::{Program, Statement, KEYWORDS};
+use crate::{Action, Arithmetic, ComparableValue, Fact, FactValue, LogicalOperator, Rule, SymbolType, VariableState};
+
+/// Formats values as literals of the rule language.
+///
+/// Text is quoted with the escapes the rule language understands, floats always carry a fraction or an exponent so
+/// that they re-parse as floats, and pattern variables are prefixed with `?`.
+impl fmt::Display for FactValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactValue::Integer(value) => write!(f, "{}", value),
+            FactValue::Float(value) => write!(f, "{:?}", value),
+            FactValue::Boolean(value) => write!(f, "{}", value),
+            FactValue::Text(text) => write_delimited(f, text, '"'),
+            FactValue::Variable(name) => {
+                f.write_str("?")?;
+                write_name(f, name)
+            },
+        }
+    }
+}
+
+/// Formats a fact as `Symbol(arguments) = value`, leaving out the parentheses of symbols without arguments and the
+/// value of facts that are `true`.
+impl fmt::Display for Fact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_name(f, &self.symbol.name)?;
+        if !self.arguments.is_empty() {
+            f.write_str("(")?;
+            write_list(f, &self.arguments, ", ")?;
+            f.write_str(")")?;
+        }
+        match self.value {
+            FactValue::Boolean(true) => Ok(()),
+            ref value => write!(f, " = {}", value),
+        }
+    }
+}
+
+/// Formats a comparison operand: a value, the name of a symbol, `@name` for a symbol referenced by name, or an
+/// arithmetic expression.
+impl fmt::Display for ComparableValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComparableValue::Direct(value) => write!(f, "{}", value),
+            ComparableValue::Symbol(symbol) => write_name(f, &symbol.name),
+            ComparableValue::SymbolName(name) => {
+                f.write_str("@")?;
+                write_name(f, name)
+            },
+            ComparableValue::Arithmetic(expression) => write!(f, "{}", expression),
+        }
+    }
+}
+
+/// Formats an arithmetic expression in infix notation, such as `(Price - Discount) * Quantity`, with `abs`, `min`,
+/// `max` and called functions written as calls.
+///
+/// Operands are parenthesized where precedence requires it: `*`, `/` and `%` bind tighter than `+` and `-`, and
+/// operators of the same precedence associate to the left, so that the text re-parses to an equal expression.
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (left, operator, right) = match self {
+            Arithmetic::Add(left, right) => (left, "+", right),
+            Arithmetic::Subtract(left, right) => (left, "-", right),
+            Arithmetic::Multiply(left, right) => (left, "*", right),
+            Arithmetic::Divide(left, right) => (left, "/", right),
+            Arithmetic::Modulo(left, right) => (left, "%", right),
+            Arithmetic::Abs(operand) => return write!(f, "abs({})", operand),
+            Arithmetic::Min(operands) | Arithmetic::Max(operands) => {
+                f.write_str(if matches!(self, Arithmetic::Min(_)) { "min(" } else { "max(" })?;
+                write_list(f, operands, ", ")?;
+                return f.write_str(")");
+            },
+            Arithmetic::Call { function, arguments } => {
+                write_name(f, function)?;
+                f.write_str("(")?;
+                write_list(f, arguments, ", ")?;
+                return f.write_str(")");
+            },
+        };
+        // Right operands of the same precedence are parenthesized, since operators associate to the left
+        let binding = precedence(self);
+        let parenthesize = |operand: &ComparableValue, minimum: u8| {
+            matches!(operand, ComparableValue::Arithmetic(expression) if precedence(expression) < minimum)
+        };
+        match parenthesize(left, binding) {
+            true => write!(f, "({}) {} ", left, operator)?,
+            false => write!(f, "{} {} ", left, operator)?,
+        }
+        match parenthesize(right, binding + 1) {
+            true => write!(f, "({})", right),
+            false => write!(f, "{}", right),
+        }
+    }
+}
+
+/// Formats an expression in the infix notation of the rule language, such as `A and not (B or C) and Level >= 3`.
+///
+/// Nested expressions are parenthesized where precedence requires it, and conjunctions and disjunctions of fewer than
+/// two expressions are written `all(...)` and `any(...)`, so that the text re-parses to an equal expression.
+impl fmt::Display for LogicalOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (left, operator, right) = match self {
+            LogicalOperator::And(expressions) if expressions.len() < 2 => return write_call(f, "all", expressions),
+            LogicalOperator::Or(expressions) if expressions.len() < 2 => return write_call(f, "any", expressions),
+            LogicalOperator::And(expressions) => return write_operands(f, expressions, " and ", |expression| {
+                matches!(expression, LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) if expressions.len() >= 2)
+            }),
+            LogicalOperator::Or(expressions) => return write_operands(f, expressions, " or ", |expression| {
+                matches!(expression, LogicalOperator::Or(expressions) if expressions.len() >= 2)
+            }),
+            LogicalOperator::Not(expression) => {
+                f.write_str("not ")?;
+                return write_operands(f, std::slice::from_ref(expression.as_ref()), "", |expression| {
+                    matches!(expression, LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) if expressions.len() >= 2)
+                });
+            },
+            LogicalOperator::AtomicFact(fact) => return write!(f, "{}", fact),
+            LogicalOperator::GreaterThan(left, right) => (left, ">", right),
+            LogicalOperator::LessThan(left, right) => (left, "<", right),
+            LogicalOperator::EqualTo(left, right) => (left, "==", right),
+            LogicalOperator::NotEqualTo(left, right) => (left, "!=", right),
+            LogicalOperator::GreaterThanOrEqualTo(left, right) => (left, ">=", right),
+            LogicalOperator::LessThanOrEqualTo(left, right) => (left, "<=", right),
+        };
+        write!(f, "{} {} {}", left, operator, right)
+    }
+}
+
+/// Formats an action as its fact, prefixed with `retract` or `modify` unless it asserts it, or as
+/// `call callback(arguments)`.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Assert(fact) => write!(f, "{}", fact),
+            Action::Retract(fact) => write!(f, "retract {}", fact),
+            Action::Modify(fact) => write!(f, "modify {}", fact),
+            Action::Call { callback, arguments } => {
+                f.write_str("call ")?;
+                write_name(f, callback)?;
+                f.write_str("(")?;
+                write_list(f, arguments, ", ")?;
+                f.write_str(")")
+            },
+        }
+    }
+}
+
+/// Formats a rule as a statement of the rule language: `rule name salience 5: premise => action, action`, leaving out
+/// the name of unnamed rules and the default salience of 0.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("rule")?;
+        if let Some(name) = &self.name {
+            f.write_str(" ")?;
+            write_name(f, name)?;
+        }
+        if self.salience != 0 {
+            write!(f, " salience {}", self.salience)?;
+        }
+        write!(f, ": {} =>", self.premise)?;
+        if !self.actions.is_empty() {
+            f.write_str(" ")?;
+        }
+        write_list(f, &self.actions, ", ")
+    }
+}
+
+/// Formats a program one statement per line, with symbols, variables, facts and rules written as in the rule
+/// language.
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (statement, _) in &self.statements {
+            match statement {
+                Statement::Symbol(symbol) => {
+                    f.write_str("symbol ")?;
+                    write_name(f, &symbol.name)?;
+                    if !symbol.signature.is_empty() {
+                        f.write_str("(")?;
+                        for (index, argument_type) in symbol.signature.iter().enumerate() {
+                            if index > 0 {
+                                f.write_str(", ")?;
+                            }
+                            write_symbol_type(f, argument_type)?;
+                        }
+                        f.write_str(")")?;
+                    }
+                    f.write_str(": ")?;
+                    write_symbol_type(f, &symbol.symbol_type)?;
+                },
+                Statement::Variable(variable) => {
+                    f.write_str("variable ")?;
+                    write_name(f, &variable.name)?;
+                    write!(f, " = {}", variable.value)?;
+                    if variable.state == VariableState::Unstable {
+                        f.write_str(" unstable")?;
+                    }
+                },
+                Statement::Fact(fact) => write!(f, "fact {}", fact)?,
+                Statement::Rule(rule) => write!(f, "{}", rule)?,
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a name as an identifier, or quoted with backticks if it is a keyword or not an identifier.
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    let mut chars = name.chars();
+    let identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_');
+    match identifier && !KEYWORDS.contains(&name) {
+        true => f.write_str(name),
+        false => write_delimited(f, name, '`'),
+    }
+}
+
+/// Writes text between delimiters, escaping the characters the rule language requires.
+fn write_delimited(f: &mut fmt::Formatter<'_>, text: &str, delimiter: char) -> fmt::Result {
+    write!(f, "{}", delimiter)?;
+    for c in text.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            c if c == delimiter => write!(f, "\\{}", c)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "{}", delimiter)
+}
+
+/// Writes a symbol type as declared in the rule language, quoting the values of enumerations.
+fn write_symbol_type(f: &mut fmt::Formatter<'_>, symbol_type: &SymbolType) -> fmt::Result {
+    match symbol_type {
+        SymbolType::Enum(alternatives) => {
+            f.write_str("Enum(")?;
+            for (index, alternative) in alternatives.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write_delimited(f, alternative, '"')?;
+            }
+            f.write_str(")")
+        },
+        SymbolType::FloatRange { min, max } => write!(f, "Float[{:?}..={:?}]", min, max),
+        symbol_type => write!(f, "{}", symbol_type),
+    }
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T], separator: &str) -> fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+/// Returns how tightly an arithmetic operator binds its operands, calls binding tightest.
+fn precedence(expression: &Arithmetic) -> u8 {
+    match expression {
+        Arithmetic::Add(..) | Arithmetic::Subtract(..) => 1,
+        Arithmetic::Multiply(..) | Arithmetic::Divide(..) | Arithmetic::Modulo(..) => 2,
+        _ => 3,
+    }
+}
+
+fn write_call(f: &mut fmt::Formatter<'_>, name: &str, expressions: &[LogicalOperator]) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    write_list(f, expressions, ", ")?;
+    f.write_str(")")
+}
+
+/// Writes the operands of an operator, parenthesizing those that would otherwise bind to their neighbours.
+fn write_operands(f: &mut fmt::Formatter<'_>, expressions: &[LogicalOperator], separator: &str, parenthesize: impl Fn(&LogicalOperator) -> bool) -> fmt::Result {
+    for (index, expression) in expressions.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        match parenthesize(expression) {
+            true => write!(f, "({})", expression)?,
+            false => write!(f, "{}", expression)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::atom;
+
+    #[test]
+    fn nested_expressions_are_parenthesized_by_precedence() {
+        let expression = LogicalOperator::And(vec![
+            LogicalOperator::Not(Box::new(LogicalOperator::Or(vec![atom("A"), atom("B")]))),
+            LogicalOperator::Or(vec![atom("C"), LogicalOperator::And(vec![atom("D"), atom("E")])]),
+            LogicalOperator::And(vec![atom("F"), atom("G")]),
+            LogicalOperator::Or(vec![atom("H")]),
+        ]);
+        assert_eq!(expression.to_string(), "not (A or B) and (C or D and E) and (F and G) and any(H)");
+    }
+
+    #[test]
+    fn values_are_written_as_literals() {
+        let values = [FactValue::Float(20.0), FactValue::Float(-1e-7), FactValue::Text("say \"hi\"\n".into()), FactValue::Variable("x".into())];
+        let written: Vec<String> = values.iter().map(ToString::to_string).collect();
+        assert_eq!(written, vec!["20.0", "-1e-7", "\"say \\\"hi\\\"\\n\"", "?x"]);
+    }
+}
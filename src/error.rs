@@ -0,0 +1,166 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{FactValue, SymbolType};
+
+/// Represents the errors that can occur while defining or evaluating the engine's knowledge base.
+///
+/// Every fallible operation of the `SymbolicReasoningEngine` reports failures through this type instead of panicking,
+/// so that a single malformed rule or fact cannot take down the host application. Each variant carries the offending
+/// symbol, value or rule so that the failure can be diagnosed without re-running the engine in debug mode.
+///
+/// Variants:
+/// - `DuplicateSymbol`: A symbol with the same name has already been defined.
+/// - `UnknownSymbol`: A fact, rule or comparison references a symbol that has not been defined.
+/// - `UnknownSymbolType`: A symbol type name could not be parsed.
+/// - `TypeMismatch`: A value does not match the type declared for the symbol it is associated with.
+/// - `ArityMismatch`: A fact or comparison supplies a different number of arguments than the predicate's signature.
+/// - `ArgumentTypeMismatch`: An argument does not match the type declared at its position in the predicate's signature.
+/// - `NonNumericComparison`: A comparison operand could not be resolved to a number.
+/// - `IncompatibleComparison`: A comparison operator was applied to operands whose types cannot be compared with it.
+/// - `NonNumericOperand`: An arithmetic operator or function was applied to an operand of a non-numeric type.
+/// - `MissingOperand`: An arithmetic function that takes at least one operand, such as `min` or `max`, was given none.
+/// - `MissingFact`: A comparison references a symbol for which no fact has been asserted or inferred.
+/// - `UnknownCallback`: A rule action calls a callback that has not been registered.
+/// - `UnknownFunction`: An arithmetic expression calls a function that has not been registered.
+/// - `DivisionByZero`: An arithmetic expression divided by zero or took a remainder modulo zero.
+/// - `InvalidTemplate`: A `${...}` placeholder in a rule conclusion is malformed or its integer result overflows.
+/// - `UnboundVariable`: A pattern variable is used where it has no value, such as in an asserted fact, in a rule
+///   conclusion it is not bound for, or in a comparison evaluated before the fact binding it.
+/// - `UnknownRule`: No rule is defined at the given index.
+/// - `UnknownActivation`: No activation is on the agenda at the given index.
+/// - `Unstratifiable`: A rule depends through negation or a comparison on `symbol`, whose facts depend on the rule's
+///   conclusions in turn, so the rules cannot be split into strata.
+/// - `NonDatalogRule`: A rule cannot be evaluated as Datalog for the given reason.
+/// - `NoStableModel`: The rules have no stable model over the knowledge base, as when a rule concludes a fact unless it
+///   holds.
+/// - `Syntax`: Rule language or JSON source text does not parse, as described by `message`, at the given line and
+///   column.
+/// - `Located`: Wraps an error raised by the rule language statement or name at the given line and column.
+/// - `InvalidJson`: The JSON value at `path`, such as `rules[3].premise.And[1]`, does not have the structure its
+///   encoding requires, as described by `message`.
+/// - `JsonPath`: Wraps an error raised by the JSON value at `path`.
+/// - `UnsupportedJsonLogic`: A JsonLogic operation or operand, or an expression converted to JsonLogic, has no
+///   equivalent on the other side.
+/// - `Io`: A knowledge base file at `path` could not be read or written, as described by `message`.
+/// - `UnsupportedFormat`: A knowledge base file was saved in a format version this engine cannot load.
+/// - `RuleEvaluation`: Wraps an error raised while evaluating the rule at index `rule` of the engine's rule set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    DuplicateSymbol { symbol: String },
+    UnknownSymbol { symbol: String },
+    UnknownSymbolType { name: String },
+    TypeMismatch { symbol: String, expected: SymbolType, found: FactValue },
+    ArityMismatch { symbol: String, expected: usize, found: usize },
+    ArgumentTypeMismatch { symbol: String, position: usize, expected: SymbolType, found: FactValue },
+    NonNumericComparison { symbol: Option<String>, value: FactValue },
+    IncompatibleComparison { operator: String, left: SymbolType, right: SymbolType },
+    NonNumericOperand { operator: String, operand: SymbolType },
+    MissingOperand { operator: String },
+    MissingFact { symbol: String },
+    UnknownCallback { name: String },
+    UnknownFunction { name: String },
+    DivisionByZero { expression: String },
+    InvalidTemplate { template: String, message: String },
+    UnboundVariable { name: String },
+    UnknownRule { rule: usize },
+    UnknownActivation { index: usize },
+    Unstratifiable { symbol: String },
+    NonDatalogRule { reason: String },
+    NoStableModel,
+    Syntax { line: usize, column: usize, message: String },
+    Located { line: usize, column: usize, source: Box<EngineError> },
+    InvalidJson { path: String, message: String },
+    JsonPath { path: String, source: Box<EngineError> },
+    UnsupportedJsonLogic { operation: String },
+    Io { path: String, message: String },
+    UnsupportedFormat { version: u32 },
+    RuleEvaluation { rule: usize, source: Box<EngineError> },
+}
+
+impl EngineError {
+    /// Wraps the error with the index of the rule whose evaluation raised it.
+    pub(crate) fn in_rule(self, rule: usize) -> Self {
+        EngineError::RuleEvaluation { rule, source: Box::new(self) }
+    }
+
+    /// Wraps the error with the position of the rule language source text that raised it.
+    pub(crate) fn at(self, line: usize, column: usize) -> Self {
+        EngineError::Located { line, column, source: Box::new(self) }
+    }
+
+    /// Wraps the error with the path of the JSON value that raised it, unless the path is the root of the document.
+    pub(crate) fn at_path(self, path: &str) -> Self {
+        match path.is_empty() {
+            true => self,
+            false => EngineError::JsonPath { path: path.to_string(), source: Box::new(self) },
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::DuplicateSymbol { symbol } => write!(f, "symbol '{}' is already defined", symbol),
+            EngineError::UnknownSymbol { symbol } => write!(f, "symbol '{}' is not defined", symbol),
+            EngineError::UnknownSymbolType { name } => write!(f, "'{}' is not a known symbol type", name),
+            EngineError::TypeMismatch { symbol, expected, found } => {
+                write!(f, "symbol '{}' expects a value of type {}, found {:?}", symbol, expected, found)
+            },
+            EngineError::ArityMismatch { symbol, expected, found } => {
+                write!(f, "symbol '{}' takes {} argument(s), found {}", symbol, expected, found)
+            },
+            EngineError::ArgumentTypeMismatch { symbol, position, expected, found } => {
+                write!(f, "argument {} of symbol '{}' expects a value of type {}, found {:?}", position, symbol, expected, found)
+            },
+            EngineError::NonNumericComparison { symbol: Some(symbol), value } => {
+                write!(f, "value {:?} of symbol '{}' cannot be compared numerically", value, symbol)
+            },
+            EngineError::NonNumericComparison { symbol: None, value } => {
+                write!(f, "value {:?} cannot be compared numerically", value)
+            },
+            EngineError::IncompatibleComparison { operator, left, right } => {
+                write!(f, "operator '{}' cannot compare {} with {}", operator, left, right)
+            },
+            EngineError::NonNumericOperand { operator, operand } => {
+                write!(f, "arithmetic operator '{}' cannot take an operand of type {}", operator, operand)
+            },
+            EngineError::MissingOperand { operator } => write!(f, "arithmetic function '{}' takes at least one operand", operator),
+            EngineError::MissingFact { symbol } => write!(f, "no fact is known for symbol '{}'", symbol),
+            EngineError::UnknownCallback { name } => write!(f, "callback '{}' is not registered", name),
+            EngineError::UnknownFunction { name } => write!(f, "function '{}' is not registered", name),
+            EngineError::DivisionByZero { expression } => write!(f, "division by zero in '{}'", expression),
+            EngineError::InvalidTemplate { template, message } => write!(f, "invalid template '{}': {}", template, message),
+            EngineError::UnboundVariable { name } => write!(f, "pattern variable '?{}' is not bound", name),
+            EngineError::UnknownRule { rule } => write!(f, "no rule is defined at index {}", rule),
+            EngineError::UnknownActivation { index } => write!(f, "no activation is on the agenda at index {}", index),
+            EngineError::Unstratifiable { symbol } => {
+                write!(f, "symbol '{}' is read through negation or a comparison by a rule it depends on, so the rules cannot be stratified", symbol)
+            },
+            EngineError::NonDatalogRule { reason } => write!(f, "rule is not Datalog: {}", reason),
+            EngineError::NoStableModel => write!(f, "the rules have no stable model"),
+            EngineError::Syntax { line, column, message } => write!(f, "syntax error at line {}, column {}: {}", line, column, message),
+            EngineError::Located { line, column, source } => write!(f, "at line {}, column {}: {}", line, column, source),
+            EngineError::InvalidJson { path, message } if path.is_empty() => write!(f, "invalid JSON: {}", message),
+            EngineError::InvalidJson { path, message } => write!(f, "invalid JSON at {}: {}", path, message),
+            EngineError::JsonPath { path, source } => write!(f, "at {}: {}", path, source),
+            EngineError::UnsupportedJsonLogic { operation } => write!(f, "JsonLogic operation '{}' is not supported", operation),
+            EngineError::Io { path, message } => write!(f, "cannot access '{}': {}", path, message),
+            EngineError::UnsupportedFormat { version } => {
+                write!(f, "knowledge base format {} is not supported, expected format {}", version, crate::FORMAT_VERSION)
+            },
+            EngineError::RuleEvaluation { rule, source } => write!(f, "error evaluating rule {}: {}", rule, source),
+        }
+    }
+}
+
+impl Error for EngineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EngineError::Located { source, .. } | EngineError::JsonPath { source, .. } | EngineError::RuleEvaluation { source, .. } => {
+                Some(source.as_ref())
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Fact, FactValue, LogicalOperator};
+
+/// Explains why a goal or premise could not be satisfied.
+///
+/// A failure report mirrors the structure of a `Proof`, but follows the conditions that did not hold: for a goal, every
+/// rule that was tried and why its premise failed; for a conjunction, the first condition that failed given the
+/// conditions before it; for a disjunction, why each alternative failed. The leaves are facts that are neither known
+/// nor derivable, comparisons with the values that did not satisfy their operator, negated conditions that hold, and
+/// goals where the search was cut to avoid a cycle.
+///
+/// When a conjunction holds in several ways up to the condition that fails, for example because a pattern variable
+/// matches several facts, the failure is explained for the first of them.
+///
+/// Variants:
+/// - `MissingFact`: No known fact matches the fact, and no rule concludes it. Pattern variables bound at that point
+///   are replaced by their values.
+/// - `Goal`: The `goal` is not a known fact, and every rule concluding it failed as described by `attempts`.
+/// - `Comparison`: An ordering comparison, such as `>` or `<=`, does not hold between the resolved numeric values
+///   `left` and `right`.
+/// - `Equality`: An `==` or `!=` comparison does not hold between the resolved values `left` and `right`.
+/// - `Negation`: The condition negated by `LogicalOperator::Not` holds.
+/// - `Alternatives`: Every alternative of a disjunction failed, for the reasons listed in order.
+/// - `Cycle`: The goal was already being searched for higher up in the search, which was cut to avoid looping forever.
+///
+/// # Examples
+/// ```
+/// use symbolic_reasoning_engine::{ComparableValue, Failure, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+///
+/// let mut engine = SymbolicReasoningEngine::new();
+/// let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+/// let picnic = engine.define_symbol("PicnicDay", SymbolType::Boolean)?;
+///
+/// engine.assert_fact(temperature.clone(), FactValue::Integer(14))?;
+/// engine.define_rule(
+///     LogicalOperator::GreaterThan(
+///         Box::new(ComparableValue::Symbol(temperature)),
+///         Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+///     ),
+///     Fact::new(picnic.clone(), FactValue::Boolean(true)),
+/// )?;
+///
+/// let Some(Failure::Goal { attempts, .. }) = engine.explain_goal(&Fact::new(picnic, FactValue::Boolean(true)))? else {
+///     panic!("the goal should fail after trying the rule");
+/// };
+/// assert_eq!(attempts[0].rule(), 0);
+/// assert_eq!(attempts[0].failure(), &Failure::Comparison { operator: ">".into(), left: 14.0, right: 20.0 });
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    MissingFact(Fact),
+    Goal { goal: Fact, attempts: Vec<RuleAttempt> },
+    Comparison { operator: String, left: f64, right: f64 },
+    Equality { operator: String, left: FactValue, right: FactValue },
+    Negation(LogicalOperator),
+    Alternatives(Vec<Failure>),
+    Cycle(Fact),
+}
+
+impl Failure {
+    /// Returns the indices of every rule tried anywhere in the report, in the order they were tried.
+    pub fn attempted_rules(&self) -> Vec<usize> {
+        match self {
+            Failure::Goal { attempts, .. } => attempts.iter()
+                .flat_map(|attempt| std::iter::once(attempt.rule).chain(attempt.failure.attempted_rules()))
+                .collect(),
+            Failure::Alternatives(failures) => failures.iter().flat_map(Failure::attempted_rules).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the facts that were neither known nor derivable anywhere in the report, in the order they were found.
+    pub fn missing_facts(&self) -> Vec<&Fact> {
+        match self {
+            Failure::MissingFact(fact) => vec![fact],
+            Failure::Goal { attempts, .. } => attempts.iter().flat_map(|attempt| attempt.failure.missing_facts()).collect(),
+            Failure::Alternatives(failures) => failures.iter().flat_map(Failure::missing_facts).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Records a rule tried while searching for a goal, and why its premise failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleAttempt {
+    rule: usize,
+    failure: Failure,
+}
+
+impl RuleAttempt {
+    pub(crate) fn new(rule: usize, failure: Failure) -> Self {
+        Self { rule, failure }
+    }
+
+    /// Returns the index of the rule that was tried.
+    pub fn rule(&self) -> usize {
+        self.rule
+    }
+
+    /// Returns why the rule's premise failed.
+    pub fn failure(&self) -> &Failure {
+        &self.failure
+    }
+}
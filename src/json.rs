@@ -0,0 +1,586 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::arithmetic::Operation;
+use crate::parser::{coerce, Comparison, Program, Statement};
+use crate::{Action, Arithmetic, ComparableValue, EngineError, Fact, FactValue, LogicalOperator, Rule, Symbol, SymbolType, Variable, VariableState};
+
+/// The comparison operators, by the name of their `LogicalOperator` variant.
+const COMPARISONS: [(&str, Comparison); 6] = [
+    ("GreaterThan", LogicalOperator::GreaterThan),
+    ("LessThan", LogicalOperator::LessThan),
+    ("EqualTo", LogicalOperator::EqualTo),
+    ("NotEqualTo", LogicalOperator::NotEqualTo),
+    ("GreaterThanOrEqualTo", LogicalOperator::GreaterThanOrEqualTo),
+    ("LessThanOrEqualTo", LogicalOperator::LessThanOrEqualTo),
+];
+
+/// The binary arithmetic operators, by the name of their `Arithmetic` variant.
+const OPERATIONS: [(&str, Operation); 5] = [
+    ("Add", Arithmetic::Add),
+    ("Subtract", Arithmetic::Subtract),
+    ("Multiply", Arithmetic::Multiply),
+    ("Divide", Arithmetic::Divide),
+    ("Modulo", Arithmetic::Modulo),
+];
+
+impl FactValue {
+    /// Encodes the value as JSON, as an object whose single key names the variant, such as `{"Integer": 3}`.
+    ///
+    /// Floats that JSON numbers cannot represent are encoded as the strings `"NaN"`, `"inf"` and `"-inf"`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            FactValue::Integer(value) => json!({ "Integer": value }),
+            FactValue::Float(value) => json!({ "Float": float(*value) }),
+            FactValue::Boolean(value) => json!({ "Boolean": value }),
+            FactValue::Text(text) => json!({ "Text": text }),
+            FactValue::Variable(name) => json!({ "Variable": name }),
+        }
+    }
+
+    /// Decodes a value from its JSON encoding.
+    ///
+    /// Besides the encoding produced by `to_json`, plain JSON scalars are accepted: integral numbers within the range
+    /// of `i32` decode as integers, other numbers as floats, booleans as booleans and strings as text.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde_json::json;
+    /// use symbolic_reasoning_engine::FactValue;
+    ///
+    /// assert_eq!(FactValue::from_json(&json!({ "Float": "inf" }))?, FactValue::Float(f64::INFINITY));
+    /// assert_eq!(FactValue::from_json(&json!(3))?, FactValue::Integer(3));
+    /// assert_eq!(FactValue::from_json(&FactValue::Text("Sunny".into()).to_json())?, FactValue::Text("Sunny".into()));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::InvalidJson` if the JSON does not encode a value.
+    pub fn from_json(value: &Value) -> Result<Self, EngineError> {
+        fact_value(value, "")
+    }
+}
+
+impl Symbol {
+    /// Encodes the symbol as a JSON object with its `name`, its `signature` unless it is empty, and its
+    /// `symbol_type`.
+    ///
+    /// Symbol types are encoded as the name of their variant, such as `"Integer"`, or as an object for those carrying
+    /// data: `{"Enum": ["Sunny", "Rainy"]}`, `{"IntegerRange": {"min": 0, "max": 10}}` and `{"FloatRange": ...}`.
+    pub fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("name".into(), json!(self.name));
+        if !self.signature.is_empty() {
+            object.insert("signature".into(), self.signature.iter().map(symbol_type).collect());
+        }
+        object.insert("symbol_type".into(), symbol_type(&self.symbol_type));
+        Value::Object(object)
+    }
+
+    /// Decodes a symbol from its JSON encoding.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde_json::json;
+    /// use symbolic_reasoning_engine::{Symbol, SymbolType};
+    ///
+    /// let symbol = Symbol::from_json(&json!({ "name": "Friend", "signature": ["Text", "Text"], "symbol_type": "Boolean" }))?;
+    /// assert_eq!(symbol, Symbol::predicate("Friend", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::InvalidJson` if the JSON does not encode a symbol.
+    pub fn from_json(value: &Value) -> Result<Self, EngineError> {
+        symbol(value, "")
+    }
+}
+
+impl Fact {
+    /// Encodes the fact as a JSON object with the name of its `symbol`, its `arguments` unless it has none, and its
+    /// `value`, such as `{"symbol": "Temperature", "arguments": [{"Text": "kitchen"}], "value": {"Float": 21.5}}`.
+    pub fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("symbol".into(), json!(self.symbol.name));
+        if !self.arguments.is_empty() {
+            object.insert("arguments".into(), self.arguments.iter().map(FactValue::to_json).collect());
+        }
+        object.insert("value".into(), self.value.to_json());
+        Value::Object(object)
+    }
+}
+
+impl ComparableValue {
+    /// Encodes the operand as a JSON object whose single key names the variant: `{"Direct": value}`,
+    /// `{"Symbol": "name"}`, `{"SymbolName": "name"}` or `{"Arithmetic": expression}`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            ComparableValue::Direct(value) => json!({ "Direct": value.to_json() }),
+            ComparableValue::Symbol(symbol) => json!({ "Symbol": symbol.name }),
+            ComparableValue::SymbolName(name) => json!({ "SymbolName": name }),
+            ComparableValue::Arithmetic(expression) => json!({ "Arithmetic": expression.to_json() }),
+        }
+    }
+}
+
+impl Arithmetic {
+    /// Encodes the expression as a JSON object whose single key names the variant.
+    ///
+    /// Binary operators hold an array of their two operands, `Abs` its operand, `Min` and `Max` arrays of operands,
+    /// and `Call` an object with the `function` name and its `arguments`, such as
+    /// `{"Subtract": [{"Symbol": "Temperature"}, {"Symbol": "Dewpoint"}]}`.
+    pub fn to_json(&self) -> Value {
+        let operands = |operands: &[ComparableValue]| operands.iter().map(ComparableValue::to_json).collect::<Value>();
+        match self {
+            Arithmetic::Add(left, right) => comparison("Add", left, right),
+            Arithmetic::Subtract(left, right) => comparison("Subtract", left, right),
+            Arithmetic::Multiply(left, right) => comparison("Multiply", left, right),
+            Arithmetic::Divide(left, right) => comparison("Divide", left, right),
+            Arithmetic::Modulo(left, right) => comparison("Modulo", left, right),
+            Arithmetic::Abs(operand) => json!({ "Abs": operand.to_json() }),
+            Arithmetic::Min(values) => json!({ "Min": operands(values) }),
+            Arithmetic::Max(values) => json!({ "Max": operands(values) }),
+            Arithmetic::Call { function, arguments } => json!({ "Call": { "function": function, "arguments": operands(arguments) } }),
+        }
+    }
+}
+
+impl LogicalOperator {
+    /// Encodes the expression as a JSON object whose single key names the variant.
+    ///
+    /// `And` and `Or` hold arrays of expressions, `Not` an expression, `AtomicFact` a fact, and comparisons an array of
+    /// their two operands, such as `{"GreaterThan": [{"Symbol": "Level"}, {"Direct": {"Integer": 3}}]}`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            LogicalOperator::And(expressions) => json!({ "And": expressions.iter().map(LogicalOperator::to_json).collect::<Value>() }),
+            LogicalOperator::Or(expressions) => json!({ "Or": expressions.iter().map(LogicalOperator::to_json).collect::<Value>() }),
+            LogicalOperator::Not(expression) => json!({ "Not": expression.to_json() }),
+            LogicalOperator::AtomicFact(fact) => json!({ "AtomicFact": fact.to_json() }),
+            LogicalOperator::GreaterThan(left, right) => comparison("GreaterThan", left, right),
+            LogicalOperator::LessThan(left, right) => comparison("LessThan", left, right),
+            LogicalOperator::EqualTo(left, right) => comparison("EqualTo", left, right),
+            LogicalOperator::NotEqualTo(left, right) => comparison("NotEqualTo", left, right),
+            LogicalOperator::GreaterThanOrEqualTo(left, right) => comparison("GreaterThanOrEqualTo", left, right),
+            LogicalOperator::LessThanOrEqualTo(left, right) => comparison("LessThanOrEqualTo", left, right),
+        }
+    }
+}
+
+impl Rule {
+    /// Encodes the rule as a JSON object with its `name` unless it has none, its `salience` unless it is 0, its
+    /// `premise` and its `actions`.
+    ///
+    /// Actions are encoded as objects whose single key names the variant: `{"Assert": fact}`, `{"Retract": fact}`,
+    /// `{"Modify": fact}` or `{"Call": {"callback": "name", "arguments": [...]}}`.
+    pub fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        if let Some(name) = &self.name {
+            object.insert("name".into(), json!(name));
+        }
+        if self.salience != 0 {
+            object.insert("salience".into(), json!(self.salience));
+        }
+        object.insert("premise".into(), self.premise.to_json());
+        object.insert("actions".into(), self.actions.iter().map(action).collect());
+        Value::Object(object)
+    }
+}
+
+/// Encodes a program as a JSON knowledge base, with its `symbols`, `variables`, `facts` and `rules` in order.
+pub(crate) fn knowledge_base(program: &Program) -> Value {
+    json!({
+        "symbols": program.symbols().map(Symbol::to_json).collect::<Value>(),
+        "variables": program.variables().map(variable).collect::<Value>(),
+        "facts": program.facts().map(Fact::to_json).collect::<Value>(),
+        "rules": program.rules().map(Rule::to_json).collect::<Value>(),
+    })
+}
+
+/// Parses JSON text.
+///
+/// # Errors
+/// Returns `EngineError::Syntax` with the line and column where the text is not valid JSON.
+pub(crate) fn parse(source: &str) -> Result<Value, EngineError> {
+    serde_json::from_str(source).map_err(|error| {
+        let message = error.to_string();
+        let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+        EngineError::Syntax { line: error.line(), column: error.column(), message: message.to_string() }
+    })
+}
+
+fn float(value: f64) -> Value {
+    match serde_json::Number::from_f64(value) {
+        Some(number) => Value::Number(number),
+        None if value.is_nan() => json!("NaN"),
+        None if value > 0.0 => json!("inf"),
+        None => json!("-inf"),
+    }
+}
+
+fn symbol_type(symbol_type: &SymbolType) -> Value {
+    match symbol_type {
+        SymbolType::Enum(alternatives) => json!({ "Enum": alternatives }),
+        SymbolType::IntegerRange { min, max } => json!({ "IntegerRange": { "min": min, "max": max } }),
+        SymbolType::FloatRange { min, max } => json!({ "FloatRange": { "min": float(*min), "max": float(*max) } }),
+        symbol_type => json!(symbol_type.to_string()),
+    }
+}
+
+/// Encodes an operator of two operands, a comparison or a binary arithmetic operator, as `{name: [left, right]}`.
+fn comparison(name: &str, left: &ComparableValue, right: &ComparableValue) -> Value {
+    json!({ name: [left.to_json(), right.to_json()] })
+}
+
+fn action(action: &Action) -> Value {
+    match action {
+        Action::Assert(fact) => json!({ "Assert": fact.to_json() }),
+        Action::Retract(fact) => json!({ "Retract": fact.to_json() }),
+        Action::Modify(fact) => json!({ "Modify": fact.to_json() }),
+        Action::Call { callback, arguments } => {
+            json!({ "Call": { "callback": callback, "arguments": arguments.iter().map(FactValue::to_json).collect::<Value>() } })
+        },
+    }
+}
+
+fn variable(variable: &Variable) -> Value {
+    let mut object = Map::new();
+    object.insert("name".into(), json!(variable.name));
+    object.insert("value".into(), variable.value.to_json());
+    if variable.state == VariableState::Unstable {
+        object.insert("state".into(), json!("Unstable"));
+    }
+    Value::Object(object)
+}
+
+/// Decodes JSON into the engine's types, resolving symbol names against the symbols it knows.
+///
+/// Errors name the path of the offending JSON value from the root of the decoded document, such as
+/// `rules[3].premise.And[1]`.
+pub(crate) struct Decoder {
+    symbols: HashMap<String, Symbol>,
+}
+
+impl Decoder {
+    pub(crate) fn new(symbols: &HashMap<String, Symbol>) -> Self {
+        Self { symbols: symbols.clone() }
+    }
+
+    /// Decodes a knowledge base into statements, each with its path. Symbols are decoded first, so that the facts
+    /// and rules of the knowledge base may use them.
+    pub(crate) fn knowledge_base(&mut self, value: &Value) -> Result<Vec<(Statement, String)>, EngineError> {
+        let fields = fields(value, "", &[], &["symbols", "variables", "facts", "rules"])?;
+        let mut statements = Vec::new();
+        for (name, element) in elements(fields, "symbols")? {
+            let symbol = symbol(element, &name)?;
+            self.symbols.insert(symbol.name.clone(), symbol.clone());
+            statements.push((Statement::Symbol(symbol), name));
+        }
+        for (name, element) in elements(fields, "variables")? {
+            statements.push((Statement::Variable(decode_variable(element, &name)?), name));
+        }
+        for (name, element) in elements(fields, "facts")? {
+            statements.push((Statement::Fact(self.fact(element, &name)?), name));
+        }
+        for (name, element) in elements(fields, "rules")? {
+            statements.push((Statement::Rule(self.rule(element, &name)?), name));
+        }
+        Ok(statements)
+    }
+
+    pub(crate) fn fact(&self, value: &Value, path: &str) -> Result<Fact, EngineError> {
+        let fields = fields(value, path, &["symbol"], &["arguments", "value"])?;
+        let name = string(&fields["symbol"], &key(path, "symbol"))?;
+        let symbol = self.symbols.get(name).cloned()
+            .ok_or_else(|| EngineError::UnknownSymbol { symbol: name.to_string() }.at_path(&key(path, "symbol")))?;
+        let arguments = match fields.get("arguments") {
+            Some(arguments) => array(arguments, &key(path, "arguments"))?.iter().enumerate()
+                .map(|(position, argument)| fact_value(argument, &index(&key(path, "arguments"), position)))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        if arguments.len() != symbol.arity() {
+            let error = EngineError::ArityMismatch { symbol: symbol.name.clone(), expected: symbol.arity(), found: arguments.len() };
+            return Err(error.at_path(path));
+        }
+        let value = match fields.get("value") {
+            Some(value) => coerce(fact_value(value, &key(path, "value"))?, &symbol.symbol_type),
+            None => FactValue::Boolean(true),
+        };
+        let arguments = arguments.into_iter().zip(&symbol.signature).map(|(argument, expected)| coerce(argument, expected)).collect();
+        Ok(Fact::with_arguments(symbol, arguments, value))
+    }
+
+    pub(crate) fn expression(&self, value: &Value, path: &str) -> Result<LogicalOperator, EngineError> {
+        let (variant, operand) = variant(value, path)?;
+        let operand_path = key(path, variant);
+        match variant {
+            "And" | "Or" => {
+                let expressions = array(operand, &operand_path)?.iter().enumerate()
+                    .map(|(position, expression)| self.expression(expression, &index(&operand_path, position)))
+                    .collect::<Result<_, _>>()?;
+                Ok(if variant == "And" { LogicalOperator::And(expressions) } else { LogicalOperator::Or(expressions) })
+            },
+            "Not" => Ok(LogicalOperator::Not(Box::new(self.expression(operand, &operand_path)?))),
+            "AtomicFact" => Ok(LogicalOperator::AtomicFact(self.fact(operand, &operand_path)?)),
+            variant => {
+                let Some((_, comparison)) = COMPARISONS.iter().find(|(name, _)| *name == variant) else {
+                    return Err(invalid(path, format!("unknown expression '{}'", variant)));
+                };
+                let [left, right] = array(operand, &operand_path)?.as_slice() else {
+                    return Err(invalid(&operand_path, "expected an array of two operands"));
+                };
+                let left = self.comparable(left, &index(&operand_path, 0))?;
+                let right = self.comparable(right, &index(&operand_path, 1))?;
+                Ok(comparison(Box::new(left), Box::new(right)))
+            },
+        }
+    }
+
+    pub(crate) fn rule(&self, value: &Value, path: &str) -> Result<Rule, EngineError> {
+        let fields = fields(value, path, &["premise", "actions"], &["name", "salience"])?;
+        let premise = self.expression(&fields["premise"], &key(path, "premise"))?;
+        let actions = array(&fields["actions"], &key(path, "actions"))?.iter().enumerate()
+            .map(|(position, action)| self.action(action, &index(&key(path, "actions"), position)))
+            .collect::<Result<_, _>>()?;
+        let mut rule = Rule::with_actions(premise, actions);
+        if let Some(name) = fields.get("name") {
+            rule.name = Some(string(name, &key(path, "name"))?.to_string());
+        }
+        if let Some(salience) = fields.get("salience") {
+            rule.salience = integer(salience, &key(path, "salience"))?;
+        }
+        Ok(rule)
+    }
+
+    fn comparable(&self, value: &Value, path: &str) -> Result<ComparableValue, EngineError> {
+        let (variant, operand) = variant(value, path)?;
+        let operand_path = key(path, variant);
+        match variant {
+            "Direct" => Ok(ComparableValue::Direct(fact_value(operand, &operand_path)?)),
+            "Symbol" => {
+                let name = string(operand, &operand_path)?;
+                let symbol = self.symbols.get(name).cloned()
+                    .ok_or_else(|| EngineError::UnknownSymbol { symbol: name.to_string() }.at_path(&operand_path))?;
+                Ok(ComparableValue::Symbol(symbol))
+            },
+            "SymbolName" => Ok(ComparableValue::SymbolName(string(operand, &operand_path)?.to_string())),
+            "Arithmetic" => Ok(ComparableValue::Arithmetic(Box::new(self.arithmetic(operand, &operand_path)?))),
+            variant => Err(invalid(path, format!("unknown operand '{}'", variant))),
+        }
+    }
+
+    fn arithmetic(&self, value: &Value, path: &str) -> Result<Arithmetic, EngineError> {
+        let (variant, operand) = variant(value, path)?;
+        let operand_path = key(path, variant);
+        let operands = |value: &Value, path: &str| array(value, path)?.iter().enumerate()
+            .map(|(position, operand)| self.comparable(operand, &index(path, position)))
+            .collect::<Result<Vec<_>, _>>();
+        match variant {
+            "Abs" => Ok(Arithmetic::Abs(Box::new(self.comparable(operand, &operand_path)?))),
+            "Min" => Ok(Arithmetic::Min(operands(operand, &operand_path)?)),
+            "Max" => Ok(Arithmetic::Max(operands(operand, &operand_path)?)),
+            "Call" => {
+                let fields = fields(operand, &operand_path, &["function"], &["arguments"])?;
+                let function = string(&fields["function"], &key(&operand_path, "function"))?.to_string();
+                let arguments = match fields.get("arguments") {
+                    Some(arguments) => operands(arguments, &key(&operand_path, "arguments"))?,
+                    None => Vec::new(),
+                };
+                Ok(Arithmetic::Call { function, arguments })
+            },
+            variant => {
+                let Some((_, operation)) = OPERATIONS.iter().find(|(name, _)| *name == variant) else {
+                    return Err(invalid(path, format!("unknown arithmetic operation '{}'", variant)));
+                };
+                let [left, right] = array(operand, &operand_path)?.as_slice() else {
+                    return Err(invalid(&operand_path, "expected an array of two operands"));
+                };
+                let left = self.comparable(left, &index(&operand_path, 0))?;
+                let right = self.comparable(right, &index(&operand_path, 1))?;
+                Ok(operation(Box::new(left), Box::new(right)))
+            },
+        }
+    }
+
+    fn action(&self, value: &Value, path: &str) -> Result<Action, EngineError> {
+        let (variant, operand) = variant(value, path)?;
+        let operand_path = key(path, variant);
+        match variant {
+            "Assert" => Ok(Action::Assert(self.fact(operand, &operand_path)?)),
+            "Retract" => Ok(Action::Retract(self.fact(operand, &operand_path)?)),
+            "Modify" => Ok(Action::Modify(self.fact(operand, &operand_path)?)),
+            "Call" => {
+                let fields = fields(operand, &operand_path, &["callback"], &["arguments"])?;
+                let callback = string(&fields["callback"], &key(&operand_path, "callback"))?.to_string();
+                let arguments = match fields.get("arguments") {
+                    Some(arguments) => array(arguments, &key(&operand_path, "arguments"))?.iter().enumerate()
+                        .map(|(position, argument)| fact_value(argument, &index(&key(&operand_path, "arguments"), position)))
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                };
+                Ok(Action::Call { callback, arguments })
+            },
+            variant => Err(invalid(path, format!("unknown action '{}'", variant))),
+        }
+    }
+}
+
+fn fact_value(value: &Value, path: &str) -> Result<FactValue, EngineError> {
+    let (variant, operand) = match value {
+        Value::Bool(value) => return Ok(FactValue::Boolean(*value)),
+        Value::String(text) => return Ok(FactValue::Text(text.clone())),
+        Value::Number(number) => {
+            return Ok(match number.as_i64().and_then(|value| i32::try_from(value).ok()) {
+                Some(value) => FactValue::Integer(value),
+                None => FactValue::Float(number.as_f64().unwrap_or(f64::NAN)),
+            });
+        },
+        value => variant(value, path)?,
+    };
+    let operand_path = key(path, variant);
+    match variant {
+        "Integer" => Ok(FactValue::Integer(integer(operand, &operand_path)?)),
+        "Float" => Ok(FactValue::Float(decode_float(operand, &operand_path)?)),
+        "Boolean" => operand.as_bool().map(FactValue::Boolean).ok_or_else(|| invalid(&operand_path, "expected a boolean")),
+        "Text" => Ok(FactValue::Text(string(operand, &operand_path)?.to_string())),
+        "Variable" => Ok(FactValue::Variable(string(operand, &operand_path)?.to_string())),
+        variant => Err(invalid(path, format!("unknown value '{}'", variant))),
+    }
+}
+
+fn symbol(value: &Value, path: &str) -> Result<Symbol, EngineError> {
+    let fields = fields(value, path, &["name", "symbol_type"], &["signature"])?;
+    let name = string(&fields["name"], &key(path, "name"))?;
+    let signature = match fields.get("signature") {
+        Some(signature) => array(signature, &key(path, "signature"))?.iter().enumerate()
+            .map(|(position, argument_type)| decode_symbol_type(argument_type, &index(&key(path, "signature"), position)))
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+    Ok(Symbol::predicate(name, signature, decode_symbol_type(&fields["symbol_type"], &key(path, "symbol_type"))?))
+}
+
+fn decode_symbol_type(value: &Value, path: &str) -> Result<SymbolType, EngineError> {
+    if let Value::String(name) = value {
+        return name.parse().map_err(|error: EngineError| error.at_path(path));
+    }
+    let (variant, operand) = variant(value, path)?;
+    let operand_path = key(path, variant);
+    match variant {
+        "Enum" => {
+            let alternatives = array(operand, &operand_path)?.iter().enumerate()
+                .map(|(position, alternative)| string(alternative, &index(&operand_path, position)).map(str::to_string))
+                .collect::<Result<_, _>>()?;
+            Ok(SymbolType::Enum(alternatives))
+        },
+        "IntegerRange" => {
+            let bounds = fields(operand, &operand_path, &["min", "max"], &[])?;
+            let min = integer(&bounds["min"], &key(&operand_path, "min"))?;
+            Ok(SymbolType::IntegerRange { min, max: integer(&bounds["max"], &key(&operand_path, "max"))? })
+        },
+        "FloatRange" => {
+            let bounds = fields(operand, &operand_path, &["min", "max"], &[])?;
+            let min = decode_float(&bounds["min"], &key(&operand_path, "min"))?;
+            Ok(SymbolType::FloatRange { min, max: decode_float(&bounds["max"], &key(&operand_path, "max"))? })
+        },
+        variant => Err(invalid(path, format!("unknown symbol type '{}'", variant))),
+    }
+}
+
+fn decode_variable(value: &Value, path: &str) -> Result<Variable, EngineError> {
+    let fields = fields(value, path, &["name", "value"], &["state"])?;
+    let name = string(&fields["name"], &key(path, "name"))?;
+    let value = fact_value(&fields["value"], &key(path, "value"))?;
+    let state = match fields.get("state").map(|state| string(state, &key(path, "state"))).transpose()? {
+        None | Some("Stable") => VariableState::Stable,
+        Some("Unstable") => VariableState::Unstable,
+        Some(state) => return Err(invalid(&key(path, "state"), format!("unknown variable state '{}'", state))),
+    };
+    Ok(Variable::new(name, value, state))
+}
+
+fn decode_float(value: &Value, path: &str) -> Result<f64, EngineError> {
+    match value {
+        Value::Number(number) => Ok(number.as_f64().unwrap_or(f64::NAN)),
+        Value::String(text) if text == "NaN" => Ok(f64::NAN),
+        Value::String(text) if text == "inf" => Ok(f64::INFINITY),
+        Value::String(text) if text == "-inf" => Ok(f64::NEG_INFINITY),
+        _ => Err(invalid(path, "expected a number, \"NaN\", \"inf\" or \"-inf\"")),
+    }
+}
+
+fn integer(value: &Value, path: &str) -> Result<i32, EngineError> {
+    value.as_i64().and_then(|value| i32::try_from(value).ok()).ok_or_else(|| invalid(path, "expected a 32-bit integer"))
+}
+
+fn string<'v>(value: &'v Value, path: &str) -> Result<&'v str, EngineError> {
+    value.as_str().ok_or_else(|| invalid(path, "expected a string"))
+}
+
+fn array<'v>(value: &'v Value, path: &str) -> Result<&'v Vec<Value>, EngineError> {
+    value.as_array().ok_or_else(|| invalid(path, "expected an array"))
+}
+
+/// Returns the fields of an object, checking that it has every required field and no unknown one.
+fn fields<'v>(value: &'v Value, path: &str, required: &[&str], optional: &[&str]) -> Result<&'v Map<String, Value>, EngineError> {
+    let object = value.as_object().ok_or_else(|| invalid(path, "expected an object"))?;
+    if let Some(unknown) = object.keys().find(|name| !required.contains(&name.as_str()) && !optional.contains(&name.as_str())) {
+        return Err(invalid(&key(path, unknown), "unknown field"));
+    }
+    if let Some(missing) = required.iter().find(|name| !object.contains_key(**name)) {
+        return Err(invalid(path, format!("missing field '{}'", missing)));
+    }
+    Ok(object)
+}
+
+/// Returns the variant named by the single key of an object, along with its operand.
+fn variant<'v>(value: &'v Value, path: &str) -> Result<(&'v str, &'v Value), EngineError> {
+    match value.as_object() {
+        Some(object) if object.len() == 1 => Ok(object.iter().next().map(|(name, operand)| (name.as_str(), operand)).unwrap()),
+        _ => Err(invalid(path, "expected an object with a single key naming a variant")),
+    }
+}
+
+/// Returns the elements of an optional array field of a knowledge base, each with its path.
+fn elements<'v>(fields: &'v Map<String, Value>, name: &str) -> Result<Vec<(String, &'v Value)>, EngineError> {
+    match fields.get(name) {
+        Some(value) => Ok(array(value, name)?.iter().enumerate().map(|(position, element)| (index(name, position), element)).collect()),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub(crate) fn key(path: &str, name: &str) -> String {
+    match path.is_empty() {
+        true => name.to_string(),
+        false => format!("{}.{}", path, name),
+    }
+}
+
+pub(crate) fn index(path: &str, position: usize) -> String {
+    format!("{}[{}]", path, position)
+}
+
+pub(crate) fn invalid(path: &str, message: impl Into<String>) -> EngineError {
+    EngineError::InvalidJson { path: path.to_string(), message: message.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_name_the_path_of_the_offending_value() {
+        let mut symbols = HashMap::new();
+        symbols.insert("Level".to_string(), Symbol::new("Level", SymbolType::Integer));
+        let decoder = Decoder::new(&symbols);
+        let level = json!({ "AtomicFact": { "symbol": "Level", "value": 3 } });
+
+        let error = decoder.expression(&json!({ "And": [level, { "Xor": [] }] }), "premise").unwrap_err();
+        assert_eq!(error, EngineError::InvalidJson { path: "premise.And[1]".into(), message: "unknown expression 'Xor'".into() });
+        let error = decoder.expression(&json!({ "Not": { "AtomicFact": { "symbol": "Mode" } } }), "").unwrap_err();
+        assert_eq!(error, EngineError::UnknownSymbol { symbol: "Mode".into() }.at_path("Not.AtomicFact.symbol"));
+        let error = decoder.rule(&json!({ "premise": { "Or": [] }, "actions": [{ "Call": { "callback": 1 } }] }), "rules[3]").unwrap_err();
+        assert_eq!(error.to_string(), "invalid JSON at rules[3].actions[0].Call.callback: expected a string");
+    }
+}
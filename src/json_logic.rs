@@ -0,0 +1,288 @@
+use serde_json::{json, Value};
+
+use crate::arithmetic::Operation;
+use crate::json::{index, invalid, key};
+use crate::parser::Comparison;
+use crate::{Arithmetic, ComparableValue, EngineError, FactValue, LogicalOperator};
+
+/// The JsonLogic comparison operators, with the `LogicalOperator` variants they map to.
+const COMPARISONS: [(&str, Comparison); 8] = [
+    ("==", LogicalOperator::EqualTo),
+    ("===", LogicalOperator::EqualTo),
+    ("!=", LogicalOperator::NotEqualTo),
+    ("!==", LogicalOperator::NotEqualTo),
+    (">", LogicalOperator::GreaterThan),
+    ("<", LogicalOperator::LessThan),
+    (">=", LogicalOperator::GreaterThanOrEqualTo),
+    ("<=", LogicalOperator::LessThanOrEqualTo),
+];
+
+/// The JsonLogic arithmetic operators, with the `Arithmetic` variants they map to.
+const OPERATIONS: [(&str, Operation); 5] = [
+    ("+", Arithmetic::Add),
+    ("-", Arithmetic::Subtract),
+    ("*", Arithmetic::Multiply),
+    ("/", Arithmetic::Divide),
+    ("%", Arithmetic::Modulo),
+];
+
+impl LogicalOperator {
+    /// Converts a JsonLogic condition into an expression.
+    ///
+    /// The logical operators `and`, `or` and `!` map to `And`, `Or` and `Not`, and the comparisons `==`, `!=`, `>`,
+    /// `<`, `>=` and `<=` to the comparison variants, with the strict `===` and `!==` mapping to `EqualTo` and
+    /// `NotEqualTo` since the engine never converts between types when comparing. The three-operand forms of `<` and
+    /// `<=` test that the middle operand lies between the others. Comparison operands are either `{"var": "name"}`,
+    /// which reads the symbol of that name as a `ComparableValue::SymbolName`, or literal numbers, strings and
+    /// booleans, or arithmetic over operands: `+`, `-`, `*`, `/`, `%`, `min` and `max` map to the variants of
+    /// `Arithmetic`, with the sums and products of more than two operands nesting to the left and the `-` of a single
+    /// operand subtracting it from zero. The literals `true` and `false` are conditions that always and never hold, and
+    /// a `var` on its own is a condition that holds when the symbol's value is `true`.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde_json::json;
+    /// use symbolic_reasoning_engine::LogicalOperator;
+    ///
+    /// let condition = json!({ "and": [
+    ///     { "==": [{ "var": "Weather" }, "Sunny"] },
+    ///     { "!": { "<": [{ "var": "Temperature" }, 20] } }
+    /// ] });
+    /// let expression = LogicalOperator::from_json_logic(&condition)?;
+    /// assert_eq!(expression.to_string(), r#"@Weather == "Sunny" and not @Temperature < 20"#);
+    ///
+    /// let error = LogicalOperator::from_json_logic(&json!({ "or": [true, { "in": ["a", "abc"] }] })).unwrap_err();
+    /// assert_eq!(error.to_string(), "at or[1]: JsonLogic operation 'in' is not supported");
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnsupportedJsonLogic` for operators and operands the engine has no equivalent for, and
+    /// `EngineError::InvalidJson` for malformed operations, wrapped in `EngineError::JsonPath` with the path of the
+    /// offending operation unless it is the whole condition.
+    pub fn from_json_logic(value: &Value) -> Result<Self, EngineError> {
+        condition(value, "")
+    }
+
+    /// Converts the expression into a JsonLogic condition, the inverse of `from_json_logic`.
+    ///
+    /// Conjunctions and disjunctions of no expressions convert to `true` and `false`, and symbols, whether referenced
+    /// directly or by name, to `var` operations.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnsupportedJsonLogic` for fact patterns, pattern variables, floats that JSON numbers
+    /// cannot represent, and `abs` and function calls in arithmetic, which JsonLogic has no equivalent for, wrapped in
+    /// `EngineError::JsonPath` with their path in the JSON encoding of the expression, such as `And[1].Not`.
+    pub fn to_json_logic(&self) -> Result<Value, EngineError> {
+        json_logic(self, "")
+    }
+}
+
+fn condition(value: &Value, path: &str) -> Result<LogicalOperator, EngineError> {
+    let object = match value {
+        Value::Bool(true) => return Ok(LogicalOperator::And(Vec::new())),
+        Value::Bool(false) => return Ok(LogicalOperator::Or(Vec::new())),
+        Value::Object(object) if object.len() == 1 => object,
+        _ => return Err(invalid(path, "expected a JsonLogic operation or a boolean")),
+    };
+    let (operator, operands) = object.iter().next().unwrap();
+    let operands_path = key(path, operator);
+    match operator.as_str() {
+        "and" | "or" => {
+            let expressions = arguments(operands).iter().enumerate()
+                .map(|(position, operand)| condition(operand, &index(&operands_path, position)))
+                .collect::<Result<_, _>>()?;
+            Ok(if operator == "and" { LogicalOperator::And(expressions) } else { LogicalOperator::Or(expressions) })
+        },
+        "!" => match arguments(operands) {
+            [operand] => Ok(LogicalOperator::Not(Box::new(condition(operand, &index(&operands_path, 0))?))),
+            _ => Err(invalid(&operands_path, "expected a single operand")),
+        },
+        "var" => {
+            let name = Box::new(ComparableValue::SymbolName(variable(operands, &operands_path)?));
+            Ok(LogicalOperator::EqualTo(name, Box::new(ComparableValue::Direct(FactValue::Boolean(true)))))
+        },
+        operator => {
+            let Some((_, comparison)) = COMPARISONS.iter().find(|(name, _)| *name == operator) else {
+                return Err(unsupported(operator).at_path(path));
+            };
+            let values = arguments(operands).iter().enumerate()
+                .map(|(position, operand)| comparable(operand, &index(&operands_path, position)))
+                .collect::<Result<Vec<_>, _>>()?;
+            match values.as_slice() {
+                [left, right] => Ok(comparison(Box::new(left.clone()), Box::new(right.clone()))),
+                [low, middle, high] if matches!(operator, "<" | "<=") => Ok(LogicalOperator::And(vec![
+                    comparison(Box::new(low.clone()), Box::new(middle.clone())),
+                    comparison(Box::new(middle.clone()), Box::new(high.clone())),
+                ])),
+                _ => Err(invalid(&operands_path, format!("expected two operands for '{}'", operator))),
+            }
+        },
+    }
+}
+
+fn comparable(value: &Value, path: &str) -> Result<ComparableValue, EngineError> {
+    let literal = match value {
+        Value::Bool(value) => FactValue::Boolean(*value),
+        Value::String(text) => FactValue::Text(text.clone()),
+        Value::Number(number) => match number.as_i64().and_then(|value| i32::try_from(value).ok()) {
+            Some(value) => FactValue::Integer(value),
+            None => FactValue::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::Object(object) if object.len() == 1 => {
+            let (operator, operands) = object.iter().next().unwrap();
+            return match operator.as_str() {
+                "var" => Ok(ComparableValue::SymbolName(variable(operands, &key(path, operator))?)),
+                operator => Ok(ComparableValue::Arithmetic(Box::new(arithmetic(operator, operands, path)?))),
+            };
+        },
+        Value::Null => return Err(unsupported("null").at_path(path)),
+        _ => return Err(invalid(path, "expected a JsonLogic operation or a literal")),
+    };
+    Ok(ComparableValue::Direct(literal))
+}
+
+/// Converts a JsonLogic arithmetic operation into an expression.
+fn arithmetic(operator: &str, operands: &Value, path: &str) -> Result<Arithmetic, EngineError> {
+    let operation = OPERATIONS.iter().find(|(name, _)| *name == operator).map(|(_, operation)| operation);
+    if operation.is_none() && !matches!(operator, "min" | "max") {
+        return Err(unsupported(operator).at_path(path));
+    }
+    let operands_path = key(path, operator);
+    let values = arguments(operands).iter().enumerate()
+        .map(|(position, operand)| comparable(operand, &index(&operands_path, position)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(operation) = operation else {
+        if values.is_empty() {
+            return Err(invalid(&operands_path, format!("expected at least one operand for '{}'", operator)));
+        }
+        return Ok(if operator == "min" { Arithmetic::Min(values) } else { Arithmetic::Max(values) });
+    };
+    match (operator, values.as_slice()) {
+        ("-", [operand]) => Ok(Arithmetic::Subtract(Box::new(ComparableValue::Direct(FactValue::Integer(0))), Box::new(operand.clone()))),
+        (_, [left, right]) => Ok(operation(Box::new(left.clone()), Box::new(right.clone()))),
+        ("+" | "*", [first, second, rest @ ..]) => {
+            let first = operation(Box::new(first.clone()), Box::new(second.clone()));
+            Ok(rest.iter().fold(first, |left, right| operation(Box::new(ComparableValue::Arithmetic(Box::new(left))), Box::new(right.clone()))))
+        },
+        _ => Err(invalid(&operands_path, format!("expected two operands for '{}'", operator))),
+    }
+}
+
+/// Returns the name read by a `var` operation.
+fn variable(operands: &Value, path: &str) -> Result<String, EngineError> {
+    match arguments(operands) {
+        [Value::String(name)] if !name.is_empty() => Ok(name.clone()),
+        [_, _] => Err(unsupported("var with a default value").at_path(path)),
+        _ => Err(invalid(path, "expected the name of a symbol")),
+    }
+}
+
+/// Returns the operands of an operation, which JsonLogic allows to be written without an array when there is one.
+fn arguments(operands: &Value) -> &[Value] {
+    match operands {
+        Value::Array(operands) => operands,
+        operand => std::slice::from_ref(operand),
+    }
+}
+
+fn json_logic(expression: &LogicalOperator, path: &str) -> Result<Value, EngineError> {
+    let (operator, variant, left, right) = match expression {
+        LogicalOperator::And(expressions) if expressions.is_empty() => return Ok(json!(true)),
+        LogicalOperator::Or(expressions) if expressions.is_empty() => return Ok(json!(false)),
+        LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => {
+            let (variant, operator) = if matches!(expression, LogicalOperator::And(_)) { ("And", "and") } else { ("Or", "or") };
+            let operands = expressions.iter().enumerate()
+                .map(|(position, expression)| json_logic(expression, &index(&key(path, variant), position)))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(json!({ operator: operands }));
+        },
+        LogicalOperator::Not(expression) => return Ok(json!({ "!": [json_logic(expression, &key(path, "Not"))?] })),
+        LogicalOperator::AtomicFact(fact) => return Err(unsupported(&format!("fact pattern {}", fact)).at_path(path)),
+        LogicalOperator::GreaterThan(left, right) => (">", "GreaterThan", left, right),
+        LogicalOperator::LessThan(left, right) => ("<", "LessThan", left, right),
+        LogicalOperator::EqualTo(left, right) => ("==", "EqualTo", left, right),
+        LogicalOperator::NotEqualTo(left, right) => ("!=", "NotEqualTo", left, right),
+        LogicalOperator::GreaterThanOrEqualTo(left, right) => (">=", "GreaterThanOrEqualTo", left, right),
+        LogicalOperator::LessThanOrEqualTo(left, right) => ("<=", "LessThanOrEqualTo", left, right),
+    };
+    let left = operand(left, &index(&key(path, variant), 0))?;
+    let right = operand(right, &index(&key(path, variant), 1))?;
+    Ok(json!({ operator: [left, right] }))
+}
+
+fn operand(value: &ComparableValue, path: &str) -> Result<Value, EngineError> {
+    match value {
+        ComparableValue::Symbol(symbol) => Ok(json!({ "var": symbol.name })),
+        ComparableValue::SymbolName(name) => Ok(json!({ "var": name })),
+        ComparableValue::Direct(FactValue::Integer(value)) => Ok(json!(value)),
+        ComparableValue::Direct(FactValue::Float(value)) => {
+            serde_json::Number::from_f64(*value).map(Value::Number).ok_or_else(|| unsupported(&format!("float {:?}", value)).at_path(path))
+        },
+        ComparableValue::Direct(FactValue::Boolean(value)) => Ok(json!(value)),
+        ComparableValue::Direct(FactValue::Text(text)) => Ok(json!(text)),
+        ComparableValue::Direct(FactValue::Variable(name)) => Err(unsupported(&format!("pattern variable ?{}", name)).at_path(path)),
+        ComparableValue::Arithmetic(expression) => {
+            let path = key(path, "Arithmetic");
+            let operands = |name: &str, operands: &[&ComparableValue]| operands.iter().enumerate()
+                .map(|(position, value)| operand(value, &index(&key(&path, name), position)))
+                .collect::<Result<Vec<_>, _>>();
+            let (operator, name) = match expression.as_ref() {
+                Arithmetic::Add(..) => ("+", "Add"),
+                Arithmetic::Subtract(..) => ("-", "Subtract"),
+                Arithmetic::Multiply(..) => ("*", "Multiply"),
+                Arithmetic::Divide(..) => ("/", "Divide"),
+                Arithmetic::Modulo(..) => ("%", "Modulo"),
+                Arithmetic::Min(..) => ("min", "Min"),
+                Arithmetic::Max(..) => ("max", "Max"),
+                Arithmetic::Abs(_) => return Err(unsupported("abs").at_path(&path)),
+                Arithmetic::Call { function, .. } => return Err(unsupported(&format!("function {}", function)).at_path(&path)),
+            };
+            Ok(json!({ operator: operands(name, &expression.operands())? }))
+        },
+    }
+}
+
+fn unsupported(operation: &str) -> EngineError {
+    EngineError::UnsupportedJsonLogic { operation: operation.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn between_and_bare_variables_expand_to_comparisons() {
+        let symbol = |name: &str| Box::new(ComparableValue::SymbolName(name.into()));
+        let direct = |value: FactValue| Box::new(ComparableValue::Direct(value));
+
+        let between = condition(&json!({ "<=": [1, { "var": ["Level"] }, 2.5] }), "").unwrap();
+        assert_eq!(between, LogicalOperator::And(vec![
+            LogicalOperator::LessThanOrEqualTo(direct(FactValue::Integer(1)), symbol("Level")),
+            LogicalOperator::LessThanOrEqualTo(symbol("Level"), direct(FactValue::Float(2.5))),
+        ]));
+        assert_eq!(condition(&json!({ "var": "Raining" }), "").unwrap(), LogicalOperator::EqualTo(symbol("Raining"), direct(FactValue::Boolean(true))));
+
+        let error = condition(&json!({ ">": [{ "var": ["Level", 0] }, 1] }), "").unwrap_err();
+        assert_eq!(error, unsupported("var with a default value").at_path(">[0].var"));
+        let error = condition(&json!({ "==": [{ "var": "Level" }] }), "").unwrap_err();
+        assert_eq!(error, invalid("==", "expected two operands for '=='"));
+    }
+
+    #[test]
+    fn arithmetic_nests_to_the_left_and_converts_back() {
+        let sum = condition(&json!({ ">": [{ "+": [1, { "var": "Level" }, { "-": [2] }] }, { "max": [0, 1] }] }), "").unwrap();
+        assert_eq!(sum.to_string(), "1 + @Level + (0 - 2) > max(0, 1)");
+        assert_eq!(condition(&json_logic(&sum, "").unwrap(), "").unwrap(), sum);
+
+        let absolute = LogicalOperator::LessThan(
+            Box::new(ComparableValue::Arithmetic(Box::new(Arithmetic::Abs(Box::new(ComparableValue::SymbolName("Level".into())))))),
+            Box::new(ComparableValue::Direct(FactValue::Integer(1))),
+        );
+        assert_eq!(json_logic(&absolute, "").unwrap_err(), unsupported("abs").at_path("LessThan[0].Arithmetic"));
+        let error = condition(&json!({ "==": [{ "/": [1] }, 1] }), "").unwrap_err();
+        assert_eq!(error, invalid("==[0]./", "expected two operands for '/'"));
+        let error = condition(&json!({ ">": [{ "min": [] }, 1] }), "").unwrap_err();
+        assert_eq!(error, invalid(">[0].min", "expected at least one operand for 'min'"));
+    }
+}
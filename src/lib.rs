@@ -1,39 +1,159 @@
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::str::FromStr;
 use regex::Regex;
 
+mod agenda;
+mod answer_set;
+mod arithmetic;
+mod datalog;
+mod dependency;
+mod display;
+mod error;
+mod failure;
+mod json;
+mod json_logic;
+mod parser;
+mod persistence;
+mod proof;
+mod query;
+mod rete;
+mod symbol_type;
+mod template;
+mod tms;
+mod working_memory;
+#[cfg(test)]
+mod test_support;
+
+pub use agenda::{Activation, ConflictStrategy};
+pub use arithmetic::{Arithmetic, Function};
+pub use error::EngineError;
+pub use failure::{Failure, RuleAttempt};
+pub use parser::Program;
+pub use persistence::FORMAT_VERSION;
+pub use proof::Proof;
+pub use query::Query;
+pub use symbol_type::SymbolType;
+pub use tms::{Justification, Label};
+use agenda::Agenda;
+use answer_set::Solver;
+use datalog::Clause;
+use dependency::DependencyGraph;
+use parser::Statement;
+use rete::Network;
+use tms::TruthMaintenance;
+use working_memory::{TotalFloat, WorkingMemory};
+
 /// Represents a symbol in the symbolic reasoning engine.
 ///
 /// A symbol is a basic unit of meaning, identified by a name and associated with a type.
 /// Symbols are used to construct facts and define the relationship between facts and rules within the engine.
+/// The `SymbolType` declares the schema of the values the symbol may hold, and is enforced by the engine.
+///
+/// A symbol may also be declared as a predicate with a `signature`, listing the types of the arguments its facts
+/// take. For example, a `Parent` predicate with the signature `[Text, Text]` describes facts such as
+/// `Parent(alice, bob)`, while `Temperature` with the signature `[Text]` describes facts such as
+/// `Temperature(room1) = 22`. Symbols without a signature have an arity of zero and describe plain symbol/value facts.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Symbol {
     name: String,
-    symbol_type: String,
+    signature: Vec<SymbolType>,
+    symbol_type: SymbolType,
 }
 
 impl Symbol {
-    pub fn new(name: &str, symbol_type: &str) -> Self {
+    pub fn new(name: &str, symbol_type: SymbolType) -> Self {
+        Self::predicate(name, Vec::new(), symbol_type)
+    }
+
+    /// Creates a predicate symbol whose facts take arguments of the types listed in `signature`.
+    pub fn predicate(name: &str, signature: Vec<SymbolType>, symbol_type: SymbolType) -> Self {
         Self {
             name: name.to_string(),
-            symbol_type: symbol_type.to_string(),
+            signature,
+            symbol_type,
         }
     }
+
+    /// Returns the unique name identifying this symbol.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the declared types of the arguments taken by facts about this symbol.
+    pub fn signature(&self) -> &[SymbolType] {
+        &self.signature
+    }
+
+    /// Returns the number of arguments taken by facts about this symbol.
+    pub fn arity(&self) -> usize {
+        self.signature.len()
+    }
+
+    /// Returns the declared type of this symbol.
+    pub fn symbol_type(&self) -> &SymbolType {
+        &self.symbol_type
+    }
 }
 
 /// Represents a fact in the symbolic reasoning engine.
 ///
 /// A fact associates a symbol with a specific value, contributing to the knowledge base of the engine.
 /// Facts are used in conjunction with rules to infer new information or make decisions based on the engine's current state.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Facts about predicate symbols additionally carry a tuple of argument terms, one per entry of the symbol's
+/// signature, so that `Temperature(room1) = 22` and `Temperature(room2) = 18` are distinct facts about the same symbol.
+/// Relations without a meaningful value, such as `Parent(alice, bob)`, are conventionally given the value
+/// `FactValue::Boolean(true)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Fact {
     symbol: Symbol,
+    arguments: Vec<FactValue>,
     value: FactValue, // Simplified to boolean for this example
 }
 
 impl Fact {
     pub fn new(symbol: Symbol, value: FactValue) -> Self {
-        Self { symbol, value }
+        Self::with_arguments(symbol, Vec::new(), value)
+    }
+
+    /// Creates a fact about a predicate symbol applied to the given arguments.
+    pub fn with_arguments(symbol: Symbol, arguments: Vec<FactValue>, value: FactValue) -> Self {
+        Self { symbol, arguments, value }
+    }
+
+    /// Returns the symbol this fact is about.
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    /// Returns the arguments the fact's predicate is applied to, empty for plain symbol/value facts.
+    pub fn arguments(&self) -> &[FactValue] {
+        &self.arguments
+    }
+
+    /// Returns the number of arguments the fact's predicate is applied to.
+    pub fn arity(&self) -> usize {
+        self.arguments.len()
+    }
+
+    /// Checks whether the fact is free of pattern variables, in both its arguments and its value.
+    pub fn is_ground(&self) -> bool {
+        self.variables().next().is_none()
+    }
+
+    /// Returns the names of the pattern variables in the fact's arguments and value, in order of appearance.
+    pub(crate) fn variables(&self) -> impl Iterator<Item = &str> {
+        self.arguments.iter().chain(std::iter::once(&self.value)).filter_map(|term| match term {
+            FactValue::Variable(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value asserted for the fact's symbol.
+    pub fn value(&self) -> &FactValue {
+        &self.value
     }
 }
 
@@ -41,12 +161,49 @@ impl Fact {
 ///
 /// This enumeration covers basic data types such as integers, floats, booleans, and text strings,
 /// allowing for a wide range of information to be represented and manipulated within the engine.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The `Variable` variant is a logic variable, written `?name`, that stands for an unknown value in rule premises,
+/// rule conclusions and goals. When a premise is evaluated, each pattern variable is unified with the corresponding
+/// value of every matching known fact, and the resulting bindings are carried into the rule's conclusion. Pattern
+/// variables can never be part of an asserted fact.
+///
+/// Values can be hashed and compared for equality, so that facts can be used as keys of hash maps. Floats are compared
+/// through a total order in which `-0.0` equals `0.0` and every NaN equals every other NaN, so that equality stays
+/// reflexive and consistent with `Hash`.
+#[derive(Debug, Clone)]
 pub enum FactValue {
     Integer(i32),
     Float(f64),
     Boolean(bool),
     Text(String),
+    Variable(String),
+}
+
+impl PartialEq for FactValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FactValue::Integer(left), FactValue::Integer(right)) => left == right,
+            (FactValue::Float(left), FactValue::Float(right)) => TotalFloat::new(*left) == TotalFloat::new(*right),
+            (FactValue::Boolean(left), FactValue::Boolean(right)) => left == right,
+            (FactValue::Text(left), FactValue::Text(right)) => left == right,
+            (FactValue::Variable(left), FactValue::Variable(right)) => left == right,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FactValue {}
+
+impl Hash for FactValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            FactValue::Integer(value) => value.hash(state),
+            FactValue::Float(value) => TotalFloat::new(*value).hash(state),
+            FactValue::Boolean(value) => value.hash(state),
+            FactValue::Text(value) | FactValue::Variable(value) => value.hash(state),
+        }
+    }
 }
 
 /// Represents a value that can be compared within the rule engine, encapsulating different types of comparable values.
@@ -62,6 +219,8 @@ pub enum FactValue {
 ///   is used when the value for comparison is to be fetched from the current set of facts based on a symbol.
 /// - `SymbolName(String)`: Similar to the `Symbol` variant, but uses a plain string to refer to the symbol name. This variant allows for a more flexible or dynamic
 ///   reference to values within the rule engine, especially when symbol names are generated or not known at compile time.
+/// - `Arithmetic(Box<Arithmetic>)`: Represents an arithmetic expression over other comparable values, such as `Price * Quantity`, which evaluates to a float
+///   when the comparison is evaluated.
 ///
 /// This enum is integral to the rule engine's ability to evaluate conditions involving comparisons, ensuring flexibility and robustness in how values are specified
 /// and retrieved for comparison operations.
@@ -70,6 +229,7 @@ pub enum ComparableValue {
     Direct(FactValue),
     Symbol(Symbol),
     SymbolName(String),
+    Arithmetic(Box<Arithmetic>),
 }
 
 /// Represents the logical operators used to construct complex logical expressions within the symbolic reasoning engine.
@@ -93,7 +253,7 @@ pub enum ComparableValue {
 /// facts and variables, providing a flexible mechanism for defining the logic that drives inference and decision-making.
 /// These operators are essential for expressing dependencies and relationships between facts, underpinning the
 /// engine's ability to reason about and interpret the data it manages.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LogicalOperator {
     And(Vec<LogicalOperator>),
     Or(Vec<LogicalOperator>),
@@ -107,15 +267,50 @@ pub enum LogicalOperator {
     LessThanOrEqualTo(Box<ComparableValue>, Box<ComparableValue>),
 }
 
+/// Represents a named variable binding within the symbolic reasoning engine.
+///
+/// Variables hold values that can be interpolated into facts and comparisons using the `${name}` syntax. The
+/// `state` of a variable controls whether it is resolved immediately when facts referencing it are asserted
+/// (`Stable`), or whether resolution is deferred until the variable settles (`Unstable`).
 #[derive(Debug, Clone, PartialEq)]
-struct Variable {
+pub struct Variable {
     name: String,
     value: FactValue,
     state: VariableState,
 }
 
+impl Variable {
+    pub fn new(name: &str, value: FactValue, state: VariableState) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            state,
+        }
+    }
+
+    /// Returns the name the variable is referenced by, without the `${}` delimiters.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value currently bound to the variable.
+    pub fn value(&self) -> &FactValue {
+        &self.value
+    }
+
+    /// Returns the resolution state of the variable.
+    pub fn state(&self) -> &VariableState {
+        &self.state
+    }
+}
+
+/// Describes whether a variable's value may be resolved into facts and comparisons.
+///
+/// Variants:
+/// - `Stable`: The value is settled and may be interpolated wherever the variable is referenced.
+/// - `Unstable`: The value may still change, so interpolation is deferred.
 #[derive(Debug, Clone, PartialEq)]
-enum VariableState {
+pub enum VariableState {
     Stable,
     Unstable,
 }
@@ -128,26 +323,125 @@ enum VariableState {
 /// of the rule is considered to be true, potentially leading to the assertion of new facts or the triggering of
 /// further logical operations.
 ///
-/// The `Rule` struct encapsulates this logic, containing both the premise and conclusion as logical expressions.
+/// The `Rule` struct encapsulates this logic, containing both the premise and the consequent of the rule.
 /// The premise is represented as a `LogicalOperator`, which can combine multiple conditions using logical
-/// operators like AND, OR, and NOT. The conclusion is represented as a `Fact`, specifying the outcome or assertion
-/// that should be made when the premise evaluates to true.
+/// operators like AND, OR, and NOT. The consequent is a list of `Action`s, specifying the facts to assert, retract
+/// or modify, and the callbacks to notify, when the premise evaluates to true.
 ///
 /// Attributes:
 /// - `premise`: A `LogicalOperator` representing the combination of conditions that must be satisfied for the rule
 ///   to be applied. This can involve complex nested logical expressions involving facts and variables.
-/// - `conclusion`: A `Fact` that specifies what should be considered true or asserted within the engine's knowledge
-///   base when the premise is satisfied. The conclusion contributes to the engine's dynamic knowledge, influencing
-///   subsequent reasoning and decision-making.
+/// - `actions`: The `Action`s executed, in order, every time the premise is satisfied. The facts asserted by the rule
+///   are its conclusions, which contribute to the engine's dynamic knowledge, influencing subsequent reasoning and
+///   decision-making.
+/// - `salience`: The priority of the rule's activations on the agenda. Activations of rules with a higher salience
+///   fire first, and rules default to a salience of 0.
+/// - `name`: The name given to the rule in the rule language, if any.
 ///
 /// Rules play a critical role in the engine's operation, allowing for the dynamic evolution of the knowledge base
 /// through logical inference based on defined conditions and relationships. They enable the engine to model and
 /// reason about complex scenarios, facilitating sophisticated decision-making processes.
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rule {
     premise: LogicalOperator,
-    conclusion: Fact,
+    actions: Vec<Action>,
+    salience: i32,
+    name: Option<String>,
+}
+
+impl Rule {
+    /// Creates a rule asserting a single conclusion when its premise holds.
+    pub fn new(premise: LogicalOperator, conclusion: Fact) -> Self {
+        Self::with_actions(premise, vec![Action::Assert(conclusion)])
+    }
+
+    /// Creates a rule executing a list of actions when its premise holds.
+    pub fn with_actions(premise: LogicalOperator, actions: Vec<Action>) -> Self {
+        Self { premise, actions, salience: 0, name: None }
+    }
+
+    /// Returns the logical expression that must hold for the rule to apply.
+    pub fn premise(&self) -> &LogicalOperator {
+        &self.premise
+    }
+
+    /// Returns the actions executed when the rule's premise holds, in execution order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the priority of the rule's activations on the agenda.
+    pub fn salience(&self) -> i32 {
+        self.salience
+    }
+
+    /// Returns the name given to the rule in the rule language, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the facts asserted or modified when the rule's premise holds.
+    ///
+    /// These are the conclusions backward chaining can establish through the rule.
+    pub fn conclusions(&self) -> impl Iterator<Item = &Fact> {
+        self.actions.iter().filter_map(|action| match action {
+            Action::Assert(fact) | Action::Modify(fact) => Some(fact),
+            Action::Retract(_) | Action::Call { .. } => None,
+        })
+    }
+}
+
+/// Represents an action executed when the premise of a rule is satisfied.
+///
+/// Facts and arguments within actions are templates, instantiated against the bindings of each match of the premise
+/// exactly as single conclusions are: pattern variables are replaced by their bound values and `${...}` placeholders
+/// are rendered. The actions of a rule are executed in order, and all the actions produced by a forward chaining pass
+/// are instantiated before any of them is executed, so that a failing rule leaves the knowledge base untouched.
+///
+/// Variants:
+/// - `Assert`: Adds the fact to the knowledge base, unless an equal fact is already known.
+/// - `Retract`: Removes every known fact that unifies with the given pattern. Pattern variables that are not bound by
+///   the premise act as wildcards, so `Temperature(room1) = ?any` retracts the temperature of `room1` whatever it is.
+/// - `Modify`: Replaces the value of the known fact with the same symbol and arguments, or asserts the fact if there
+///   is none.
+/// - `Call`: Invokes the callback registered under `callback` through `register_callback`, passing the instantiated
+///   `arguments`. Callbacks let the host application react when a rule fires.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    Assert(Fact),
+    Retract(Fact),
+    Modify(Fact),
+    Call { callback: String, arguments: Vec<FactValue> },
+}
+
+/// A host function invoked by `Action::Call` with the instantiated arguments of the action.
+pub type Callback = Box<dyn FnMut(&[FactValue])>;
+
+/// One way a logical expression is satisfied: the bindings of its pattern variables, the known facts it relies on,
+/// the fact patterns it requires to be absent, and, when backward chaining, the proofs of the conditions it satisfied.
+#[derive(Debug, Clone)]
+struct Solution {
+    bindings: HashMap<String, Variable>,
+    antecedents: Vec<Fact>,
+    exclusions: Vec<Fact>,
+    proofs: Vec<Proof>,
+}
+
+impl Solution {
+    fn new(bindings: HashMap<String, Variable>) -> Self {
+        Self { bindings, antecedents: Vec::new(), exclusions: Vec::new(), proofs: Vec::new() }
+    }
+
+    /// Extends the solution with the facts an additional condition relies on.
+    fn supported_by(&self, facts: impl IntoIterator<Item = Fact>) -> Self {
+        let mut solution = self.clone();
+        for fact in facts {
+            if !solution.antecedents.contains(&fact) {
+                solution.antecedents.push(fact);
+            }
+        }
+        solution
+    }
 }
 
 /// Represents the core of the symbolic reasoning engine.
@@ -170,6 +464,8 @@ pub struct Rule {
 /// - `variable_bindings`: A mapping of variable names to their `FactValue` instances. This allows the engine to handle
 ///   dynamic values and conditions within rules and logical expressions, enhancing the engine's flexibility and
 ///   applicability to real-world scenarios.
+/// - `agenda`: The activations of the rules whose premise holds, waiting to fire in the order set by the rules'
+///   salience and the conflict resolution strategy.
 ///
 /// Usage:
 /// The engine is used by first defining the necessary symbols, facts, and rules that represent the domain of interest.
@@ -180,21 +476,37 @@ pub struct Rule {
 ///
 /// Example:
 /// ```
-/// // let mut engine = SymbolicReasoningEngine::new();
-/// // engine.assert_fact(...); // Assert initial facts
-/// // engine.assert_variable(...); // Define variables and their bindings
-/// // engine.add_rule(...); // Define rules
-/// // engine.forward_chaining_with_variables(); // Perform inference to update the knowledge base
+/// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+///
+/// let mut engine = SymbolicReasoningEngine::new();
+/// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+/// let activity = engine.define_symbol("Activity", SymbolType::Text)?;
+///
+/// engine.assert_fact(weather.clone(), FactValue::Text("Sunny".into()))?; // Assert initial facts
+/// engine.define_rule( // Define rules
+///     LogicalOperator::AtomicFact(Fact::new(weather, FactValue::Text("Sunny".into()))),
+///     Fact::new(activity.clone(), FactValue::Text("Hiking".into())),
+/// )?;
+/// engine.forward_chaining_with_variables()?; // Perform inference to update the knowledge base
+///
+/// assert!(engine.contains_fact(&Fact::new(activity, FactValue::Text("Hiking".into()))));
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
 /// ```
 ///
 /// The `SymbolicReasoningEngine` is designed to be extensible, allowing for the integration of additional functionality
 /// and optimizations to suit specific requirements or to enhance its reasoning capabilities.
-#[allow(dead_code)]
 pub struct SymbolicReasoningEngine {
     symbols: HashMap<String, Symbol>,
-    facts: Vec<Fact>,
+    facts: WorkingMemory,
     rules: Vec<Rule>,
     variable_bindings: HashMap<String, Variable>,
+    callbacks: HashMap<String, Callback>,
+    functions: HashMap<String, Function>,
+    tms: TruthMaintenance,
+    network: Network,
+    agenda: Agenda,
+    /// The stratum of every rule, or `None` if rules were added since the strata were last computed.
+    strata: Option<Vec<usize>>,
     debug: bool,
 }
 
@@ -219,22 +531,77 @@ pub struct SymbolicReasoningEngine {
 ///
 /// The `SymbolicReasoningEngine` aims to provide a comprehensive framework for symbolic reasoning, supporting a wide
 /// range of applications from automated decision-making systems to educational tools for logic and reasoning.
-#[allow(dead_code)]
 impl SymbolicReasoningEngine {
     /// Creates a new instance of the symbolic reasoning engine, initializing an empty knowledge base,
     /// an empty set of rules, and no variable bindings.
     ///
     /// Returns a `SymbolicReasoningEngine` instance ready for the definition of symbols, facts, rules, and variables.
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             symbols: HashMap::new(),
-            facts: Vec::new(),
+            facts: WorkingMemory::default(),
             rules: Vec::new(),
             variable_bindings: HashMap::new(),
+            callbacks: HashMap::new(),
+            functions: HashMap::new(),
+            tms: TruthMaintenance::default(),
+            network: Network::default(),
+            agenda: Agenda::default(),
+            strata: Some(Vec::new()),
             debug: false
         }
     }
 
+    /// Returns an iterator over every fact currently held in the knowledge base, in assertion order.
+    ///
+    /// This includes both facts asserted through `assert_fact` and facts inferred by forward chaining.
+    pub fn facts(&self) -> impl Iterator<Item = &Fact> {
+        self.facts.iter()
+    }
+
+    /// Checks whether the knowledge base holds a fact equal to the given one.
+    ///
+    /// # Arguments
+    /// * `fact` - The `Fact` to look for.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if an equal fact has been asserted or inferred; otherwise, `false`.
+    pub fn contains_fact(&self, fact: &Fact) -> bool {
+        self.facts.contains(fact)
+    }
+
+    /// Returns the rules defined in the engine, in definition order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Looks up a previously defined symbol by name.
+    ///
+    /// # Arguments
+    /// * `name` - The name the symbol was defined with.
+    ///
+    /// # Returns
+    /// * `Option<&Symbol>` - The symbol if it has been defined; otherwise, `None`.
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.get(name)
+    }
+
+    /// Returns an iterator over every symbol defined in the engine, in no particular order.
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.values()
+    }
+
+    /// Looks up the current binding of a variable by name.
+    ///
+    /// # Arguments
+    /// * `name` - The variable name, without the `${}` delimiters.
+    ///
+    /// # Returns
+    /// * `Option<&Variable>` - The bound variable if it has been asserted; otherwise, `None`.
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.variable_bindings.get(name)
+    }
+
     /// Enables the debugging mode for the rule engine.
     ///
     /// This method sets the internal `debug` flag of the rule engine to `true`, activating the output of debug messages across the engine. When debug mode is enabled,
@@ -244,10 +611,15 @@ impl SymbolicReasoningEngine {
     ///
     /// Enabling debug mode is a crucial tool for developers to understand the inner workings of the rule engine, diagnose problems, and ensure that the logic
     /// of rule evaluation and fact assertion behaves as expected.
-    fn enable_debug(&mut self) {
+    pub fn enable_debug(&mut self) {
         self.debug = true;
     }
 
+    /// Disables the debugging mode enabled by `enable_debug`, so that the engine stops printing debug messages.
+    pub fn disable_debug(&mut self) {
+        self.debug = false;
+    }
+
     /// Prints a debug message to the console if debugging is enabled for the rule engine.
     ///
     /// This method is a utility function used throughout the rule engine to output debug information. It checks the internal `debug` flag
@@ -273,14 +645,15 @@ impl SymbolicReasoningEngine {
     /// and rules, serving as identifiers that link values or conditions within the engine's logic.
     ///
     /// Each symbol is uniquely identified by its name, and associated with a specific type that determines
-    /// the kind of values it can hold or be compared against in facts and rules. Common types include "Integer",
-    /// "Float", "Boolean", and "Text", among others that the engine supports.
+    /// the kind of values it can hold or be compared against in facts and rules. The supported types are
+    /// `Integer`, `Float`, `Boolean` and `Text`, as well as enumerations of allowed text values and inclusive
+    /// integer or float ranges (see `SymbolType`).
     ///
     /// # Arguments
     /// * `name` - A string slice (`&str`) representing the unique name of the symbol. This name is used to
     ///   reference the symbol in facts, rules, and logical expressions.
-    /// * `symbol_type` - A string slice (`&str`) specifying the type of the symbol. The type influences how
-    ///   the symbol can be used in the engine, particularly in comparisons and evaluations.
+    /// * `symbol_type` - A `SymbolType` specifying the schema of the symbol's values. The type is enforced when
+    ///   facts are asserted and rules are defined, and determines how the symbol can be used in comparisons.
     ///
     /// # Returns
     /// Returns a `Symbol` instance representing the newly defined symbol. This instance can be used directly
@@ -288,27 +661,64 @@ impl SymbolicReasoningEngine {
     ///
     /// # Example
     /// ```
-    /// //let mut engine = SymbolicReasoningEngine::new();
-    /// //let temperature_symbol = engine.define_symbol("temperature", "Integer");
+    /// use symbolic_reasoning_engine::{SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature_symbol = engine.define_symbol("temperature", SymbolType::Integer).unwrap();
     /// // Now, "temperature" can be used as a symbol in facts and rules, associated with integer values.
+    /// assert_eq!(temperature_symbol.name(), "temperature");
     /// ```
     ///
-    /// # Panics
-    /// This method may panic if an attempt is made to define a symbol with a name that already exists within
-    /// the engine's context, ensuring that symbol names remain unique and unambiguous.
+    /// # Errors
+    /// Returns `EngineError::DuplicateSymbol` if a symbol with the same name already exists within the engine's
+    /// context, ensuring that symbol names remain unique and unambiguous.
     ///
     /// It is essential to define symbols before using them in facts and rules, as they establish the basic
     /// vocabulary for expressing the knowledge and logic encapsulated by the engine.
-    fn define_symbol(&mut self, name: &str, symbol_type: &str) -> Symbol {
-        let symbol = Symbol {
-            name: name.to_string(),
-            symbol_type: symbol_type.to_string(),
-        };
+    pub fn define_symbol(&mut self, name: &str, symbol_type: SymbolType) -> Result<Symbol, EngineError> {
+        self.define_predicate(name, Vec::new(), symbol_type)
+    }
+
+    /// Defines a new predicate symbol, whose facts take a tuple of arguments in addition to their value.
+    ///
+    /// Predicates allow a single symbol to describe many entities, such as `Parent(alice, bob)` or
+    /// `Temperature(room1) = 22`, instead of inventing one symbol per entity. The `signature` declares the number and
+    /// types of the arguments, and is enforced whenever a fact about the predicate is asserted or used in a rule.
+    ///
+    /// # Arguments
+    /// * `name` - A string slice (`&str`) representing the unique name of the predicate.
+    /// * `signature` - The `SymbolType` of each argument, in order. An empty signature defines a plain symbol, exactly
+    ///   as `define_symbol` does.
+    /// * `symbol_type` - A `SymbolType` specifying the schema of the values associated with each tuple of arguments.
+    ///   Relations without a meaningful value are conventionally declared as `SymbolType::Boolean`.
+    ///
+    /// # Returns
+    /// Returns the `Symbol` representing the newly defined predicate, to be used in facts built with
+    /// `Fact::with_arguments` and asserted with `assert_predicate`.
+    ///
+    /// # Example
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    /// assert_eq!(parent.arity(), 2);
+    ///
+    /// let arguments = vec![FactValue::Text("alice".into()), FactValue::Text("bob".into())];
+    /// engine.assert_predicate(parent.clone(), arguments.clone(), FactValue::Boolean(true))?;
+    /// assert!(engine.specify_goal(&Fact::with_arguments(parent, arguments, FactValue::Boolean(true)))?);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::DuplicateSymbol` if a symbol or predicate with the same name already exists.
+    pub fn define_predicate(&mut self, name: &str, signature: Vec<SymbolType>, symbol_type: SymbolType) -> Result<Symbol, EngineError> {
         if self.find_symbol(name) {
-            panic!("Symbol already defined");
+            return Err(EngineError::DuplicateSymbol { symbol: name.to_string() });
         }
+        let symbol = Symbol::predicate(name, signature, symbol_type);
         self.symbols.insert(name.to_string(), symbol.clone());
-        symbol
+        Ok(symbol)
     }
 
     /// Checks if a symbol with the specified name exists within the rule engine's symbol table.
@@ -325,44 +735,140 @@ impl SymbolicReasoningEngine {
     ///
     /// This method is essential for ensuring that references to symbols in rules and facts are valid, thereby preventing the rule engine from attempting
     /// to evaluate rules or facts with undefined symbols.
-    fn find_symbol(&mut self, name: &str) -> bool {
+    fn find_symbol(&self, name: &str) -> bool {
         self.symbols.contains_key(name)
     }
 
+    /// Verifies that the given symbol has been defined in the engine with the same name and type.
+    ///
+    /// # Arguments
+    /// * `symbol` - The `Symbol` referenced by a fact, rule or comparison.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if no matching symbol has been defined via `define_symbol`.
+    fn validate_symbol(&self, symbol: &Symbol) -> Result<(), EngineError> {
+        match self.symbols.get(&symbol.name) {
+            Some(defined) if defined == symbol => Ok(()),
+            _ => Err(EngineError::UnknownSymbol { symbol: symbol.name.clone() }),
+        }
+    }
+
     /// Asserts or updates a variable with a specified value in the engine's context.
     ///
     /// This method is responsible for binding a variable name to a value within the engine's variable bindings.
     /// It should be called prior to asserting facts that depend on these variables to ensure that the variable
     /// values are correctly set and can be resolved during rule evaluation and fact matching.
     ///
-    /// Variables asserted using this method can be used within `FactValue::Comparison` or directly as `FactValue`
-    /// in facts, enabling dynamic behavior in rule evaluation based on variable values.
+    /// Variables asserted using this method are referenced as `FactValue::Variable` operands in comparisons and
+    /// patterns, where they stand for their bound value, and as `${...}` placeholders in text values, which are only
+    /// replaced while the variable is `VariableState::Stable`.
     ///
     /// # Arguments
-    /// * `var_name` - A `String` representing the name of the variable to be asserted or updated. This name
-    ///   is used as the key to store the variable's value in the engine's bindings and must be unique within
-    ///   the context of a rule evaluation session.
-    /// * `value` - A `FactValue` representing the value to be bound to the variable. This value can be of any
-    ///   supported `FactValue` type (e.g., `Integer`, `Float`, `Boolean`, `Text`).
+    /// * `var` - The `Variable` to be asserted or updated. Its name is used as the key to store it in the engine's
+    ///   bindings, and its value can be of any supported `FactValue` type (e.g., `Integer`, `Float`, `Boolean`,
+    ///   `Text`).
     ///
     /// # Usage
     /// ```
-    /// // Example usage within the symbolic reasoning engine:
-    /// // engine.assert_variable("temperature", FactValue::Integer(25));
-    /// // Now, the engine knows that "temperature" is bound to the value 25
-    /// // This variable can then be used in asserting facts or within rule conditions
+    /// use symbolic_reasoning_engine::{ComparableValue, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine, Variable, VariableState};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature = engine.define_symbol("temperature", SymbolType::Integer)?;
+    /// engine.assert_fact(temperature.clone(), FactValue::Integer(30))?;
+    /// engine.assert_variable(&Variable::new("threshold", FactValue::Integer(25), VariableState::Stable));
+    ///
+    /// // The variable stands for its value in rule conditions
+    /// let above_threshold = LogicalOperator::GreaterThan(
+    ///     Box::new(ComparableValue::Symbol(temperature)),
+    ///     Box::new(ComparableValue::Direct(FactValue::Variable("threshold".into()))),
+    /// );
+    /// assert!(engine.is_premise_true(&above_threshold)?);
+    ///
+    /// // Asserting it again updates its value
+    /// engine.assert_variable(&Variable::new("threshold", FactValue::Integer(35), VariableState::Stable));
+    /// assert!(!engine.is_premise_true(&above_threshold)?);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
     /// # Note
     /// Attempting to assert a variable that already exists in the bindings will update its value. This allows
     /// for dynamic adjustments of variable values based on new information or changing conditions within the
     /// engine's execution context.
-    fn assert_variable(&mut self, var: &Variable) {
+    pub fn assert_variable(&mut self, var: &Variable) {
         // Insert or update the variable's value in the bindings
         self.variable_bindings.insert(var.name.to_string(), var.clone());
+        self.network.invalidate();
         self.print_debug("Variable asserted/updated in the bindings.");
     }
 
+    /// Registers a host function that rules can invoke through `Action::Call`.
+    ///
+    /// # Arguments
+    /// * `name` - The name rules refer to the callback by. Registering a callback under an existing name replaces it.
+    /// * `callback` - The function invoked with the instantiated arguments of the action every time a rule calling it
+    ///   fires.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use symbolic_reasoning_engine::{Action, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let alarm = engine.define_symbol("Alarm", SymbolType::Boolean)?;
+    /// engine.assert_fact(alarm.clone(), FactValue::Boolean(true))?;
+    ///
+    /// let notifications = Rc::new(RefCell::new(Vec::new()));
+    /// let sink = notifications.clone();
+    /// engine.register_callback("notify", move |arguments| sink.borrow_mut().push(arguments.to_vec()));
+    ///
+    /// engine.define_rule_with_actions(
+    ///     LogicalOperator::AtomicFact(Fact::new(alarm, FactValue::Boolean(true))),
+    ///     vec![Action::Call { callback: "notify".into(), arguments: vec![FactValue::Text("alarm raised".into())] }],
+    /// )?;
+    /// engine.forward_chaining()?;
+    ///
+    /// assert_eq!(*notifications.borrow(), vec![vec![FactValue::Text("alarm raised".into())]]);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn register_callback(&mut self, name: &str, callback: impl FnMut(&[FactValue]) + 'static) {
+        self.callbacks.insert(name.to_string(), Box::new(callback));
+    }
+
+    /// Registers a host function that arithmetic expressions can call through `Arithmetic::Call`.
+    ///
+    /// Functions are looked up when the expressions calling them are evaluated, so rules may call functions that are
+    /// registered after they are defined.
+    ///
+    /// # Arguments
+    /// * `name` - The name expressions refer to the function by. Registering a function under an existing name
+    ///   replaces it.
+    /// * `function` - The function applied to the values of the arguments of a call. Its errors, such as those it
+    ///   returns for arguments it does not accept, are raised by the evaluation of the comparison holding the call.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{FactValue, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// engine.register_function("hypot", |arguments| Ok(arguments.iter().map(|value| value * value).sum::<f64>().sqrt()));
+    /// engine.load_program("
+    ///     symbol Width: Float
+    ///     symbol Height: Float
+    ///     symbol Oversized: Boolean
+    ///     fact Width = 3.0
+    ///     fact Height = 4.0
+    ///     rule: hypot(Width, Height) >= 5 => Oversized
+    /// ")?;
+    /// engine.forward_chaining()?;
+    ///
+    /// assert!(engine.contains_fact(&engine.parse_fact("Oversized")?));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn register_function(&mut self, name: &str, function: impl Fn(&[f64]) -> Result<f64, EngineError> + 'static) {
+        self.functions.insert(name.to_string(), Box::new(function));
+    }
+
     /// Asserts a new fact into the engine's knowledge base.
     ///
     /// This method allows for the addition of a new fact, composed of a symbol and its associated value,
@@ -379,116 +885,456 @@ impl SymbolicReasoningEngine {
     ///
     /// # Examples
     /// ```
-    /// // Assuming an instance `engine` of `SymbolicReasoningEngine` and a symbol for "temperature"
-    /// //let temperature_symbol = engine.define_symbol("temperature", "Integer");
+    /// use symbolic_reasoning_engine::{EngineError, Fact, FactValue, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature_symbol = engine.define_symbol("temperature", SymbolType::Integer)?;
     /// // Assert the fact that the temperature is 25 degrees
-    /// //engine.assert_fact(temperature_symbol, FactValue::Integer(25));
+    /// engine.assert_fact(temperature_symbol.clone(), FactValue::Integer(25))?;
+    /// assert!(engine.contains_fact(&Fact::new(temperature_symbol.clone(), FactValue::Integer(25))));
+    ///
+    /// // Text cannot be stored under an integer symbol
+    /// let result = engine.assert_fact(temperature_symbol, FactValue::Text("Warm".into()));
+    /// assert!(matches!(result, Err(EngineError::TypeMismatch { .. })));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
+    /// Text values containing `${name}` variable references are interpolated as soon as all referenced variables are
+    /// bound and stable, and the result is converted to the symbol's type (e.g. `"${temp}"` becomes an `Integer` for an
+    /// integer symbol). References to unbound or unstable variables are kept verbatim and resolved during evaluation.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if the symbol has not been defined via `define_symbol`, and
+    /// `EngineError::TypeMismatch` if the value, after interpolation, does not satisfy the symbol's `SymbolType`.
+    ///
     /// By asserting facts, users of the engine can populate the knowledge base with relevant information
     /// required for logical evaluation and reasoning. This mechanism supports dynamic updates to the
     /// engine's understanding of the domain, reflecting changes in conditions or the discovery of new
     /// information.
-    fn assert_fact(&mut self, symbol: Symbol, value: FactValue) {
-        let fact = Fact { symbol: symbol.clone(), value: value.clone() };
+    pub fn assert_fact(&mut self, symbol: Symbol, value: FactValue) -> Result<(), EngineError> {
+        self.assert_predicate(symbol, Vec::new(), value)
+    }
+
+    /// Asserts a new fact about a predicate symbol applied to the given arguments.
+    ///
+    /// This is the general form of `assert_fact`: the fact `Temperature(room1) = 22` is asserted by passing the
+    /// `Temperature` predicate, the arguments `[Text("room1")]` and the value `Integer(22)`. Text arguments referencing
+    /// stable variables are interpolated and converted to the declared argument type, just like the value.
+    ///
+    /// Asserting a fact that is already known does not duplicate it, but makes it hold unconditionally: a fact inferred
+    /// by a rule and then asserted directly is no longer withdrawn when the facts it was inferred from are retracted.
+    ///
+    /// # Arguments
+    /// * `symbol` - The predicate `Symbol`, as returned by `define_predicate`.
+    /// * `arguments` - One `FactValue` per entry of the predicate's signature.
+    /// * `value` - The `FactValue` associated with the tuple of arguments.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if the predicate has not been defined, `EngineError::ArityMismatch` if the
+    /// number of arguments differs from the predicate's signature, `EngineError::ArgumentTypeMismatch` if an argument
+    /// does not satisfy its declared type, and `EngineError::TypeMismatch` if the value does not satisfy the
+    /// predicate's `SymbolType`. Returns `EngineError::UnboundVariable` if the fact contains a pattern variable, since
+    /// only ground facts can be asserted.
+    pub fn assert_predicate(&mut self, symbol: Symbol, arguments: Vec<FactValue>, value: FactValue) -> Result<(), EngineError> {
+        let fact = self.ground_fact(Fact { symbol, arguments, value })?;
+        self.add_fact(fact);
+        Ok(())
+    }
+
+    /// Retracts every known fact matching the given fact, withdrawing the derived facts that depend on them.
+    ///
+    /// Pattern variables in `fact` match any value, so `Temperature(room1) = ?any` retracts the temperature of `room1`
+    /// whatever it is. Facts inferred by rules keep track of the facts their rule matched, and are withdrawn once they
+    /// lose all their support, recursively: retracting `Sunny` withdraws `GoodForHiking` inferred from it, and in turn
+    /// the facts inferred from `GoodForHiking`. A derived fact still supported by another match, or asserted directly,
+    /// is kept.
+    ///
+    /// # Arguments
+    /// * `fact` - The fact, or fact pattern, to retract.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Fact>, EngineError>` - The facts removed from the knowledge base: the retracted facts followed by
+    ///   the withdrawn derived facts. The vector is empty if no known fact matches.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// let hiking = engine.define_symbol("Hiking", SymbolType::Boolean)?;
+    ///
+    /// let sunny = Fact::new(weather, FactValue::Text("Sunny".into()));
+    /// engine.assert_fact(sunny.symbol().clone(), sunny.value().clone())?;
+    /// engine.define_rule(LogicalOperator::AtomicFact(sunny.clone()), Fact::new(hiking.clone(), FactValue::Boolean(true)))?;
+    /// engine.forward_chaining()?;
+    ///
+    /// let removed = engine.retract_fact(&sunny)?;
+    /// assert_eq!(removed, vec![sunny, Fact::new(hiking, FactValue::Boolean(true))]);
+    /// assert_eq!(engine.facts().count(), 0);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// Withdrawn facts keep their justifications, and are restored without re-running the rules if the retracted facts
+    /// are asserted again. Conversely, retracting a fact restores the conclusions that required it to be absent.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if the fact's symbol has not been defined, and the schema errors of
+    /// `assert_predicate` if the fact does not satisfy the symbol's signature or type.
+    pub fn retract_fact(&mut self, fact: &Fact) -> Result<Vec<Fact>, EngineError> {
+        self.validate_symbol(&fact.symbol)?;
+        self.validate_fact_value(fact)?;
+
+        let matching: Vec<Fact> = self.facts.candidates(fact)
+            .filter(|known_fact| self.matches_pattern(fact, known_fact))
+            .cloned()
+            .collect();
+        let mut removed = self.remove_facts(&matching);
+        removed.extend(self.relabel(&matching));
+        Ok(removed)
+    }
 
-        if !self.should_resolve_immediately(&fact) {
-            self.add_fact(fact);
-            return;
+    /// Replaces the value of the known fact with the same symbol and arguments, withdrawing the derived facts that
+    /// depended on the previous value.
+    ///
+    /// The new fact is asserted, as by `assert_predicate`, even if no fact with the same symbol and arguments is known.
+    ///
+    /// # Arguments
+    /// * `fact` - The fact holding the new value.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Fact>, EngineError>` - The facts removed from the knowledge base: the previous facts with the same
+    ///   symbol and arguments followed by the withdrawn derived facts.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{ComparableValue, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+    /// let warm = engine.define_symbol("Warm", SymbolType::Boolean)?;
+    ///
+    /// engine.assert_fact(temperature.clone(), FactValue::Integer(25))?;
+    /// engine.define_rule(
+    ///     LogicalOperator::GreaterThan(
+    ///         Box::new(ComparableValue::Symbol(temperature.clone())),
+    ///         Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+    ///     ),
+    ///     Fact::new(warm.clone(), FactValue::Boolean(true)),
+    /// )?;
+    /// engine.forward_chaining()?;
+    /// assert!(engine.contains_fact(&Fact::new(warm.clone(), FactValue::Boolean(true))));
+    ///
+    /// engine.modify_fact(Fact::new(temperature.clone(), FactValue::Integer(12)))?;
+    /// assert!(engine.contains_fact(&Fact::new(temperature, FactValue::Integer(12))));
+    /// assert!(!engine.contains_fact(&Fact::new(warm, FactValue::Boolean(true))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns the same errors as `assert_predicate`.
+    pub fn modify_fact(&mut self, fact: Fact) -> Result<Vec<Fact>, EngineError> {
+        let fact = self.ground_fact(fact)?;
+
+        let previous: Vec<Fact> = self.facts.with_symbol(&fact.symbol.name)
+            .filter(|known_fact| known_fact.symbol == fact.symbol && known_fact.arguments == fact.arguments && **known_fact != fact)
+            .cloned()
+            .collect();
+        let mut removed = self.remove_facts(&previous);
+        self.tms.justify(Justification::premise(fact.clone()));
+        if !self.facts.contains(&fact) {
+            self.push_fact(fact.clone());
         }
+        let mut changed = previous;
+        changed.push(fact);
+        removed.extend(self.relabel(&changed));
+        Ok(removed)
+    }
 
-        match value {
-            FactValue::Text(fact_string) => {
-                // Extract variables from the fact string
-                let variables = self.extract_variables_from_fact(&fact);
-
-                // Mutable copy of the fact string to modify based on variable resolution
-                let mut resolved_fact_string = fact_string.to_owned();
-
-                // Iterate over variables to resolve them conditionally
-                for variable in variables {
-                    if self.is_variable_stable(&variable) {
-                        // Resolve the variable to its value
-                        let resolved_variable = self.resolve_variable_value(&variable, &self.variable_bindings);
-                        match resolved_variable {
-                            Some(var) => {
-                                let fact_value = var.value;
-                                match fact_value {
-                                    FactValue::Text(value) => {
-                                        // Replace the variable in the fact string with its value
-                                        resolved_fact_string = resolved_fact_string.replace(&format!("${{{}}}", fact_string), &value);
-                                    },
-                                    _ => {
-                                        continue;
-                                    }
-                                }
-                            },
-                            _ => {
-                                continue;
-                            }
-                        }
-                    }
-                    // If the variable is not stable, leave it as is for now
-                }
+    /// Interpolates stable variables into a fact about to be asserted and checks it against its symbol's schema.
+    ///
+    /// # Errors
+    /// Returns the errors documented on `assert_predicate`.
+    fn ground_fact(&self, mut fact: Fact) -> Result<Fact, EngineError> {
+        self.validate_symbol(&fact.symbol)?;
+
+        let has_variables = !self.extract_variables_from_fact(&fact).is_empty();
+        if has_variables && self.should_resolve_immediately(&fact) {
+            if let FactValue::Text(fact_string) = &fact.value {
+                // Interpolate stable variables and convert the result to the symbol's declared type
+                let resolved_fact_string = self.resolve_variables_in_context(fact_string, true);
+                fact.value = fact.symbol.symbol_type.coerce(&resolved_fact_string)
+                    .ok_or_else(|| EngineError::TypeMismatch {
+                        symbol: fact.symbol.name.clone(),
+                        expected: fact.symbol.symbol_type.clone(),
+                        found: FactValue::Text(resolved_fact_string),
+                    })?;
+            }
 
-                let interpolated_fact = Fact { symbol, value: FactValue::Text(resolved_fact_string) };
-                self.add_fact(interpolated_fact);
-            },
-            _ => {
-                self.add_fact(fact);
+            for (position, argument_type) in fact.symbol.signature.iter().enumerate() {
+                let Some(FactValue::Text(argument_string)) = fact.arguments.get(position) else {
+                    continue;
+                };
+                let resolved_argument_string = self.resolve_variables_in_context(argument_string, true);
+                fact.arguments[position] = argument_type.coerce(&resolved_argument_string)
+                    .ok_or_else(|| EngineError::ArgumentTypeMismatch {
+                        symbol: fact.symbol.name.clone(),
+                        position,
+                        expected: argument_type.clone(),
+                        found: FactValue::Text(resolved_argument_string),
+                    })?;
             }
         }
+
+        if let Some(name) = fact.variables().next() {
+            return Err(EngineError::UnboundVariable { name: name.to_string() });
+        }
+
+        self.validate_fact_value(&fact)?;
+        Ok(fact)
     }
 
+    /// Adds a fact holding unconditionally to the knowledge base, or marks it as such if it is already known.
+    ///
+    /// Facts whose justifications rely on the new fact, or require it to be absent, are relabelled accordingly.
     fn add_fact(&mut self, fact: Fact) {
-        self.facts.push(fact);
+        let has_dependents = self.tms.has_dependents(&fact, |pattern, known_fact| self.matches_pattern(pattern, known_fact));
+        self.tms.justify(Justification::premise(fact.clone()));
+        if !self.facts.contains(&fact) {
+            self.push_fact(fact.clone());
+        }
+        if has_dependents {
+            self.relabel(&[fact]);
+        }
     }
 
-    /// Check if all variables in the fact are already bound and meet criteria for immediate resolution
-    fn should_resolve_immediately(&mut self, fact: &Fact) -> bool {
-        let variables = self.extract_variables_from_fact(fact);
+    /// Appends a fact to the knowledge base, recording it for the match network.
+    fn push_fact(&mut self, fact: Fact) {
+        self.network.inserted(&fact);
+        self.facts.push(fact);
+    }
 
-        for var_name in variables {
-            if let Some(_) = self.variable_bindings.get(&var_name) {
-                if !self.is_variable_stable(&var_name) {
-                    return false;
-                }
-                // Additional checks for frequency of usage, strategic importance, etc., can be added here
-            } else {
-                // If any variable in the fact is unbound, defer resolution
-                return false;
-            }
+    /// Removes the given facts from the knowledge base, forgetting their justifications.
+    ///
+    /// # Returns
+    /// * `Vec<Fact>` - The removed facts, in knowledge base order. Derived facts depending on them are not withdrawn
+    ///   until the knowledge base is relabelled.
+    fn remove_facts(&mut self, facts: &[Fact]) -> Vec<Fact> {
+        let removed = self.facts.remove_all(facts);
+        for fact in facts {
+            self.tms.forget(fact);
         }
-
-        // If all variables are bound and meet the criteria, resolve immediately
-        true
+        for fact in &removed {
+            self.network.removed(fact);
+        }
+        removed
     }
 
-    // Helper function to extract variables from a fact
-    fn extract_variables_from_fact(&mut self, fact: &Fact) -> Vec<String> {
-        let mut variables = HashSet::new();
+    /// Recomputes the in/out label of the changed facts and of the facts depending on them, so that the knowledge base
+    /// holds exactly the facts with a valid justification.
+    ///
+    /// # Arguments
+    /// * `changed` - The facts asserted, retracted or justified since the knowledge base was last relabelled.
+    ///
+    /// # Returns
+    /// * `Vec<Fact>` - The facts withdrawn from the knowledge base, in knowledge base order.
+    fn relabel(&mut self, changed: &[Fact]) -> Vec<Fact> {
+        let (withdrawn, restored) = self.tms.relabel(&self.facts, changed, |pattern, known_fact| self.matches_pattern(pattern, known_fact));
+
+        // Facts coming back in are appended after the facts that stayed in
+        let withdrawn = self.facts.remove_all(&withdrawn);
+        for fact in &withdrawn {
+            self.network.removed(fact);
+        }
+        for fact in restored {
+            self.push_fact(fact);
+        }
+        withdrawn
+    }
 
-        match &fact.value {
-            FactValue::Text(string_to_interpolate) => {
-                let escaped_placeholder = "ESCAPED_VAR_PLACEHOLDER";
-                // Temporarily replace escaped variables with a placeholder
-                let escaped_string_to_interpolate = string_to_interpolate.replace("\\${", escaped_placeholder);
+    /// Returns the justifications currently supporting a fact.
+    ///
+    /// A fact asserted directly is supported by a premise justification, and a fact inferred by forward chaining by one
+    /// justification per match of a rule asserting it, recording the rule and the facts the match relied on. Only
+    /// valid justifications are returned, so the result is empty for facts that are not in the knowledge base,
+    /// including facts withdrawn because the facts they were inferred from were retracted.
+    ///
+    /// # Arguments
+    /// * `fact` - The fact to explain.
+    ///
+    /// # Returns
+    /// * `Vec<&Justification>` - The valid justifications of the fact, in the order they were recorded.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, Label, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let rain = engine.define_symbol("Rain", SymbolType::Boolean)?;
+    /// let wet = engine.define_symbol("WetGrass", SymbolType::Boolean)?;
+    ///
+    /// let raining = Fact::new(rain.clone(), FactValue::Boolean(true));
+    /// let wet_grass = Fact::new(wet, FactValue::Boolean(true));
+    /// engine.assert_fact(rain.clone(), FactValue::Boolean(true))?;
+    /// engine.define_rule(LogicalOperator::AtomicFact(raining.clone()), wet_grass.clone())?;
+    /// engine.forward_chaining_with_variables()?;
+    /// assert_eq!(engine.why(&wet_grass)[0].antecedents(), &[raining.clone()]);
+    ///
+    /// // Retracting the rain labels the wet grass out, and asserting it again brings it back in
+    /// engine.retract_fact(&raining)?;
+    /// assert!(engine.why(&wet_grass).is_empty());
+    /// assert_eq!(engine.label(&wet_grass), Some(Label::Out));
+    ///
+    /// engine.assert_fact(rain, FactValue::Boolean(true))?;
+    /// assert_eq!(engine.label(&wet_grass), Some(Label::In));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn why(&self, fact: &Fact) -> Vec<&Justification> {
+        if !self.facts.contains(fact) {
+            return Vec::new();
+        }
+        self.tms.justifications_of(fact)
+            .filter(|justification| self.tms.is_valid(justification, &self.facts, |pattern, known_fact| self.matches_pattern(pattern, known_fact)))
+            .collect()
+    }
 
-                // Use a HashSet to ensure uniqueness
-                let variable_regex = Regex::new(r"\$\{(\w+)}").unwrap(); // Adjust regex according to your variable naming conventions
+    /// Returns the truth maintenance label of a fact.
+    ///
+    /// # Returns
+    /// * `Option<Label>` - `Label::In` if the fact is in the knowledge base, `Label::Out` if it was asserted or inferred
+    ///   before but currently has no valid justification, and `None` if the engine has never known it or it was
+    ///   retracted.
+    pub fn label(&self, fact: &Fact) -> Option<Label> {
+        if self.facts.contains(fact) {
+            Some(Label::In)
+        } else if self.tms.is_node(fact) {
+            Some(Label::Out)
+        } else {
+            None
+        }
+    }
 
-                for cap in variable_regex.captures_iter(&escaped_string_to_interpolate) {
-                    if let Some(matched) = cap.get(1) { // Get the first capture group which is the variable name without the prefix
-                        variables.insert(matched.as_str().to_string());
-                    }
+    /// Checks that a fact's arguments and value satisfy the schema declared by its symbol's signature and `SymbolType`.
+    ///
+    /// Text values that still reference variables, and pattern variables, are accepted as-is, since they can only be
+    /// checked once the variables are resolved or bound during evaluation.
+    ///
+    /// # Errors
+    /// Returns `EngineError::ArityMismatch` if the fact has a different number of arguments than the symbol's
+    /// signature, `EngineError::ArgumentTypeMismatch` if an argument is not accepted by its declared type, and
+    /// `EngineError::TypeMismatch` if the value is not accepted by the symbol's type.
+    fn validate_fact_value(&self, fact: &Fact) -> Result<(), EngineError> {
+        if fact.arguments.len() != fact.symbol.arity() {
+            return Err(EngineError::ArityMismatch {
+                symbol: fact.symbol.name.clone(),
+                expected: fact.symbol.arity(),
+                found: fact.arguments.len(),
+            });
+        }
+
+        for (position, (argument_type, argument)) in fact.symbol.signature.iter().zip(&fact.arguments).enumerate() {
+            if !argument_type.accepts(argument) && !Self::is_deferred_value(argument) {
+                return Err(EngineError::ArgumentTypeMismatch {
+                    symbol: fact.symbol.name.clone(),
+                    position,
+                    expected: argument_type.clone(),
+                    found: argument.clone(),
+                });
+            }
+        }
+
+        if fact.symbol.symbol_type.accepts(&fact.value) || Self::is_deferred_value(&fact.value) {
+            return Ok(());
+        }
+
+        Err(EngineError::TypeMismatch {
+            symbol: fact.symbol.name.clone(),
+            expected: fact.symbol.symbol_type.clone(),
+            found: fact.value.clone(),
+        })
+    }
+
+    /// Checks whether a value is only known during evaluation, either because it is text holding at least one `${...}`
+    /// placeholder or because it is a pattern variable.
+    fn is_deferred_value(value: &FactValue) -> bool {
+        match value {
+            FactValue::Text(text) => template::has_placeholders(text),
+            FactValue::Variable(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Check if all variables in the fact are already bound and meet criteria for immediate resolution
+    fn should_resolve_immediately(&self, fact: &Fact) -> bool {
+        let variables = self.extract_variables_from_fact(fact);
+
+        for var_name in variables {
+            if self.variable_bindings.contains_key(&var_name) {
+                if !self.is_variable_stable(&var_name) {
+                    return false;
                 }
-            },
-            _ => {}
+                // Additional checks for frequency of usage, strategic importance, etc., can be added here
+            } else {
+                // If any variable in the fact is unbound, defer resolution
+                return false;
+            }
+        }
+
+        // If all variables are bound and meet the criteria, resolve immediately
+        true
+    }
+
+    // Helper function to extract variables from a fact's value and arguments
+    fn extract_variables_from_fact(&self, fact: &Fact) -> Vec<String> {
+        let mut variables = HashSet::new();
+
+        for value in fact.arguments.iter().chain(std::iter::once(&fact.value)) {
+            if let FactValue::Text(string_to_interpolate) = value {
+                variables.extend(Self::extract_variables_from_text(string_to_interpolate));
+            }
         }
 
         variables.into_iter().collect()
     }
 
+    // Helper function to extract the names of the variables referenced by a string
+    fn extract_variables_from_text(string_to_interpolate: &str) -> HashSet<String> {
+        let mut variables = HashSet::new();
+
+        let escaped_placeholder = "ESCAPED_VAR_PLACEHOLDER";
+        // Temporarily replace escaped variables with a placeholder
+        let escaped_string_to_interpolate = string_to_interpolate.replace("\\${", escaped_placeholder);
+
+        // Use a HashSet to ensure uniqueness
+        let variable_regex = Regex::new(r"\$\{(\w+)}").unwrap(); // Adjust regex according to your variable naming conventions
+
+        for cap in variable_regex.captures_iter(&escaped_string_to_interpolate) {
+            if let Some(matched) = cap.get(1) { // Get the first capture group which is the variable name without the prefix
+                variables.insert(matched.as_str().to_string());
+            }
+        }
+
+        variables
+    }
+
+    /// Returns a copy of the fact with variables in its text value and arguments interpolated.
+    ///
+    /// # Arguments
+    /// * `fact` - The `Fact` to interpolate, typically from a rule premise or a goal.
+    /// * `check_stable` - Whether unstable variables are left uninterpolated, as in `resolve_variables_in_context`.
+    fn interpolate_fact(&self, fact: &Fact, check_stable: bool) -> Fact {
+        let interpolate = |value: &FactValue| match value {
+            FactValue::Text(text) => FactValue::Text(self.resolve_variables_in_context(text, check_stable)),
+            _ => value.clone(),
+        };
+
+        Fact {
+            symbol: fact.symbol.clone(),
+            arguments: fact.arguments.iter().map(interpolate).collect(),
+            value: interpolate(&fact.value),
+        }
+    }
+
     // Helper function to check if a variable is considered stable
     fn is_variable_stable(&self, variable_name: &str) -> bool {
         match self.variable_bindings.get(variable_name) {
@@ -497,21 +1343,6 @@ impl SymbolicReasoningEngine {
         }
     }
 
-    /// A placeholder method to determine or retrieve the value of a variable.
-    /// This will involve looking up the value from a data source,
-    /// user input, or another mechanism based on the application's requirements.
-    ///
-    /// # Arguments
-    /// * `var_name` - The name of the variable whose value is to be determined.
-    ///
-    /// # Returns
-    /// The determined `FactValue` for the variable.
-    fn determine_variable_value(&self, var_name: &str) -> Option<&Variable> {
-        // Placeholder implementation
-        // The actual implementation would depend on how variable values are provided or determined
-        self.variable_bindings.get(var_name)
-    }
-
     /// Resolves variables in the given input string based on the current knowledge base.
     ///
     /// This method searches the input string for variables, identified by the syntax `${varName}`, and attempts to replace them
@@ -550,6 +1381,7 @@ impl SymbolicReasoningEngine {
                         FactValue::Float(val) => val.to_string(),
                         FactValue::Boolean(val) => val.to_string(),
                         FactValue::Text(val) => val.clone(),
+                        FactValue::Variable(name) => format!("?{}", name),
                     };
                     // Replace the variable in the string with its value
                     resolved_string = resolved_string.replace(&format!("${{{}}}", var_name), &replacement);
@@ -577,30 +1409,715 @@ impl SymbolicReasoningEngine {
     ///   AND, OR, and NOT operations, potentially nested to represent intricate logical relationships.
     /// * `conclusion` - A `Fact` that specifies the outcome or assertion to be made when the premise of the rule
     ///   evaluates to true. This fact is added to the knowledge base, representing new information derived from
-    ///   applying the rule.
+    ///   applying the rule. The conclusion is a template instantiated for every match of the premise: pattern
+    ///   variables are replaced by their bound values, and text values and arguments may hold `${...}` placeholders
+    ///   that are interpolated from the match's bindings, such as `"${city} is warm"`, or computed from them, such as
+    ///   `"${t * 9 / 5 + 32}"`. A value made only of placeholders is converted to the declared type once rendered.
     ///
     /// # Examples
     /// ```
-    /// // Assuming an instance `engine` of `SymbolicReasoningEngine`
+    /// use symbolic_reasoning_engine::{ComparableValue, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
     /// // Define symbols and facts as prerequisites
-    /// //let temperature_symbol = engine.define_symbol("temperature", "Integer");
-    /// //let hot_weather_symbol = engine.define_symbol("weather", "Text");
+    /// let temperature_symbol = engine.define_symbol("temperature", SymbolType::Integer)?;
+    /// let hot_weather_symbol = engine.define_symbol("weather", SymbolType::Text)?;
     ///
     /// // Define a rule stating that if the temperature is greater than 30 degrees, it is considered hot weather
-    /// //engine.define_rule(
-    /// //    LogicalOperator::GreaterThan(
-    /// //        Fact::new(temperature_symbol.clone(), FactValue::Integer(30))
-    /// //    ),
-    /// //    Fact::new(hot_weather_symbol.clone(), FactValue::Text(String::from("Hot")))
-    /// //);
+    /// engine.define_rule(
+    ///     LogicalOperator::GreaterThan(
+    ///         Box::new(ComparableValue::Symbol(temperature_symbol.clone())),
+    ///         Box::new(ComparableValue::Direct(FactValue::Integer(30)))
+    ///     ),
+    ///     Fact::new(hot_weather_symbol.clone(), FactValue::Text(String::from("Hot")))
+    /// )?;
+    /// assert_eq!(engine.rules().len(), 1);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
     /// This example demonstrates defining a rule that interprets high temperature as an indication of hot weather.
     /// Such rules enable the engine to reason about the domain, making logical inferences that enrich its understanding
     /// and guide its decision-making processes.
-    fn define_rule(&mut self, premise: LogicalOperator, conclusion: Fact) {
-        let rule = Rule { premise, conclusion };
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if the conclusion, or any fact or `ComparableValue::Symbol` within the
+    /// premise, refers to a symbol that has not been defined. `ComparableValue::SymbolName` references are resolved
+    /// lazily during evaluation and are therefore not checked for existence here.
+    ///
+    /// Returns `EngineError::TypeMismatch` if the conclusion or a fact within the premise holds a value that does not
+    /// satisfy its symbol's `SymbolType`, or a comparison tests an enumerated symbol against a value it can never hold.
+    /// Returns `EngineError::IncompatibleComparison` if a comparison's operands have types that the operator cannot
+    /// compare, such as ordering text or testing a boolean for equality with a number, and
+    /// `EngineError::NonNumericOperand` if an arithmetic expression within a comparison has a non-numeric operand, or
+    /// `EngineError::MissingOperand` if it takes the `min` or `max` of no operand.
+    ///
+    /// Returns `EngineError::InvalidTemplate` if a `${...}` placeholder in the conclusion is malformed.
+    ///
+    /// Returns `EngineError::UnboundVariable` if the conclusion uses a pattern variable that is not bound by any fact
+    /// the premise requires to hold. Facts under `LogicalOperator::Not` do not bind variables, since they only hold
+    /// when no matching fact exists, and neither do the alternatives of a `LogicalOperator::Or` unless every one of
+    /// them binds the variable. For the same reason, the rule is rejected if a comparison uses a pattern variable that
+    /// is neither bound by a fact before it in the premise nor an engine variable. Pattern variables appearing only
+    /// under `LogicalOperator::Not` are allowed, and match any value.
+    ///
+    /// Rules whose premise negates their own conclusions, directly or through other rules, are accepted, but cannot
+    /// be forward chained; see `strata`.
+    pub fn define_rule(&mut self, premise: LogicalOperator, conclusion: Fact) -> Result<(), EngineError> {
+        self.define_rule_with_actions(premise, vec![Action::Assert(conclusion)])
+    }
+
+    /// Defines and adds a new rule whose consequent is a list of actions.
+    ///
+    /// This is the general form of `define_rule`, for rules that assert several facts, retract or modify others, or
+    /// notify the host application through callbacks when they fire. The actions are executed in order, every time
+    /// the premise is satisfied, with their facts and arguments instantiated from the bindings of the match.
+    ///
+    /// # Arguments
+    /// * `premise` - A `LogicalOperator` that represents the condition or set of conditions that must be satisfied
+    ///   for the rule to be applied.
+    /// * `actions` - The `Action`s to execute when the premise evaluates to true.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Action, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let door = engine.define_symbol("Door", SymbolType::Text)?;
+    /// let alarm = engine.define_symbol("Alarm", SymbolType::Boolean)?;
+    /// let armed = engine.define_symbol("Armed", SymbolType::Boolean)?;
+    ///
+    /// engine.assert_fact(door.clone(), FactValue::Text("Open".into()))?;
+    /// engine.assert_fact(armed.clone(), FactValue::Boolean(true))?;
+    /// engine.define_rule_with_actions(
+    ///     LogicalOperator::AtomicFact(Fact::new(door, FactValue::Text("Open".into()))),
+    ///     vec![
+    ///         Action::Assert(Fact::new(alarm.clone(), FactValue::Boolean(true))),
+    ///         Action::Modify(Fact::new(armed.clone(), FactValue::Boolean(false))),
+    ///     ],
+    /// )?;
+    /// engine.forward_chaining()?;
+    ///
+    /// assert!(engine.contains_fact(&Fact::new(alarm, FactValue::Boolean(true))));
+    /// assert!(engine.contains_fact(&Fact::new(armed.clone(), FactValue::Boolean(false))));
+    /// assert!(!engine.contains_fact(&Fact::new(armed, FactValue::Boolean(true))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns the same errors as `define_rule` for the premise and for the facts of `Assert`, `Retract` and `Modify`
+    /// actions, except that pattern variables of a `Retract` action need not be bound by the premise. Returns
+    /// `EngineError::UnboundVariable` if an argument of a `Call` action uses a pattern variable the premise does not
+    /// bind. Callbacks are looked up when the rule fires, so they may be registered after the rule is defined.
+    pub fn define_rule_with_actions(&mut self, premise: LogicalOperator, actions: Vec<Action>) -> Result<(), EngineError> {
+        self.add_rule(Rule::with_actions(premise, actions))
+    }
+
+    /// Adds a rule to the engine, keeping its name and salience.
+    ///
+    /// # Arguments
+    /// * `rule` - The `Rule` to add, such as one parsed from the rule language.
+    ///
+    /// # Errors
+    /// Returns the same errors as `define_rule_with_actions` for the rule's premise and actions.
+    pub fn add_rule(&mut self, rule: Rule) -> Result<(), EngineError> {
+        self.validate_expression(&rule.premise)?;
+
+        // Every pattern variable asserted by the rule must be bound by a fact the premise requires to hold
+        let mut bound_variables = self.variable_bindings.keys().cloned().collect();
+        Self::check_safety(&rule.premise, &mut bound_variables)?;
+        let check_bound = |name: &str| match bound_variables.contains(name) {
+            true => Ok(()),
+            false => Err(EngineError::UnboundVariable { name: name.to_string() }),
+        };
+
+        for action in &rule.actions {
+            match action {
+                Action::Assert(fact) | Action::Modify(fact) => {
+                    self.validate_conclusion(fact)?;
+                    fact.variables().try_for_each(check_bound)?;
+                },
+                Action::Retract(fact) => self.validate_conclusion(fact)?,
+                Action::Call { arguments, .. } => {
+                    for argument in arguments {
+                        match argument {
+                            FactValue::Variable(name) => check_bound(name)?,
+                            FactValue::Text(text) => template::validate(text)?,
+                            _ => {},
+                        }
+                    }
+                },
+            }
+        }
+
         self.rules.push(rule);
+        self.network.add_rule();
+        // Strata are computed for every rule at once, when they are next needed
+        self.strata = None;
+        Ok(())
+    }
+
+    /// Parses a program of the rule language and defines its symbols, binds its variables, asserts its facts and adds
+    /// its rules, in order.
+    ///
+    /// Symbols used by the program may be defined by the engine or by the program itself. See `Program` for the
+    /// syntax of the language.
+    ///
+    /// # Arguments
+    /// * `source` - The text of the program.
+    ///
+    /// # Returns
+    /// * `Result<Program, EngineError>` - The program that was loaded.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// engine.load_program(r#"
+    ///     symbol Weather: Text
+    ///     symbol Temperature: Float
+    ///     symbol Activity: Text
+    ///
+    ///     fact Weather = "Sunny"
+    ///     fact Temperature = 24
+    ///     rule hiking: Weather == "Sunny" and Temperature >= 20 => Activity = "Hiking"
+    /// "#)?;
+    /// engine.forward_chaining()?;
+    ///
+    /// let activity = engine.symbol("Activity").unwrap().clone();
+    /// assert!(engine.contains_fact(&Fact::new(activity, FactValue::Text("Hiking".into()))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Syntax` if the program does not parse, in which case nothing is loaded. Errors raised
+    /// while resolving names or loading a statement are wrapped in `EngineError::Located` with the position of the
+    /// offending name or statement, and the statements before it are kept.
+    pub fn load_program(&mut self, source: &str) -> Result<Program, EngineError> {
+        let program = parser::parse(source, &self.symbols)?;
+        for (statement, position) in &program.statements {
+            self.load_statement(statement.clone()).map_err(|error| position.locate(error))?;
+        }
+        Ok(program)
+    }
+
+    /// Parses a fact or fact pattern written as in a `fact` statement of the rule language, resolving its symbol among
+    /// the engine's symbols. The fact is not asserted.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let activity = engine.define_symbol("Activity", SymbolType::Text)?;
+    /// assert_eq!(engine.parse_fact("Activity = ?what")?, Fact::new(activity, FactValue::Variable("what".into())));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Syntax` if the text is not a single fact, and `EngineError::Located` wrapping the errors
+    /// raised while resolving its symbol.
+    pub fn parse_fact(&self, source: &str) -> Result<Fact, EngineError> {
+        parser::parse_fact(source, &self.symbols)
+    }
+
+    fn load_statement(&mut self, statement: Statement) -> Result<(), EngineError> {
+        match statement {
+            Statement::Symbol(symbol) => self.define_predicate(&symbol.name, symbol.signature, symbol.symbol_type).map(drop),
+            Statement::Variable(variable) => {
+                self.assert_variable(&variable);
+                Ok(())
+            },
+            Statement::Fact(fact) => self.assert_predicate(fact.symbol, fact.arguments, fact.value),
+            Statement::Rule(rule) => self.add_rule(rule),
+        }
+    }
+
+    /// Returns the knowledge base as a program of the rule language, which loads into an empty engine as a copy of
+    /// this one.
+    ///
+    /// The program defines the engine's symbols sorted by name, binds its variables sorted by name, asserts every
+    /// fact of the knowledge base in assertion order, and defines the rules in definition order. Inferred facts are
+    /// asserted like any other, so the copy does not retract them with their justifications. Registered callbacks,
+    /// the agenda and the conflict resolution strategy are not part of the program.
+    ///
+    /// # Returns
+    /// * `Program` - The program, whose `Display` output is the rule language text.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{FactValue, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// engine.assert_fact(weather, FactValue::Text("Sunny".into()))?;
+    /// assert_eq!(engine.program().to_string(), "symbol Weather: Text\nfact Weather = \"Sunny\"\n");
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn program(&self) -> Program {
+        let mut symbols: Vec<&Symbol> = self.symbols.values().collect();
+        symbols.sort_by(|left, right| left.name.cmp(&right.name));
+        let mut variables: Vec<&Variable> = self.variable_bindings.values().collect();
+        variables.sort_by(|left, right| left.name.cmp(&right.name));
+
+        Program::from_statements(symbols.into_iter().map(|symbol| Statement::Symbol(symbol.clone()))
+            .chain(variables.into_iter().map(|variable| Statement::Variable(variable.clone())))
+            .chain(self.facts.iter().map(|fact| Statement::Fact(fact.clone())))
+            .chain(self.rules.iter().map(|rule| Statement::Rule(rule.clone()))))
+    }
+
+    /// Saves the knowledge base to a file in the versioned format described by `FORMAT_VERSION`.
+    ///
+    /// The file holds the engine's `program`, preceded by a header naming the format version. It round-trips every
+    /// symbol type, fact value, variable state and rule, so that `load_from` restores an engine with equal symbols,
+    /// variables, facts and rules.
+    ///
+    /// # Arguments
+    /// * `path` - The file to write, which is replaced if it exists.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{FactValue, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let level = engine.define_symbol("Level", SymbolType::Integer)?;
+    /// engine.assert_fact(level, FactValue::Integer(3))?;
+    ///
+    /// let path = std::env::temp_dir().join("save_to_example.kb");
+    /// engine.save_to(&path)?;
+    /// let loaded = SymbolicReasoningEngine::load_from(&path)?;
+    /// assert_eq!(loaded.facts().collect::<Vec<_>>(), engine.facts().collect::<Vec<_>>());
+    /// # std::fs::remove_file(path).unwrap();
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Io` if the file cannot be written.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        persistence::write(path.as_ref(), &self.program().to_string())
+    }
+
+    /// Loads an engine from a knowledge base file saved by `save_to`.
+    ///
+    /// # Arguments
+    /// * `path` - The file to read.
+    ///
+    /// # Returns
+    /// * `Result<Self, EngineError>` - A new engine holding the saved symbols, variables, facts and rules.
+    ///
+    /// # Errors
+    /// Returns `EngineError::Io` if the file cannot be read, `EngineError::UnsupportedFormat` if it was saved in
+    /// another format version, and the errors of `load_program` if its content does not load, with positions counted
+    /// in the file.
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        Self::load_from_with_migration(path, |version, _| Err(EngineError::UnsupportedFormat { version }))
+    }
+
+    /// Loads an engine from a knowledge base file, migrating files saved in another format version.
+    ///
+    /// Files in the current format load as with `load_from`. Otherwise the migration hook receives the version found
+    /// in the file's header and the whole file, and returns the source in the current format to load instead, header
+    /// included or not.
+    ///
+    /// # Arguments
+    /// * `path` - The file to read.
+    /// * `migrate` - The migration hook, which may fail with `EngineError::UnsupportedFormat` for versions it does
+    ///   not know.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{EngineError, SymbolicReasoningEngine};
+    ///
+    /// // A hypothetical format 0 spelled "predicate" where format 1 spells "symbol"
+    /// let path = std::env::temp_dir().join("load_from_with_migration_example.kb");
+    /// std::fs::write(&path, "# symbolic_reasoning_engine knowledge base, format 0\npredicate Level: Integer\n").unwrap();
+    ///
+    /// let engine = SymbolicReasoningEngine::load_from_with_migration(&path, |version, source| match version {
+    ///     0 => Ok(source.replace("predicate ", "symbol ")),
+    ///     version => Err(EngineError::UnsupportedFormat { version }),
+    /// })?;
+    /// assert!(engine.symbol("Level").is_some());
+    /// # std::fs::remove_file(path).unwrap();
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Io` if the file cannot be read, `EngineError::Syntax` if it does not start with a format
+    /// header, the errors of the migration hook, and the errors of `load_program` if the source does not load.
+    pub fn load_from_with_migration(path: impl AsRef<Path>, migrate: impl FnOnce(u32, String) -> Result<String, EngineError>) -> Result<Self, EngineError> {
+        let source = persistence::read(path.as_ref(), migrate)?;
+        let mut engine = Self::new();
+        engine.load_program(&source)?;
+        Ok(engine)
+    }
+
+    /// Loads a JSON knowledge base into the engine.
+    ///
+    /// The knowledge base is an object with optional `symbols`, `variables`, `facts` and `rules` arrays, loaded in
+    /// that order whatever order they are written in, so that facts and rules may use the symbols it defines as well
+    /// as those of the engine. Symbols, facts and rules use the encodings of `Symbol::to_json`, `Fact::to_json` and
+    /// `Rule::to_json`, and variables are objects with a `name`, a `value` and an optional `state` of `"Stable"` or
+    /// `"Unstable"`. Fact values may be written as plain JSON scalars, as described by `FactValue::from_json`, and
+    /// integers are accepted where floats are expected.
+    ///
+    /// # Arguments
+    /// * `source` - The JSON text of the knowledge base.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// engine.load_json(r#"{
+    ///     "symbols": [
+    ///         { "name": "Temperature", "symbol_type": "Float" },
+    ///         { "name": "Hot", "symbol_type": "Boolean" }
+    ///     ],
+    ///     "facts": [{ "symbol": "Temperature", "value": 31 }],
+    ///     "rules": [{
+    ///         "premise": { "GreaterThan": [{ "Symbol": "Temperature" }, { "Direct": 30 }] },
+    ///         "actions": [{ "Assert": { "symbol": "Hot" } }]
+    ///     }]
+    /// }"#)?;
+    /// engine.forward_chaining()?;
+    ///
+    /// let hot = engine.symbol("Hot").unwrap().clone();
+    /// assert!(engine.contains_fact(&Fact::new(hot, FactValue::Boolean(true))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Syntax` if the text is not valid JSON and `EngineError::InvalidJson` if a value does not
+    /// have the structure of its encoding, in which case nothing is loaded. Errors raised while resolving names or
+    /// loading an element are wrapped in `EngineError::JsonPath` with the path of the offending value, such as
+    /// `rules[3].premise.And[1]`, and the elements before it are kept.
+    pub fn load_json(&mut self, source: &str) -> Result<(), EngineError> {
+        let statements = json::Decoder::new(&self.symbols).knowledge_base(&json::parse(source)?)?;
+        for (statement, path) in statements {
+            self.load_statement(statement).map_err(|error| error.at_path(&path))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the knowledge base as JSON, which `load_json` loads into an empty engine as a copy of this one.
+    ///
+    /// The knowledge base holds the same symbols, variables, facts and rules as `program`, in the same order.
+    pub fn to_json(&self) -> serde_json::Value {
+        json::knowledge_base(&self.program())
+    }
+
+    /// Returns the facts of the knowledge base that were inferred by rules rather than asserted, as a JSON array in
+    /// assertion order.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde_json::json;
+    /// use symbolic_reasoning_engine::SymbolicReasoningEngine;
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// engine.load_program("symbol Rain: Boolean\nsymbol Wet: Boolean\nfact Rain\nrule: Rain => Wet")?;
+    /// engine.forward_chaining()?;
+    /// assert_eq!(engine.derived_facts_to_json(), json!([{ "symbol": "Wet", "value": { "Boolean": true } }]));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn derived_facts_to_json(&self) -> serde_json::Value {
+        self.facts.iter()
+            .filter(|fact| {
+                let justifications = self.why(fact);
+                !justifications.is_empty() && justifications.iter().all(|justification| !justification.is_premise())
+            })
+            .map(Fact::to_json)
+            .collect()
+    }
+
+    /// Decodes a fact from its JSON encoding, resolving its symbol among the engine's symbols.
+    ///
+    /// # Errors
+    /// Returns `EngineError::InvalidJson` if the JSON does not encode a fact, and `EngineError::JsonPath` wrapping
+    /// `EngineError::UnknownSymbol` or `EngineError::ArityMismatch` if its symbol is not defined or takes a different
+    /// number of arguments.
+    pub fn fact_from_json(&self, value: &serde_json::Value) -> Result<Fact, EngineError> {
+        json::Decoder::new(&self.symbols).fact(value, "")
+    }
+
+    /// Decodes an expression from its JSON encoding, resolving the symbols of its facts and comparisons among the
+    /// engine's symbols.
+    ///
+    /// # Errors
+    /// Returns `EngineError::InvalidJson` if the JSON does not encode an expression, and `EngineError::JsonPath`
+    /// wrapping the errors raised by the value at the given path, such as `And[1].AtomicFact.symbol`.
+    pub fn expression_from_json(&self, value: &serde_json::Value) -> Result<LogicalOperator, EngineError> {
+        json::Decoder::new(&self.symbols).expression(value, "")
+    }
+
+    /// Decodes a rule from its JSON encoding, resolving the symbols of its premise and actions among the engine's
+    /// symbols. The rule is not added to the engine.
+    ///
+    /// # Examples
+    /// ```
+    /// use serde_json::json;
+    /// use symbolic_reasoning_engine::{EngineError, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// engine.define_symbol("Rain", SymbolType::Boolean)?;
+    /// let rule = engine.rule_from_json(&json!({
+    ///     "name": "umbrella",
+    ///     "premise": { "AtomicFact": { "symbol": "Rain" } },
+    ///     "actions": [{ "Call": { "callback": "notify", "arguments": ["Take an umbrella"] } }]
+    /// }))?;
+    /// assert_eq!(rule.to_string(), "rule umbrella: Rain => call notify(\"Take an umbrella\")");
+    ///
+    /// let error = engine.rule_from_json(&json!({ "premise": { "And": [{ "Xor": [] }] }, "actions": [] })).unwrap_err();
+    /// assert_eq!(error.to_string(), "invalid JSON at premise.And[0]: unknown expression 'Xor'");
+    /// # Ok::<(), EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::InvalidJson` if the JSON does not encode a rule, and `EngineError::JsonPath` wrapping the
+    /// errors raised by the value at the given path.
+    pub fn rule_from_json(&self, value: &serde_json::Value) -> Result<Rule, EngineError> {
+        json::Decoder::new(&self.symbols).rule(value, "")
+    }
+
+    /// Returns the indices of the rules in each stratum of the predicate dependency graph, in evaluation order.
+    ///
+    /// Every symbol asserted or modified by a rule depends on the symbols its premise reads: positively through fact
+    /// patterns, and negatively through `LogicalOperator::Not` and comparisons, whose outcome may change as facts are
+    /// added. Strata order the rules so that every symbol a rule reads negatively is complete, that is no rule of the
+    /// same or a later stratum concludes it, before the rule is evaluated. Forward chaining and Datalog evaluation
+    /// fire the rules stratum by stratum, which gives `LogicalOperator::Not` a well-defined meaning independent of the
+    /// order in which rules were defined.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Vec<usize>>, EngineError>` - The non-empty strata, each listing its rules in definition order.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let cloudy = engine.define_symbol("Cloudy", SymbolType::Boolean)?;
+    /// let rain = engine.define_symbol("Rain", SymbolType::Boolean)?;
+    /// let dry = engine.define_symbol("Dry", SymbolType::Boolean)?;
+    /// let fact = |symbol| Fact::new(symbol, FactValue::Boolean(true));
+    ///
+    /// // Dry weather negates rain, so it is only concluded once rain can no longer be derived
+    /// engine.define_rule(LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(fact(rain.clone())))), fact(dry.clone()))?;
+    /// engine.define_rule(LogicalOperator::AtomicFact(fact(cloudy.clone())), fact(rain.clone()))?;
+    /// assert_eq!(engine.strata()?, vec![vec![1], vec![0]]);
+    ///
+    /// engine.assert_fact(cloudy, FactValue::Boolean(true))?;
+    /// engine.forward_chaining()?;
+    /// assert!(engine.contains_fact(&fact(rain)));
+    /// assert!(!engine.contains_fact(&fact(dry)));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if a rule depends through negation or a comparison on its own
+    /// conclusions, in which case the rules have no stratification.
+    pub fn strata(&self) -> Result<Vec<Vec<usize>>, EngineError> {
+        let strata = match &self.strata {
+            Some(strata) => strata.clone(),
+            None => DependencyGraph::new(&self.rules).rule_strata(&self.rules)?,
+        };
+        let mut grouped: Vec<Vec<usize>> = vec![Vec::new(); strata.iter().max().map_or(0, |stratum| stratum + 1)];
+        for (rule, stratum) in strata.into_iter().enumerate() {
+            grouped[stratum].push(rule);
+        }
+        grouped.retain(|rules| !rules.is_empty());
+        Ok(grouped)
+    }
+
+    /// Verifies that a fact asserted, retracted or modified by a rule refers to a defined symbol, satisfies its schema
+    /// and holds well-formed templates.
+    fn validate_conclusion(&self, conclusion: &Fact) -> Result<(), EngineError> {
+        self.validate_symbol(&conclusion.symbol)?;
+        self.validate_fact_value(conclusion)?;
+        for term in conclusion.arguments.iter().chain(std::iter::once(&conclusion.value)) {
+            if let FactValue::Text(text) = term {
+                template::validate(text)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a logical expression is safe, collecting the pattern variables bound by the facts it requires to
+    /// hold.
+    ///
+    /// A conjunction binds the variables bound by any of its conditions, from left to right, a disjunction the
+    /// variables bound by every alternative, and a negation none. Comparisons may only use variables bound before them.
+    ///
+    /// # Arguments
+    /// * `expression` - The `LogicalOperator` to inspect, typically a rule premise.
+    /// * `bound_variables` - The variables bound so far, to which the variables bound by the expression are added.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnboundVariable` if a comparison uses a variable that is not bound.
+    fn check_safety(expression: &LogicalOperator, bound_variables: &mut HashSet<String>) -> Result<(), EngineError> {
+        match expression {
+            LogicalOperator::And(expressions) => {
+                expressions.iter().try_for_each(|expr| Self::check_safety(expr, bound_variables))?;
+            },
+            LogicalOperator::Or(expressions) => {
+                let mut bound_by_all: Option<HashSet<String>> = None;
+                for expr in expressions {
+                    let mut bound = bound_variables.clone();
+                    Self::check_safety(expr, &mut bound)?;
+                    bound_by_all = Some(match bound_by_all {
+                        Some(previous) => previous.intersection(&bound).cloned().collect(),
+                        None => bound,
+                    });
+                }
+                bound_variables.extend(bound_by_all.unwrap_or_default());
+            },
+            LogicalOperator::Not(expression) => Self::check_safety(expression, &mut bound_variables.clone())?,
+            LogicalOperator::AtomicFact(fact) => bound_variables.extend(fact.variables().map(str::to_string)),
+            LogicalOperator::GreaterThan(left, right)
+            | LogicalOperator::LessThan(left, right)
+            | LogicalOperator::EqualTo(left, right)
+            | LogicalOperator::NotEqualTo(left, right)
+            | LogicalOperator::GreaterThanOrEqualTo(left, right)
+            | LogicalOperator::LessThanOrEqualTo(left, right) => {
+                for value in [left, right].into_iter().flat_map(|value| value.leaves()) {
+                    if let ComparableValue::Direct(FactValue::Variable(name)) = value {
+                        if !bound_variables.contains(name) {
+                            return Err(EngineError::UnboundVariable { name: name.clone() });
+                        }
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Recursively verifies that every symbol referenced by a logical expression has been defined, and that facts and
+    /// comparisons within it are consistent with the declared symbol types.
+    ///
+    /// # Arguments
+    /// * `expression` - The `LogicalOperator` to validate, typically a rule premise.
+    ///
+    /// # Errors
+    /// Returns the first `EngineError::UnknownSymbol`, `EngineError::TypeMismatch`,
+    /// `EngineError::IncompatibleComparison`, `EngineError::NonNumericOperand` or `EngineError::MissingOperand`
+    /// encountered.
+    fn validate_expression(&self, expression: &LogicalOperator) -> Result<(), EngineError> {
+        match expression {
+            LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => {
+                expressions.iter().try_for_each(|expr| self.validate_expression(expr))
+            },
+            LogicalOperator::Not(expression) => self.validate_expression(expression),
+            LogicalOperator::AtomicFact(fact) => {
+                self.validate_symbol(&fact.symbol)?;
+                self.validate_fact_value(fact)
+            },
+            LogicalOperator::GreaterThan(left, right) => self.validate_comparison(">", left, right),
+            LogicalOperator::LessThan(left, right) => self.validate_comparison("<", left, right),
+            LogicalOperator::EqualTo(left, right) => self.validate_comparison("==", left, right),
+            LogicalOperator::NotEqualTo(left, right) => self.validate_comparison("!=", left, right),
+            LogicalOperator::GreaterThanOrEqualTo(left, right) => self.validate_comparison(">=", left, right),
+            LogicalOperator::LessThanOrEqualTo(left, right) => self.validate_comparison("<=", left, right),
+        }
+    }
+
+    /// Verifies that the operands of a comparison can be compared with the given operator.
+    ///
+    /// Ordering operators require both operands to be numeric, while equality operators require operands of
+    /// compatible types. The operands of arithmetic expressions must be numeric whatever the operator. Operands whose
+    /// type is only known at evaluation time, such as text referencing variables or `ComparableValue::SymbolName`
+    /// references to symbols that are not yet defined, are checked during evaluation.
+    ///
+    /// # Arguments
+    /// * `operator` - The textual form of the comparison operator, used for error reporting.
+    /// * `left` - The left operand of the comparison.
+    /// * `right` - The right operand of the comparison.
+    fn validate_comparison(&self, operator: &str, left: &ComparableValue, right: &ComparableValue) -> Result<(), EngineError> {
+        // Arithmetic expressions read the symbols among their operands
+        for value in [left, right].into_iter().flat_map(ComparableValue::leaves) {
+            if let ComparableValue::Symbol(symbol) = value {
+                self.validate_symbol(symbol)?;
+            }
+            // Comparisons resolve the value of a plain symbol, which predicates do not have without arguments
+            if let Some(symbol) = self.comparable_symbol(value).filter(|symbol| symbol.arity() > 0) {
+                return Err(EngineError::ArityMismatch { symbol: symbol.name.clone(), expected: symbol.arity(), found: 0 });
+            }
+        }
+
+        // Arithmetic evaluates every operand as a number, whatever the comparison
+        for value in [left, right] {
+            if let ComparableValue::Arithmetic(expression) = value {
+                self.validate_arithmetic(expression)?;
+            }
+        }
+
+        let (Some(left_type), Some(right_type)) = (self.comparable_type(left), self.comparable_type(right)) else {
+            return Ok(());
+        };
+
+        let is_equality = matches!(operator, "==" | "!=");
+        let compatible = if is_equality {
+            left_type.is_comparable_with(&right_type)
+        } else {
+            left_type.is_numeric() && right_type.is_numeric()
+        };
+        if !compatible {
+            return Err(EngineError::IncompatibleComparison { operator: operator.to_string(), left: left_type, right: right_type });
+        }
+
+        // Reject equality tests against values an enumerated symbol can never hold
+        if is_equality {
+            for (reference, other) in [(left, right), (right, left)] {
+                if let (Some(symbol), ComparableValue::Direct(value)) = (self.comparable_symbol(reference), other) {
+                    let fact = Fact::new(symbol.clone(), value.clone());
+                    if matches!(symbol.symbol_type, SymbolType::Enum(_)) {
+                        self.validate_fact_value(&fact)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the operands of an arithmetic expression, and of the expressions nested in it, are numeric.
+    ///
+    /// # Errors
+    /// Returns `EngineError::NonNumericOperand` naming the innermost operator applied to the first operand whose type
+    /// is known not to be numeric, and `EngineError::MissingOperand` if a `min` or `max` has no operand.
+    fn validate_arithmetic(&self, expression: &Arithmetic) -> Result<(), EngineError> {
+        if matches!(expression, Arithmetic::Min(operands) | Arithmetic::Max(operands) if operands.is_empty()) {
+            return Err(EngineError::MissingOperand { operator: expression.operator().to_string() });
+        }
+        for operand in expression.operands() {
+            match operand {
+                ComparableValue::Arithmetic(nested) => self.validate_arithmetic(nested)?,
+                leaf => {
+                    if let Some(operand_type) = self.comparable_type(leaf).filter(|operand_type| !operand_type.is_numeric()) {
+                        return Err(EngineError::NonNumericOperand { operator: expression.operator().to_string(), operand: operand_type });
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the symbol a comparison operand refers to, if it refers to a defined symbol.
+    fn comparable_symbol<'a>(&'a self, value: &'a ComparableValue) -> Option<&'a Symbol> {
+        match value {
+            ComparableValue::Symbol(symbol) => Some(symbol),
+            ComparableValue::SymbolName(symbol_name) => self.symbols.get(symbol_name),
+            ComparableValue::Direct(_) | ComparableValue::Arithmetic(_) => None,
+        }
+    }
+
+    /// Determines the type of a comparison operand, if it can be known before evaluation.
+    fn comparable_type(&self, value: &ComparableValue) -> Option<SymbolType> {
+        match value {
+            ComparableValue::Direct(FactValue::Text(text)) if text.contains("${") => None,
+            ComparableValue::Direct(fact_value) => SymbolType::of_value(fact_value),
+            ComparableValue::Arithmetic(_) => Some(SymbolType::Float),
+            _ => self.comparable_symbol(value).map(|symbol| symbol.symbol_type.clone()),
+        }
     }
 
     /// Retrieves a reference to a `Fact` from the knowledge base using a given symbol.
@@ -618,39 +2135,43 @@ impl SymbolicReasoningEngine {
     /// This method is essential for the rule engine's ability to dynamically access facts in the knowledge base using symbols.
     /// It facilitates the translation of symbolic references into concrete data, enabling the evaluation of rules and logical expressions
     /// that depend on the current state of the knowledge base.
-    fn get_fact_from_symbol(&self, symbol: Symbol) -> Option<&Fact> {
-        self.facts.iter().find_map(|known_fact| {
-            if known_fact.symbol == symbol {
-                Some(known_fact)
-            } else {
-                None
-            }
-        })
+    fn get_fact_from_symbol(&self, symbol: &Symbol) -> Option<&Fact> {
+        self.facts.with_symbol(&symbol.name).find(|known_fact| &known_fact.symbol == symbol && known_fact.arguments.is_empty())
     }
 
-    /// Retrieves the value associated with a given fact from the knowledge base.
+    /// Converts the value of a known fact to its numerical representation for comparison purposes.
     ///
     /// # Arguments
-    /// * `fact` - A reference to the `Fact` whose value is to be retrieved.
+    /// * `fact` - A reference to the known `Fact` whose value is to be converted.
     ///
     /// # Returns
-    /// * An `Option<f64>` representing the numerical value of the fact for comparison purposes.
-    ///   Returns `None` if the fact is not found or if its value cannot be represented as a number.
-    fn get_comparable_fact_value(&self, fact: &Fact) -> Option<f64> {
-        self.facts.iter().find_map(|known_fact| {
-            if known_fact.symbol == fact.symbol {
-                match known_fact.value.clone() {
-                    FactValue::Integer(value) => Some(value as f64),
-                    FactValue::Float(value) => Some(value),
-                    FactValue::Text(value) => {
-                        let interpolated_string = self.resolve_variables_in_context(&value, true);
-                        Some(f64::from_str(&interpolated_string).unwrap_or(0f64))
-                    },
-                    // Handle other FactValue variants that can be represented as a number or return None
-                    _ => None,
-                }
-            } else {
-                None
+    /// * A `Result<f64, EngineError>` holding the numerical value of the fact. Text values are interpolated with
+    ///   stable variables and parsed as numbers.
+    ///
+    /// # Errors
+    /// Returns `EngineError::TypeMismatch` if the fact's symbol has a numeric `SymbolType` but holds a value that cannot
+    /// be represented as a number, and `EngineError::NonNumericComparison` for any other non-numeric value.
+    fn get_comparable_fact_value(&self, fact: &Fact) -> Result<f64, EngineError> {
+        let numeric = match &fact.value {
+            FactValue::Integer(value) => Some(*value as f64),
+            FactValue::Float(value) => Some(*value),
+            FactValue::Text(value) => {
+                let interpolated_string = self.resolve_variables_in_context(value, true);
+                f64::from_str(&interpolated_string).ok()
+            },
+            FactValue::Boolean(_) | FactValue::Variable(_) => None,
+        };
+
+        numeric.ok_or_else(|| if fact.symbol.symbol_type.is_numeric() {
+            EngineError::TypeMismatch {
+                symbol: fact.symbol.name.clone(),
+                expected: fact.symbol.symbol_type.clone(),
+                found: fact.value.clone(),
+            }
+        } else {
+            EngineError::NonNumericComparison {
+                symbol: Some(fact.symbol.name.clone()),
+                value: fact.value.clone(),
             }
         })
     }
@@ -669,118 +2190,50 @@ impl SymbolicReasoningEngine {
     ///   expression forms the premise of a rule and can include nested logical structures for complex condition evaluation.
     ///
     /// # Returns
-    /// * `bool` - A boolean value indicating whether the premise is true (`true`) or false (`false`) given the current
-    ///   state of the engine's knowledge base and variable bindings.
+    /// * `Result<bool, EngineError>` - A boolean value indicating whether the premise is true (`true`) or false (`false`)
+    ///   given the current state of the engine's knowledge base and variable bindings.
+    ///
+    /// Pattern variables are existentially quantified: a premise such as `Sunny(?city)` is true if any known fact
+    /// unifies with it, and variables shared between conjuncts must be bound to the same value in each of them.
+    ///
+    /// # Errors
+    /// Returns an `EngineError` if a comparison within the expression cannot be resolved, for example because it
+    /// references an undefined symbol, a symbol without a known fact, a non-numeric value, or an unbound pattern
+    /// variable.
     ///
     /// # Examples
     /// ```
-    /// // Assuming an instance `engine` of `SymbolicReasoningEngine`
-    /// // and previously defined symbols and variable bindings
+    /// use symbolic_reasoning_engine::{ComparableValue, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather_symbol = engine.define_symbol("weather", SymbolType::Text)?;
+    /// let temperature_symbol = engine.define_symbol("temperature", SymbolType::Integer)?;
+    /// engine.assert_fact(weather_symbol.clone(), FactValue::Text(String::from("Sunny")))?;
+    /// engine.assert_fact(temperature_symbol.clone(), FactValue::Integer(28))?;
     ///
     /// // Define a logical expression as a premise
-    /// //let expression = LogicalOperator::And(vec![
-    /// //    LogicalOperator::Fact(Fact::new(weather_symbol, FactValue::Text(String::from("Sunny")))),
-    /// //    LogicalOperator::GreaterThan(
-    /// //        Fact::new(temperature_symbol, FactValue::Integer(25))
-    /// //    )
-    /// //]);
+    /// let expression = LogicalOperator::And(vec![
+    ///     LogicalOperator::AtomicFact(Fact::new(weather_symbol, FactValue::Text(String::from("Sunny")))),
+    ///     LogicalOperator::GreaterThan(
+    ///         Box::new(ComparableValue::Symbol(temperature_symbol)),
+    ///         Box::new(ComparableValue::Direct(FactValue::Integer(25))),
+    ///     ),
+    /// ]);
     ///
     /// // Evaluate the expression to see if the conditions are met
-    /// //let is_true = engine.is_premise_true(&expression);
-    /// //assert_eq!(is_true, true); // Assumes the knowledge base supports this evaluation
+    /// assert!(engine.is_premise_true(&expression)?);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
     /// The `is_premise_true` method facilitates dynamic and flexible rule evaluation within the engine, supporting
     /// a wide range of logical operations and conditions. Its ability to accurately assess premises enables the engine
     /// to perform sophisticated reasoning and inference, adapting to changes in the knowledge base and variable states.
-    fn is_premise_true(&self, expression: &LogicalOperator) -> bool {
-        self.print_debug(&format!("Starting evaluation of expression: {:?}", expression));
-        let result = match expression {
-            LogicalOperator::And(expressions) => {
-                // AND: All expressions must be true
-                expressions.iter().all(|expr| {
-                    let res = self.is_premise_true(expr);
-                    self.print_debug(&format!("AND expr: {:?}, result: {}", expr, res));
-                    res
-                })
-            },
-            LogicalOperator::Or(expressions) => {
-                // OR: At least one expression must be true
-                expressions.iter().any(|expr| {
-                    let res = self.is_premise_true(expr);
-                    self.print_debug(&format!("OR expr: {:?}, result: {}", expr, res));
-                    res
-                })
-            },
-            LogicalOperator::Not(expression) => {
-                // NOT: The expression must not be true
-                let res = !self.is_premise_true(expression);
-                self.print_debug(&format!("NOT expr: {:?}, result: {}", expression, res));
-                res
-            },
-            LogicalOperator::AtomicFact(fact) => {
-                match fact.value.clone() {
-                    FactValue::Text(fact_value) => {
-                        let interpolated_value = self.resolve_variables_in_context(&fact_value, true);
-                        let interpolated_fact_value = FactValue::Text(interpolated_value);
-                        let interpolated_fact = Fact { symbol: fact.symbol.clone(), value: interpolated_fact_value };
-                        // Directly evaluate the fact against the known facts
-                        let res = self.facts.contains(&interpolated_fact);
-                        self.print_debug(&format!("Fact evaluation: {:?}, result: {}", interpolated_fact, res));
-                        res
-                    },
-                    _ => {
-                        let res = self.facts.contains(&fact);
-                        self.print_debug(&format!("Fact evaluation: {:?}, result: {}", fact, res));
-                        res
-                    }
-                }
-            },
-            LogicalOperator::GreaterThan(left, right) => {
-                if self.compare_values(left, right, |a, b| a > b) {
-                    true
-                } else {
-                    false
-                }
-            },
-            LogicalOperator::LessThan(left, right) => {
-                if self.compare_values(left, right, |a, b| a < b) {
-                    true
-                } else {
-                    false
-                }
-            },
-            LogicalOperator::EqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a == b) {
-                    true
-                } else {
-                    false
-                }
-            },
-            LogicalOperator::NotEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a != b) {
-                    true
-                } else {
-                    false
-                }
-            },
-            LogicalOperator::GreaterThanOrEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a >= b) {
-                    true
-                } else {
-                    false
-                }
-            },
-            LogicalOperator::LessThanOrEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a <= b) {
-                    true
-                } else {
-                    false
-                }
-            },
-        };
-        self.print_debug(&format!("Expression evaluation completed: {:?}, result: {}", expression, result));
-        result
+    pub fn is_premise_true(&self, expression: &LogicalOperator) -> Result<bool, EngineError> {
+        self.print_debug(&format!("Starting evaluation of expression: {}", expression));
+        let solutions = self.evaluate_logical_expression(expression, &Solution::new(self.variable_bindings.clone()), None)?;
+        let result = !solutions.is_empty();
+        self.print_debug(&format!("Expression evaluation completed: {}, result: {}", expression, result));
+        Ok(result)
     }
 
     /// Executes the forward chaining inference process over the current set of rules and facts.
@@ -791,6 +2244,9 @@ impl SymbolicReasoningEngine {
     /// is found to be true, the conclusion of the rule is asserted as a new fact, potentially triggering further inferences
     /// as the knowledge base is updated with new information.
     ///
+    /// Rules whose premise contains pattern variables fire once per distinct set of bindings, executing their actions
+    /// with the bound values substituted for the variables.
+    ///
     /// This process continues until no new facts can be inferred from the current set of rules, indicating a stable state
     /// where all applicable rules have been exhausted. Forward chaining is particularly effective in systems where the
     /// knowledge base is incrementally expanded through the application of rules, facilitating dynamic reasoning based on
@@ -802,29 +2258,60 @@ impl SymbolicReasoningEngine {
     /// the relevant rules and variables.
     ///
     /// ```
-    /// //let mut engine = SymbolicReasoningEngine::new();
-    /// // Assuming the engine has been populated with facts, rules, and variables
-    /// //engine.forward_chaining();
-    /// // After calling forward_chaining, the knowledge base may be expanded with new inferred facts.
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let rain = engine.define_symbol("Rain", SymbolType::Boolean)?;
+    /// let wet_grass = engine.define_symbol("WetGrass", SymbolType::Boolean)?;
+    /// let slippery = engine.define_symbol("Slippery", SymbolType::Boolean)?;
+    /// engine.assert_fact(rain.clone(), FactValue::Boolean(true))?;
+    /// engine.define_rule(
+    ///     LogicalOperator::AtomicFact(Fact::new(rain, FactValue::Boolean(true))),
+    ///     Fact::new(wet_grass.clone(), FactValue::Boolean(true)),
+    /// )?;
+    /// engine.define_rule(
+    ///     LogicalOperator::AtomicFact(Fact::new(wet_grass, FactValue::Boolean(true))),
+    ///     Fact::new(slippery.clone(), FactValue::Boolean(true)),
+    /// )?;
+    ///
+    /// // After calling forward_chaining, the knowledge base is expanded with the facts inferred in chain
+    /// engine.forward_chaining()?;
+    /// assert!(engine.contains_fact(&Fact::new(slippery, FactValue::Boolean(true))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
     /// # Note
     /// The effectiveness and efficiency of forward chaining can depend on the complexity and number of rules, the size
     /// of the knowledge base, and the specificity of rule premises. It is important to design rules with clear and
     /// relevant premises to ensure productive and meaningful inferences.
-    fn forward_chaining(&mut self) {
-        let mut new_facts = Vec::new();
-
-        for rule in &self.rules {
-            if self.is_premise_true(&rule.premise) {
-                if !self.facts.contains(&rule.conclusion) {
-                    new_facts.push(rule.conclusion.clone());
-                }
+    ///
+    /// Rules fire stratum by stratum, as listed by `strata`. Every activation of a stratum on the agenda fires once, in
+    /// agenda order, all of them having been instantiated before the first one fires. The agenda is then brought up to
+    /// date again, and the stratum keeps firing the activations its own conclusions enabled until it has none left.
+    /// Only then are the activations of the next stratum fired, so that the facts they negate are settled. Refraction
+    /// keeps a match that already fired from firing again in later calls, until one of the facts it relied on is
+    /// retracted and asserted again.
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if the rules cannot be stratified, and `EngineError::RuleEvaluation`
+    /// wrapping the underlying error if any rule's premise cannot be evaluated or its actions cannot be instantiated,
+    /// for example because they call a callback that has not been registered. No action of the stratum being
+    /// instantiated is executed when an error occurs, but the actions of earlier strata are kept.
+    pub fn forward_chaining(&mut self) -> Result<(), EngineError> {
+        let mut minimum = 0;
+        loop {
+            self.update_agenda()?;
+            let Some(stratum) = self.agenda.next_stratum(minimum) else {
+                return Ok(());
+            };
+
+            // Execute the actions of every activation of the stratum once all of them have been instantiated
+            for activation in self.agenda.take_stratum(stratum) {
+                self.apply_actions(activation);
             }
+            // Stay on the stratum, its conclusions may have enabled further activations of its own rules
+            minimum = stratum;
         }
-
-        // Add all new facts to the knowledge base
-        self.facts.extend(new_facts);
     }
 
     /// Executes the forward chaining inference process, considering both static facts and dynamic variables.
@@ -841,15 +2328,35 @@ impl SymbolicReasoningEngine {
     /// process is repeated until no new facts can be inferred, ensuring that the knowledge base reflects all derivable
     /// information given the initial facts, rules, and variable states.
     ///
+    /// Activations fire one at a time, in agenda order, and the agenda is brought up to date with the knowledge base
+    /// after each of them, so that a rule with a higher salience can enable or disable the activations of the rules
+    /// after it. Refraction ensures that each match of a rule fires at most once, so that rules retracting facts or
+    /// notifying callbacks do not fire repeatedly.
+    ///
     /// # Usage
     /// Like `forward_chaining`, this method operates on the engine's internal state and does not require arguments. It
     /// should be invoked after initializing the knowledge base, rules, and variable bindings to drive the inference process.
     ///
     /// ```
-    /// //let mut engine = SymbolicReasoningEngine::new();
-    /// // Populate the engine with initial facts, rules, and variable bindings
-    /// //engine.forward_chaining_with_variables();
-    /// // The knowledge base may now include new facts inferred considering both static facts and variable states.
+    /// use symbolic_reasoning_engine::{ComparableValue, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine, Variable, VariableState};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+    /// let heating = engine.define_symbol("Heating", SymbolType::Text)?;
+    /// engine.assert_fact(temperature.clone(), FactValue::Integer(16))?;
+    /// engine.assert_variable(&Variable::new("comfort", FactValue::Integer(19), VariableState::Stable));
+    /// engine.define_rule(
+    ///     LogicalOperator::LessThan(
+    ///         Box::new(ComparableValue::Symbol(temperature)),
+    ///         Box::new(ComparableValue::Direct(FactValue::Variable("comfort".into()))),
+    ///     ),
+    ///     Fact::new(heating.clone(), FactValue::Text("On below ${comfort} degrees".into())),
+    /// )?;
+    ///
+    /// // The knowledge base now includes the facts inferred considering both static facts and variable states
+    /// engine.forward_chaining_with_variables()?;
+    /// assert!(engine.contains_fact(&Fact::new(heating, FactValue::Text("On below 19 degrees".into()))));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
     /// ```
     ///
     /// # Note
@@ -858,79 +2365,503 @@ impl SymbolicReasoningEngine {
     /// accurate and meaningful inferences. Designing rules and premises that effectively leverage variables can
     /// significantly enhance the engine's reasoning capabilities, enabling it to adapt to a wider range of dynamic
     /// conditions and scenarios.
-    fn forward_chaining_with_variables(&mut self) {
-        let mut new_facts_added = true;
-
-        while new_facts_added {
-            new_facts_added = false;
-            let mut conclusions_to_add = Vec::new();
-
-            for rule in self.rules.clone() {
-                let new_fact = self.apply_rule_conclusion(&rule.conclusion);
-                match self.match_rule(&rule.premise) {
-                    Some(bindings) => {
-                        self.variable_bindings.extend(bindings);
-                        conclusions_to_add.push(new_fact);
-                    },
-                    _ => {}
-                };
-            }
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` wrapping the underlying error if any rule's premise cannot be evaluated or
+    /// its actions cannot be instantiated, and `EngineError::Unstratifiable` if the rules cannot be stratified.
+    /// Activations that fired before the error are kept.
+    pub fn forward_chaining_with_variables(&mut self) -> Result<(), EngineError> {
+        while self.fire_next()?.is_some() {}
+        Ok(())
+    }
+
+    /// Brings the agenda up to date with the knowledge base and fires its first activation.
+    ///
+    /// This executes a single step of `forward_chaining_with_variables`, so that the agenda can be inspected through
+    /// `agenda` and reordered through `move_activation` between firings.
+    ///
+    /// # Returns
+    /// * `Result<Option<Activation>, EngineError>` - The activation that fired, or `None` if the agenda is empty.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if a rule's premise cannot be evaluated or its actions cannot be
+    /// instantiated, in which case no activation fires.
+    pub fn fire_next(&mut self) -> Result<Option<Activation>, EngineError> {
+        self.update_agenda()?;
+        let Some(activation) = self.agenda.pop() else {
+            return Ok(None);
+        };
+        self.apply_actions(activation.clone());
+        Ok(Some(activation))
+    }
+
+    /// Returns the activations waiting to fire, in firing order, after bringing the agenda up to date with the
+    /// knowledge base.
+    ///
+    /// Activations of rules in earlier strata come first, then those of rules with a higher salience, followed by the
+    /// order set by the conflict resolution strategy. Each activation holds the instantiated actions of a rule for the
+    /// matches of its premise that have not fired yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{ConflictStrategy, Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let alarm = engine.define_symbol("Alarm", SymbolType::Boolean)?;
+    /// let action = engine.define_symbol("Action", SymbolType::Text)?;
+    /// let raised = LogicalOperator::AtomicFact(Fact::new(alarm.clone(), FactValue::Boolean(true)));
+    ///
+    /// engine.define_rule(raised.clone(), Fact::new(action.clone(), FactValue::Text("Log".into())))?;
+    /// engine.define_rule(raised, Fact::new(action.clone(), FactValue::Text("Evacuate".into())))?;
+    /// engine.set_salience(1, 10)?;
+    /// engine.assert_fact(alarm, FactValue::Boolean(true))?;
+    ///
+    /// // The rule with the higher salience fires first, unless the agenda is reordered
+    /// let rules: Vec<usize> = engine.agenda()?.iter().map(|activation| activation.rule()).collect();
+    /// assert_eq!(rules, vec![1, 0]);
+    /// engine.move_activation(1, 0)?;
+    /// assert_eq!(engine.fire_next()?.map(|activation| activation.rule()), Some(0));
+    ///
+    /// engine.set_conflict_strategy(ConflictStrategy::Recency);
+    /// engine.forward_chaining_with_variables()?;
+    /// assert!(engine.agenda()?.is_empty());
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if a rule's premise cannot be evaluated or its actions cannot be
+    /// instantiated.
+    pub fn agenda(&mut self) -> Result<&[Activation], EngineError> {
+        self.update_agenda()?;
+        Ok(self.agenda.activations())
+    }
+
+    /// Moves the activation at index `from` of the agenda to index `to`, shifting the activations in between.
+    ///
+    /// Indices refer to the agenda as last returned by `agenda`. The activation keeps its new place when the agenda is
+    /// brought up to date, until the conflict resolution strategy or its rule's salience changes, or a rule is defined.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownActivation` if either index is past the end of the agenda.
+    pub fn move_activation(&mut self, from: usize, to: usize) -> Result<(), EngineError> {
+        self.agenda.move_activation(from, to)
+    }
+
+    /// Sets the strategy ordering the activations of rules with the same salience, reordering the whole agenda.
+    pub fn set_conflict_strategy(&mut self, strategy: ConflictStrategy) {
+        self.agenda.set_strategy(strategy);
+    }
 
-            for new_fact in conclusions_to_add {
-                if !self.facts.contains(&new_fact) {
-                    self.facts.push(new_fact);
-                    new_facts_added = true;
+    /// Returns the strategy ordering the activations of rules with the same salience.
+    pub fn conflict_strategy(&self) -> ConflictStrategy {
+        self.agenda.strategy()
+    }
+
+    /// Sets the salience of a rule, so that its activations fire before those of rules with a lower salience.
+    ///
+    /// # Arguments
+    /// * `rule` - The index of the rule in definition order.
+    /// * `salience` - The new priority of the rule; rules default to 0.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownRule` if no rule has the given index.
+    pub fn set_salience(&mut self, rule: usize, salience: i32) -> Result<(), EngineError> {
+        let Some(defined) = self.rules.get_mut(rule) else {
+            return Err(EngineError::UnknownRule { rule });
+        };
+        defined.salience = salience;
+        self.agenda.set_salience(rule, salience);
+        Ok(())
+    }
+
+    /// Derives every fact following from the rules through stratified, semi-naive Datalog evaluation.
+    ///
+    /// This evaluation mode computes the minimal model of the rules and the knowledge base, and is meant for recursive
+    /// rules such as transitive closures. Rules are split into strata along the predicate dependency graph, so that
+    /// the facts a rule negates or compares are all derived in earlier strata before it is evaluated. Within a stratum,
+    /// rules are first matched against the whole knowledge base, and each following round only looks for derivations
+    /// relying on a fact derived in the previous round, until a round derives nothing new.
+    ///
+    /// Evaluation terminates because Datalog rules only assert facts built from values of known facts: rules that
+    /// retract or modify facts, call callbacks, or compute values through `${...}` templates are rejected.
+    ///
+    /// Derived facts are justified like facts inferred by forward chaining, so `why` lists every derivation found.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Fact>, EngineError>` - The facts added to the knowledge base, in the order they were derived.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let edge = engine.define_predicate("Edge", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    /// let path = engine.define_predicate("Path", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    /// let var = |name: &str| FactValue::Variable(name.into());
+    /// let atom = |symbol, from: &str, to: &str| LogicalOperator::AtomicFact(Fact::with_arguments(symbol, vec![var(from), var(to)], FactValue::Boolean(true)));
+    ///
+    /// engine.define_rule(atom(edge.clone(), "x", "y"), Fact::with_arguments(path.clone(), vec![var("x"), var("y")], FactValue::Boolean(true)))?;
+    /// engine.define_rule(
+    ///     LogicalOperator::And(vec![atom(path.clone(), "x", "y"), atom(edge.clone(), "y", "z")]),
+    ///     Fact::with_arguments(path.clone(), vec![var("x"), var("z")], FactValue::Boolean(true)),
+    /// )?;
+    /// for (from, to) in [("a", "b"), ("b", "c"), ("c", "a")] {
+    ///     engine.assert_predicate(edge.clone(), vec![FactValue::Text(from.into()), FactValue::Text(to.into())], FactValue::Boolean(true))?;
+    /// }
+    ///
+    /// // Every node of the cycle reaches every node, including itself
+    /// assert_eq!(engine.evaluate_datalog()?.len(), 9);
+    /// assert!(engine.evaluate_datalog()?.is_empty());
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if a rule depends through negation or a comparison on its own
+    /// conclusions, and `EngineError::RuleEvaluation` wrapping `EngineError::NonDatalogRule` if a rule is not a Datalog
+    /// rule, in which cases nothing is derived. Errors raised while evaluating a rule are wrapped in
+    /// `EngineError::RuleEvaluation`, and the facts derived before them are kept.
+    pub fn evaluate_datalog(&mut self) -> Result<Vec<Fact>, EngineError> {
+        let strata = self.rule_strata()?.to_vec();
+        let mut clauses: Vec<(usize, Clause)> = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            clauses.extend(Clause::from_rule(index, rule)?.into_iter().map(|clause| (strata[index], clause)));
+        }
+        clauses.sort_by_key(|(stratum, _)| *stratum);
+
+        let mut derived = Vec::new();
+        let mut changed = Vec::new();
+        for stratum in clauses.chunk_by(|(left, _), (right, _)| left == right) {
+            let mut delta: Option<Vec<Fact>> = None;
+            loop {
+                let mut derivations = Vec::new();
+                for (_, clause) in stratum {
+                    let mut derive = |restricted: Option<(usize, &[Fact])>| -> Result<(), EngineError> {
+                        let found = clause.derive(self, restricted).map_err(|error| error.in_rule(clause.rule))?;
+                        derivations.extend(found.into_iter().map(|(fact, solution)| (clause.rule, fact, solution)));
+                        Ok(())
+                    };
+                    match &delta {
+                        // The first round matches every rule against the whole knowledge base
+                        None => derive(None)?,
+                        Some(delta) => for (position, pattern) in clause.patterns() {
+                            let facts: Vec<Fact> = delta.iter().filter(|fact| fact.symbol == pattern.symbol).cloned().collect();
+                            if !facts.is_empty() {
+                                derive(Some((position, &facts)))?;
+                            }
+                        },
+                    }
                 }
+
+                let mut new_facts = Vec::new();
+                for (rule, fact, solution) in derivations {
+                    if !self.facts.contains(&fact) {
+                        if self.tms.has_dependents(&fact, |pattern, known_fact| self.matches_pattern(pattern, known_fact)) {
+                            changed.push(fact.clone());
+                        }
+                        self.push_fact(fact.clone());
+                        new_facts.push(fact.clone());
+                    }
+                    self.tms.justify(Justification::inferred(rule, solution.antecedents, solution.exclusions, fact));
+                }
+                if new_facts.is_empty() {
+                    break;
+                }
+                derived.extend(new_facts.iter().cloned());
+                delta = Some(new_facts);
             }
         }
+
+        if !changed.is_empty() {
+            self.relabel(&changed);
+        }
+        Ok(derived)
+    }
+
+    /// Enumerates the stable models, or answer sets, of the rules over the knowledge base.
+    ///
+    /// Unlike stratified evaluation, stable models give a meaning to rules that negate each other's conclusions, such
+    /// as a rule choosing one option unless another is chosen and a rule choosing the other option unless the first
+    /// is. A set of facts is a stable model if it is exactly the set of facts derived from the knowledge base by the
+    /// rules whose negations and comparisons hold in it: every fact of a stable model has a derivation that does not
+    /// rely on the model's own facts being absent. Programs without negation through recursion have a single stable
+    /// model, the one `evaluate_datalog` derives, while other programs may have several, or none.
+    ///
+    /// The knowledge base is left unchanged. Rules are restricted as for `evaluate_datalog`, and the search may take
+    /// time exponential in the number of negations and comparisons the ground rules depend on.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Vec<Fact>>, EngineError>` - The facts each stable model adds to the knowledge base, in the order
+    ///   they are derived.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let tea = engine.define_symbol("Tea", SymbolType::Boolean)?;
+    /// let coffee = engine.define_symbol("Coffee", SymbolType::Boolean)?;
+    /// let fact = |symbol| Fact::new(symbol, FactValue::Boolean(true));
+    /// let unless = |symbol| LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(fact(symbol))));
+    ///
+    /// // Choose tea unless coffee is chosen, and coffee unless tea is chosen
+    /// engine.define_rule(unless(coffee.clone()), fact(tea.clone()))?;
+    /// engine.define_rule(unless(tea.clone()), fact(coffee.clone()))?;
+    /// assert_eq!(engine.stable_models()?, vec![vec![fact(tea)], vec![fact(coffee)]]);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `EngineError::NoStableModel` if the rules have no stable model, and `EngineError::RuleEvaluation`
+    /// wrapping `EngineError::NonDatalogRule` if a rule is not a Datalog rule. Errors raised while evaluating a rule are
+    /// wrapped in `EngineError::RuleEvaluation`.
+    pub fn stable_models(&self) -> Result<Vec<Vec<Fact>>, EngineError> {
+        let models = Solver::new(self)?.stable_models()?;
+        match models.is_empty() {
+            true => Err(EngineError::NoStableModel),
+            false => Ok(models),
+        }
+    }
+
+    /// Executes the instantiated actions of one activation of a rule.
+    ///
+    /// Facts asserted by the activation are justified by the facts its matches relied on and required to be absent.
+    /// Facts retracted or modified by it are removed along with the derived facts that depended on them, and modified
+    /// facts hold unconditionally afterwards, as if they had been asserted directly.
+    fn apply_actions(&mut self, activation: Activation) {
+        let mut changed = Vec::new();
+
+        for action in &activation.actions {
+            match action {
+                Action::Assert(fact) => {
+                    if self.tms.has_dependents(fact, |pattern, known_fact| self.matches_pattern(pattern, known_fact)) {
+                        changed.push(fact.clone());
+                    }
+                    if !self.facts.contains(fact) {
+                        self.push_fact(fact.clone());
+                    }
+                },
+                Action::Retract(pattern) => {
+                    let matching: Vec<Fact> = self.facts.candidates(pattern)
+                        .filter(|known_fact| self.matches_pattern(pattern, known_fact))
+                        .cloned()
+                        .collect();
+                    if !self.remove_facts(&matching).is_empty() {
+                        changed.extend(matching);
+                    }
+                },
+                Action::Modify(fact) => {
+                    let previous: Vec<Fact> = self.facts.with_symbol(&fact.symbol.name)
+                        .filter(|known_fact| known_fact.symbol == fact.symbol && known_fact.arguments == fact.arguments && *known_fact != fact)
+                        .cloned()
+                        .collect();
+                    if !self.remove_facts(&previous).is_empty() {
+                        changed.extend(previous);
+                    }
+                    if self.tms.has_dependents(fact, |pattern, known_fact| self.matches_pattern(pattern, known_fact)) {
+                        changed.push(fact.clone());
+                    }
+                    self.tms.justify(Justification::premise(fact.clone()));
+                    if !self.facts.contains(fact) {
+                        self.push_fact(fact.clone());
+                    }
+                },
+                Action::Call { callback, arguments } => {
+                    if let Some(callback) = self.callbacks.get_mut(callback) {
+                        callback(arguments);
+                    }
+                },
+            }
+        }
+
+        self.justify_conclusions(&activation);
+        if !changed.is_empty() {
+            self.relabel(&changed);
+        }
+    }
+
+    /// Records the justifications of the known facts asserted by an activation, one per match.
+    fn justify_conclusions(&mut self, activation: &Activation) {
+        for action in &activation.actions {
+            let Action::Assert(fact) = action else {
+                continue;
+            };
+            if !self.facts.contains(fact) {
+                continue;
+            }
+            for solution in &activation.matches {
+                let justification = Justification::inferred(activation.rule, solution.antecedents.clone(), solution.exclusions.clone(), fact.clone());
+                self.tms.justify(justification);
+            }
+        }
+    }
+
+    /// Brings the match network up to date with the knowledge base and replaces the activations of the rules whose
+    /// matches changed with the ones for their current matches.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if a rule's premise cannot be evaluated or its actions cannot be
+    /// instantiated. The agenda is left unchanged, and the network is rebuilt on the next update.
+    fn update_agenda(&mut self) -> Result<(), EngineError> {
+        self.rule_strata()?;
+        let mut network = std::mem::take(&mut self.network);
+        let updated = network.update(self);
+        self.network = network;
+        updated?;
+
+        // A rule whose new matches cannot be instantiated keeps its changes, to be taken by the next update
+        for index in self.network.changed() {
+            let changes = self.network.changes(index);
+            let stratum = self.strata.as_deref().unwrap_or_default()[index];
+            let activations = self.rule_activations(index, stratum, &changes.added).map_err(|error| error.in_rule(index))?;
+            self.agenda.update(index, &changes.dropped, &changes.unmatched, activations);
+            self.network.clear_changes(index);
+        }
+        Ok(())
+    }
+
+    /// Returns the stratum of every rule, stratifying the rules again if rules were added since they last were, and
+    /// moving the activations on the agenda to their new stratum.
+    ///
+    /// # Errors
+    /// Returns `EngineError::Unstratifiable` if the rules cannot be stratified.
+    fn rule_strata(&mut self) -> Result<&[usize], EngineError> {
+        if self.strata.is_none() {
+            let strata = DependencyGraph::new(&self.rules).rule_strata(&self.rules)?;
+            self.agenda.set_strata(&strata);
+            self.strata = Some(strata);
+        }
+        Ok(self.strata.as_deref().unwrap_or_default())
+    }
+
+    /// Instantiates the actions of a rule for new sets of bindings that satisfy its premise.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the rule in the engine's rule set.
+    /// * `stratum` - The stratum of the rule.
+    /// * `matches` - The new matches of the rule's premise, as found by the match network: their position among the
+    ///   rule's matches, the time tags of the facts they relied on, and their solution.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Activation>, EngineError>` - One activation per distinct list of instantiated actions, in the order
+    ///   the matches were given, holding every match producing it that has not fired yet. The vector is empty if every
+    ///   match has fired.
+    fn rule_activations(&self, index: usize, stratum: usize, matches: &[(&[u64], &[u64], &Solution)]) -> Result<Vec<Activation>, EngineError> {
+        let rule = &self.rules[index];
+        let specificity = agenda::specificity(&rule.premise);
+        let mut activations: Vec<Activation> = Vec::new();
+        let mut by_actions: HashMap<Vec<Action>, usize> = HashMap::new();
+        for &(position, tags, solution) in matches {
+            if self.agenda.has_fired(index, tags) {
+                continue;
+            }
+            let actions = rule.actions.iter()
+                .map(|action| self.instantiate_action(action, &solution.bindings))
+                .collect::<Result<Vec<_>, _>>()?;
+            let found = *by_actions.entry(actions).or_insert_with_key(|actions| {
+                activations.push(Activation::new(index, actions.clone(), stratum, rule.salience, specificity));
+                activations.len() - 1
+            });
+            activations[found].add_match(solution.clone(), tags.to_vec(), position.to_vec());
+        }
+        Ok(activations)
+    }
+
+    /// Instantiates a single action of a rule against the bindings of one match of its premise.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownCallback` if a `Call` action refers to a callback that has not been registered, in
+    /// addition to the errors raised while instantiating the action's facts and arguments.
+    fn instantiate_action(&self, action: &Action, bindings: &HashMap<String, Variable>) -> Result<Action, EngineError> {
+        match action {
+            Action::Assert(fact) => Ok(Action::Assert(self.apply_rule_conclusion(fact, bindings)?)),
+            Action::Modify(fact) => Ok(Action::Modify(self.apply_rule_conclusion(fact, bindings)?)),
+            Action::Retract(fact) => Ok(Action::Retract(self.instantiate_fact(fact, bindings)?)),
+            Action::Call { callback, arguments } => {
+                if !self.callbacks.contains_key(callback) {
+                    return Err(EngineError::UnknownCallback { name: callback.clone() });
+                }
+                let arguments = arguments.iter().map(|argument| match argument {
+                    FactValue::Variable(name) => Self::bound_value(argument, bindings)
+                        .cloned()
+                        .map_err(|_| EngineError::UnboundVariable { name: name.clone() }),
+                    FactValue::Text(text) => template::render(self, text, bindings).map(FactValue::Text),
+                    _ => Ok(argument.clone()),
+                }).collect::<Result<_, _>>()?;
+                Ok(Action::Call { callback: callback.clone(), arguments })
+            },
+        }
+    }
+
+    /// Instantiates a rule's conclusion template against the bindings of one match of its premise.
+    ///
+    /// Pattern variables are replaced by their bound values, and `${...}` placeholders in text values and arguments are
+    /// rendered from the bindings. Rendered text that no longer holds placeholders is converted to the type declared
+    /// for its position, so that `"${t + 1}"` yields an `Integer` for an integer symbol.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnboundVariable` if a pattern variable of the conclusion is not bound, the errors of
+    /// `template::render` if a placeholder cannot be computed, and the errors of `validate_fact_value` if a value does
+    /// not satisfy the conclusion's signature or `SymbolType`.
+    fn apply_rule_conclusion(&self, conclusion: &Fact, bindings: &HashMap<String, Variable>) -> Result<Fact, EngineError> {
+        let fact = self.instantiate_fact(conclusion, bindings)?;
+        if let Some(name) = fact.variables().next() {
+            return Err(EngineError::UnboundVariable { name: name.to_string() });
+        }
+        Ok(fact)
     }
 
-    // Separated the logic to apply the rule's conclusion into its own method to avoid borrowing issues
-    fn apply_rule_conclusion(&self, conclusion: &Fact) -> Fact {
-        conclusion.clone()
+    /// Instantiates a fact template like `apply_rule_conclusion`, but leaves unbound pattern variables in place.
+    fn instantiate_fact(&self, template: &Fact, bindings: &HashMap<String, Variable>) -> Result<Fact, EngineError> {
+        let mut fact = self.substitute_bindings(template, bindings);
+
+        let symbol = fact.symbol.clone();
+        for (position, (argument, argument_type)) in fact.arguments.iter_mut().zip(&symbol.signature).enumerate() {
+            self.render_template(argument, argument_type, bindings, |found| EngineError::ArgumentTypeMismatch {
+                symbol: symbol.name.clone(),
+                position,
+                expected: argument_type.clone(),
+                found,
+            })?;
+        }
+        self.render_template(&mut fact.value, &symbol.symbol_type, bindings, |found| EngineError::TypeMismatch {
+            symbol: symbol.name.clone(),
+            expected: symbol.symbol_type.clone(),
+            found,
+        })?;
+
+        self.validate_fact_value(&fact)?;
+        Ok(fact)
     }
 
-    /// Evaluates the premise of a rule and determines if it matches the current state of the knowledge base, considering variable bindings.
-    ///
-    /// This method plays a critical role in the inference process of the symbolic reasoning engine by evaluating the logical
-    /// expressions that constitute the premises of rules. It determines whether these premises are satisfied given the current
-    /// facts and variable bindings within the engine. If the premise is satisfied, the method returns a mapping of any variables
-    /// involved in the premise to their resolved values, facilitating the application of the rule's conclusion with accurate,
-    /// context-specific data.
-    ///
-    /// The method supports complex logical expressions involving AND, OR, NOT operators, and direct fact comparisons, allowing
-    /// for the evaluation of sophisticated rule premises. Variable values are resolved against the current bindings to ensure
-    /// that dynamic conditions are accurately assessed.
-    ///
-    /// # Arguments
-    /// * `premise` - A reference to a `LogicalOperator` representing the logical expression that forms the premise of a rule.
-    ///
-    /// # Returns
-    /// * `Option<HashMap<String, FactValue>>` - An optional mapping of variable names to their resolved values if the premise
-    ///   is satisfied. Returns `None` if the premise is not satisfied, indicating that the rule should not be applied.
-    ///
-    /// # Examples
-    /// ```
-    /// // Assuming an instance `engine` of `SymbolicReasoningEngine`
-    /// // and a rule premise that involves temperature and weather conditions
-    /// //let premise = LogicalOperator::And(vec![
-    /// //    LogicalOperator::Fact(Fact::new(temperature_symbol, FactValue::GreaterThan(FactValue::Integer(20)))),
-    /// //    LogicalOperator::Fact(Fact::new(weather_symbol, FactValue::Text(String::from("Sunny"))))
-    /// //]);
+    /// Renders a templated text term of a conclusion in place, converting it to the given type once fully rendered.
     ///
-    /// // Evaluate the rule's premise
-    /// //if let Some(bindings) = engine.match_rule(&premise) {
-    ///     // Premise is satisfied, proceed with applying the rule's conclusion
-    ///     // Bindings contain any variable resolutions involved in the premise
-    /// //} else {
-    ///     // Premise is not satisfied, rule is not applied
-    /// //}
-    /// ```
+    /// Terms that are not text holding placeholders are left untouched, as are references to unbound or unstable
+    /// variables, which are resolved during evaluation.
     ///
-    /// The `match_rule` method enables the engine to dynamically assess rule premises against the evolving knowledge base,
-    /// supporting conditional logic and variable-based reasoning within the rule evaluation framework.
-    fn match_rule(&self, premise: &LogicalOperator) -> Option<HashMap<String, Variable>> {
-        self.evaluate_logical_expression(premise, &self.variable_bindings, false, &mut None)
+    /// # Errors
+    /// Returns the errors of `template::render`, or the error built by `mismatch` from the rendered text if it cannot be
+    /// converted to `symbol_type`.
+    fn render_template(
+        &self,
+        term: &mut FactValue,
+        symbol_type: &SymbolType,
+        bindings: &HashMap<String, Variable>,
+        mismatch: impl FnOnce(FactValue) -> EngineError
+    ) -> Result<(), EngineError> {
+        let FactValue::Text(text) = term else {
+            return Ok(());
+        };
+        if !template::has_placeholders(text) {
+            return Ok(());
+        }
+
+        let rendered = template::render(self, text, bindings)?;
+        *term = if template::has_placeholders(&rendered) {
+            FactValue::Text(rendered)
+        } else {
+            symbol_type.coerce(&rendered).ok_or_else(|| mismatch(FactValue::Text(rendered)))?
+        };
+        Ok(())
     }
 
     /// Evaluates a logical expression against the current knowledge base and provided variable bindings.
@@ -943,18 +2874,33 @@ impl SymbolicReasoningEngine {
     /// resolved values. This is particularly useful for rules that involve variables, as it allows for the dynamic
     /// application of rule conclusions based on the specific conditions met at the time of evaluation.
     ///
+    /// Pattern variables in atomic facts are unified with every matching known fact, so an expression may be satisfied
+    /// in several ways: `And` joins the solutions of its operands, `Or` concatenates them, and `Not` holds, without
+    /// binding anything, when its operand has no solution under the current bindings. Comparisons filter solutions
+    /// using the values bound so far. A pattern variable already bound in `existing`, including an asserted variable
+    /// of the same name, must unify with that value.
+    ///
+    /// Each solution also records its support: the known facts its atomic facts unified with and the facts of the
+    /// symbols its comparisons read, as antecedents, and the instantiated patterns of the atomic facts negated by
+    /// `Not`, as exclusions.
+    ///
     /// # Arguments
     /// * `expression` - A reference to a `LogicalOperator` representing the logical expression to be evaluated.
-    /// * `existing_bindings` - A reference to a `HashMap` containing any existing variable bindings. These bindings
-    ///   represent the current state of variables within the engine's context and are used to resolve variable
-    ///   references within the expression.
-    /// * `use_backward_chaining` - A reference to a bool flagging the use of recursive rule searches in fact evaluation for backward chaining
-    /// * `visited` - A mutable reference to an optional vector tracking visited goals to prevent cycles. Backward chaining must provide this argument.
+    /// * `existing` - The solution being extended, holding any existing variable bindings and the support gathered so
+    ///   far. These bindings represent the current state of variables within the engine's context and are used to
+    ///   resolve variable references within the expression.
+    /// * `visited` - An optional mutable reference to a vector tracking visited goals to prevent cycles. Providing it
+    ///   enables backward chaining: facts that are not known are recursively searched for through the rules, provided
+    ///   that all their pattern variables are bound by the time they are evaluated.
     ///
     /// # Returns
-    /// * `Option<HashMap<String, FactValue>>` - An optional mapping of variable names to their resolved values
-    ///   if the expression evaluates to true, indicating that the conditions represented by the expression are
-    ///   satisfied. Returns `None` if the expression evaluates to false, indicating that the conditions are not met.
+    /// * `Result<Vec<Solution>, EngineError>` - One extension of `existing` for every way the expression evaluates to
+    ///   true, indicating that the conditions represented by the expression
+    ///   are satisfied. The vector is empty if the expression evaluates to false, indicating that the conditions are
+    ///   not met.
+    ///
+    /// # Errors
+    /// Returns an `EngineError` if a comparison within the expression cannot be resolved.
     ///
     /// # Examples
     /// ```
@@ -977,100 +2923,124 @@ impl SymbolicReasoningEngine {
     ///
     /// The `evaluate_logical_expression` method enables nuanced and conditional logic to be applied within the engine,
     /// supporting the evaluation of rules and conditions that reflect the complex dynamics of the domain being modeled.
-    fn evaluate_logical_expression(&self, expression: &LogicalOperator, existing_bindings: &HashMap<String, Variable>, use_backward_chaining: bool, visited: &mut Option<&mut Vec<Fact>>) -> Option<HashMap<String, Variable>> {
-        match expression {
+    fn evaluate_logical_expression(&self, expression: &LogicalOperator, existing: &Solution, mut visited: Option<&mut Vec<Fact>>) -> Result<Vec<Solution>, EngineError> {
+        let bindings = &existing.bindings;
+        let backward = visited.is_some();
+        let (comparison, proof, operands) = match expression {
             LogicalOperator::And(expressions) => {
-                let mut combined_bindings = existing_bindings.clone();
+                let mut solutions = vec![existing.clone()];
                 for expr in expressions {
-                    if let Some(bindings) = self.evaluate_logical_expression(expr, &combined_bindings, use_backward_chaining, visited) {
-                        combined_bindings = bindings; // Instead of extending, replace to avoid duplication
-                    } else {
-                        return None; // Short-circuit on the first false expression
+                    let mut joined_solutions = Vec::new();
+                    for solution in &solutions {
+                        joined_solutions.extend(self.evaluate_logical_expression(expr, solution, visited.as_deref_mut())?);
+                    }
+                    solutions = joined_solutions;
+                    if solutions.is_empty() {
+                        break; // Short-circuit on the first false expression
                     }
                 }
-                Some(combined_bindings)
+                return Ok(solutions);
+            },
+            LogicalOperator::Or(expressions) => {
+                let mut solutions = Vec::new();
+                for expr in expressions {
+                    solutions.extend(self.evaluate_logical_expression(expr, existing, visited.as_deref_mut())?);
+                }
+                return Ok(solutions);
             },
-            LogicalOperator::Or(expressions) => expressions.iter()
-                .find_map(|expr| self.evaluate_logical_expression(expr, existing_bindings, use_backward_chaining, visited)),
-            LogicalOperator::Not(expression) => match self.evaluate_logical_expression(expression, existing_bindings, use_backward_chaining, visited) {
-                None => Some(existing_bindings.clone()), // NOT expression is true if inner is false
-                Some(_) => None, // NOT expression is false if inner is true
+            LogicalOperator::Not(expression) => {
+                let solutions = self.evaluate_logical_expression(expression, existing, visited)?;
+                // NOT expression is true if inner is false
+                if !solutions.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let mut solution = existing.clone();
+                let negated = match expression.as_ref() {
+                    LogicalOperator::AtomicFact(fact) => {
+                        let pattern = self.substitute_bindings(&self.interpolate_fact(fact, false), bindings);
+                        solution.exclusions.push(pattern.clone());
+                        LogicalOperator::AtomicFact(pattern)
+                    },
+                    expression => expression.clone(),
+                };
+                if backward {
+                    solution.proofs.push(Proof::Negation(negated));
+                }
+                return Ok(vec![solution]);
             },
             LogicalOperator::AtomicFact(fact) => {
-                match &fact.value {
-                    FactValue::Text(fact_value) => {
-                        let interpolated_fact_value = self.resolve_variables_in_context(&fact_value, false);
-                        let interpolated_fact = Fact { symbol: fact.symbol.clone(), value: FactValue::Text(interpolated_fact_value) };
-                        if use_backward_chaining {
-                            for known_fact in &self.facts {
-                                if self.match_fact(&interpolated_fact, known_fact) {
-                                    return Some(existing_bindings.clone());
-                                }
-                            }
-                            match visited {
-                                Some(visited_facts) => {
-                                    if self.search_for_rules(&interpolated_fact, visited_facts) {
-                                        return Some(existing_bindings.clone());
-                                    }
-                                },
-                                _ => panic!("Backward chaining calls to evaluate_logical_expression must provide visited rules")
-                            }
-                        } else {
-                            for known_fact in &self.facts {
-                                if self.match_fact(&interpolated_fact, known_fact) {
-                                    return Some(existing_bindings.clone());
-                                }
-                            }
+                let interpolated_fact = self.interpolate_fact(fact, false);
+                let pattern = self.substitute_bindings(&interpolated_fact, bindings);
+
+                let mut solutions: Vec<_> = self.facts.candidates(&pattern)
+                    .filter_map(|known_fact| {
+                        let bindings = self.unify_fact(&pattern, known_fact, bindings)?;
+                        let mut solution = Solution { bindings, ..existing.supported_by([known_fact.clone()]) };
+                        if backward {
+                            solution.proofs.push(Proof::Fact(known_fact.clone()));
                         }
-                    },
-                    _ => {}
+                        Some(solution)
+                    })
+                    .collect();
+
+                // When backward chaining, attempt to derive the fact through the rules
+                if let Some(visited_facts) = visited {
+                    if solutions.is_empty() && pattern.is_ground() {
+                        if let Some(proof) = self.search_for_rules(&pattern, visited_facts)? {
+                            let mut solution = existing.clone();
+                            solution.proofs.push(proof);
+                            solutions.push(solution);
+                        }
+                    }
                 }
 
-                None // Fact does not match any known facts
+                return Ok(solutions);
             },
             LogicalOperator::GreaterThan(left, right) => {
-                if self.compare_values(left, right, |a, b| a > b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (holds, proof) = self.compare_values(">", left, right, bindings, |a, b| a > b)?;
+                (holds, proof, [left, right])
             },
             LogicalOperator::LessThan(left, right) => {
-                if self.compare_values(left, right, |a, b| a < b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (holds, proof) = self.compare_values("<", left, right, bindings, |a, b| a < b)?;
+                (holds, proof, [left, right])
             },
             LogicalOperator::EqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a == b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (equal, proof) = self.compare_equality("==", left, right, bindings)?;
+                (equal, proof, [left, right])
             },
             LogicalOperator::NotEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a != b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (equal, proof) = self.compare_equality("!=", left, right, bindings)?;
+                (!equal, proof, [left, right])
             },
             LogicalOperator::GreaterThanOrEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a >= b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (holds, proof) = self.compare_values(">=", left, right, bindings, |a, b| a >= b)?;
+                (holds, proof, [left, right])
             },
             LogicalOperator::LessThanOrEqualTo(left, right) => {
-                if self.compare_values(left, right, |a, b| a <= b) {
-                    Some(existing_bindings.clone())
-                } else {
-                    None
-                }
+                let (holds, proof) = self.compare_values("<=", left, right, bindings, |a, b| a <= b)?;
+                (holds, proof, [left, right])
             },
+        };
+
+        if !comparison {
+            return Ok(Vec::new());
+        }
+
+        // Comparisons rely on the facts of the symbols they read
+        let support = operands.into_iter()
+            .flat_map(|operand| operand.leaves())
+            .filter_map(|operand| match operand {
+                ComparableValue::Direct(_) | ComparableValue::Arithmetic(_) => None,
+                ComparableValue::Symbol(symbol) => self.get_fact_from_symbol(symbol),
+                ComparableValue::SymbolName(name) => self.symbols.get(name).and_then(|symbol| self.get_fact_from_symbol(symbol)),
+            })
+            .cloned();
+        let mut solution = existing.supported_by(support);
+        if backward {
+            solution.proofs.push(proof);
         }
+        Ok(vec![solution])
     }
 
     /// Compares two `ComparableValue` instances using a specified comparison function.
@@ -1083,23 +3053,110 @@ impl SymbolicReasoningEngine {
     /// the result of the comparison (e.g., greater than, less than, equal to, etc.).
     ///
     /// # Arguments
+    /// * `operator` - The textual form of the comparison operator, used to describe the comparison in proofs.
     /// * `left` - A reference to the first `ComparableValue` to compare.
     /// * `right` - A reference to the second `ComparableValue` to compare.
+    /// * `bindings` - The variable bindings pattern variables in either value are resolved against.
     /// * `comparison` - A function that defines the type of comparison to perform between the two values.
     ///   It must accept two `f64` arguments and return a `bool` indicating the result of the comparison.
     ///
     /// # Returns
-    /// * `bool` - The result of applying the `comparison` function to the resolved values of `left` and `right`.
+    /// * `Result<(bool, Proof), EngineError>` - The result of applying the `comparison` function to the resolved values
+    ///   of `left` and `right`, along with a `Proof::Comparison` recording those values, or the error raised while
+    ///   resolving either value.
     fn compare_values(
         &self,
+        operator: &str,
         left: &ComparableValue,
         right: &ComparableValue,
+        bindings: &HashMap<String, Variable>,
         comparison: fn(f64, f64) -> bool
-    ) -> bool {
-        let left_value = self.resolve_comparable_value(left);
-        let right_value = self.resolve_comparable_value(right);
+    ) -> Result<(bool, Proof), EngineError> {
+        let left_value = self.resolve_comparable_value(left, bindings)?;
+        let right_value = self.resolve_comparable_value(right, bindings)?;
+
+        let proof = Proof::Comparison { operator: operator.to_string(), left: left_value, right: right_value };
+        Ok((comparison(left_value, right_value), proof))
+    }
+
+    /// Tests two `ComparableValue` instances for equality, respecting the types of the resolved values.
+    ///
+    /// Numeric values are compared numerically, so that `Integer(20)` equals `Float(20.0)`, and text produced by
+    /// variable interpolation is parsed as a number when compared against a numeric value. Text and boolean values are
+    /// compared directly.
+    ///
+    /// # Arguments
+    /// * `operator` - The textual form of the comparison operator, used for error reporting and in proofs.
+    /// * `left` - A reference to the first `ComparableValue` to compare.
+    /// * `right` - A reference to the second `ComparableValue` to compare.
+    /// * `bindings` - The variable bindings pattern variables in either value are resolved against.
+    ///
+    /// # Returns
+    /// * `Result<(bool, Proof), EngineError>` - Whether the resolved values are equal, along with a `Proof::Equality`
+    ///   recording those values.
+    ///
+    /// # Errors
+    /// Returns `EngineError::IncompatibleComparison` if the resolved values have types that cannot be compared, in
+    /// addition to any error raised while resolving either value.
+    fn compare_equality(&self, operator: &str, left: &ComparableValue, right: &ComparableValue, bindings: &HashMap<String, Variable>) -> Result<(bool, Proof), EngineError> {
+        let left_value = self.resolve_comparable_fact_value(left, bindings)?;
+        let right_value = self.resolve_comparable_fact_value(right, bindings)?;
+
+        let equal = match (&left_value, &right_value) {
+            (FactValue::Boolean(l), FactValue::Boolean(r)) => Ok(l == r),
+            (FactValue::Text(l), FactValue::Text(r)) => Ok(l == r),
+            _ => {
+                let as_number = |value: &FactValue| match value {
+                    FactValue::Integer(val) => Some(*val as f64),
+                    FactValue::Float(val) => Some(*val),
+                    FactValue::Text(val) => f64::from_str(val).ok(),
+                    FactValue::Boolean(_) | FactValue::Variable(_) => None,
+                };
+                match (as_number(&left_value), as_number(&right_value)) {
+                    (Some(l), Some(r)) => Ok(l == r),
+                    // Resolved values are always ground, so both types are known
+                    _ => SymbolType::of_value(&left_value).zip(SymbolType::of_value(&right_value))
+                        .map_or(Ok(false), |(left, right)| Err(EngineError::IncompatibleComparison {
+                            operator: operator.to_string(),
+                            left,
+                            right,
+                        })),
+                }
+            },
+        }?;
+
+        Ok((equal, Proof::Equality { operator: operator.to_string(), left: left_value, right: right_value }))
+    }
+
+    /// Resolves a `ComparableValue` to the `FactValue` it denotes, interpolating stable variables in text values and
+    /// replacing pattern variables with their bound values. Arithmetic expressions denote the float they evaluate to.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` or `EngineError::MissingFact` if the referenced symbol or its fact cannot
+    /// be found, `EngineError::UnboundVariable` if a pattern variable has no binding, and the errors raised while
+    /// evaluating an arithmetic expression.
+    fn resolve_comparable_fact_value(&self, value: &ComparableValue, bindings: &HashMap<String, Variable>) -> Result<FactValue, EngineError> {
+        let fact_value = match value {
+            ComparableValue::Direct(fact_value) => Self::bound_value(fact_value, bindings)?,
+            ComparableValue::Arithmetic(expression) => return self.evaluate_arithmetic(expression, bindings).map(FactValue::Float),
+            ComparableValue::Symbol(symbol) => {
+                &self.get_fact_from_symbol(symbol)
+                    .ok_or_else(|| EngineError::MissingFact { symbol: symbol.name.clone() })?
+                    .value
+            },
+            ComparableValue::SymbolName(symbol_name) => {
+                let symbol = self.symbols.get(symbol_name)
+                    .ok_or_else(|| EngineError::UnknownSymbol { symbol: symbol_name.clone() })?;
+                &self.get_fact_from_symbol(symbol)
+                    .ok_or_else(|| EngineError::MissingFact { symbol: symbol_name.clone() })?
+                    .value
+            },
+        };
 
-        comparison(left_value, right_value)
+        match fact_value {
+            FactValue::Text(text) => Ok(FactValue::Text(self.resolve_variables_in_context(text, true))),
+            _ => Ok(fact_value.clone()),
+        }
     }
 
     /// Resolves a `ComparableValue` to its numerical representation as an `f64`.
@@ -1110,110 +3167,133 @@ impl SymbolicReasoningEngine {
     ///
     /// # Arguments
     /// * `value` - A reference to the `ComparableValue` to be resolved.
+    /// * `bindings` - The variable bindings a pattern variable in `value` is resolved against.
     ///
     /// # Returns
-    /// * `f64` - The numerical representation of the input `ComparableValue`.
+    /// * `Result<f64, EngineError>` - The numerical representation of the input `ComparableValue`.
     ///
-    /// # Panics
-    /// This method panics if:
-    /// - The `ComparableValue` is of type `Direct` with a non-numeric `FactValue` (neither `Integer` nor `Float`).
-    /// - The `ComparableValue` is of type `Symbol`, and the symbol cannot be found in the knowledge base.
-    /// - The `ComparableValue` is of type `Symbol`, and the corresponding `FactValue` is not numeric.
-    /// - The `ComparableValue` type is not supported by this method.
+    /// # Errors
+    /// This method returns an error if:
+    /// - The `ComparableValue` is of type `Direct` with a non-numeric `FactValue` (`EngineError::NonNumericComparison`).
+    /// - The `ComparableValue` is of type `Direct` with a pattern variable that has no binding (`EngineError::UnboundVariable`).
+    /// - The `ComparableValue` is of type `SymbolName`, and no symbol with that name has been defined (`EngineError::UnknownSymbol`).
+    /// - The `ComparableValue` refers to a symbol for which no fact is known (`EngineError::MissingFact`).
+    /// - The `ComparableValue` refers to a symbol whose fact value is not numeric (`EngineError::TypeMismatch` or
+    ///   `EngineError::NonNumericComparison`).
+    /// - The `ComparableValue` is of type `Arithmetic`, and the expression divides by zero (`EngineError::DivisionByZero`),
+    ///   calls a function that is not registered (`EngineError::UnknownFunction`), or raises any of these errors while
+    ///   resolving its operands.
     ///
     /// This method is crucial for the operation of the rule engine, allowing it to perform numeric comparisons on facts
     /// and fact values, which are essential for making logical inferences based on the rules defined within the engine.
-    fn resolve_comparable_value(&self, value: &ComparableValue) -> f64 {
+    fn resolve_comparable_value(&self, value: &ComparableValue, bindings: &HashMap<String, Variable>) -> Result<f64, EngineError> {
         match value {
-            ComparableValue::Direct(fact_value) => match fact_value {
-                FactValue::Integer(val) => *val as f64,
-                FactValue::Float(val) => *val,
-                FactValue::Text(val) => f64::from_str(&self.resolve_variables_in_context(&val, true)).unwrap_or(0f64),
-                _ => panic!("Unsupported FactValue type from ComparableValue::Direct for conversion to f64"),
+            ComparableValue::Direct(fact_value) => {
+                let fact_value = Self::bound_value(fact_value, bindings)?;
+                let numeric = match fact_value {
+                    FactValue::Integer(val) => Some(*val as f64),
+                    FactValue::Float(val) => Some(*val),
+                    FactValue::Text(val) => f64::from_str(&self.resolve_variables_in_context(val, true)).ok(),
+                    FactValue::Boolean(_) | FactValue::Variable(_) => None,
+                };
+                numeric.ok_or_else(|| EngineError::NonNumericComparison { symbol: None, value: fact_value.clone() })
             },
             ComparableValue::Symbol(symbol) => {
-                let fact = self.get_fact_from_symbol(symbol.clone()).expect("Symbol not found in knowledge base");
-
-                let fact_value = self.get_comparable_fact_value(fact);
-                match fact_value {
-                    Some(val) => val,
-                    _ => panic!("Unsupported FactValue type from ComparableValue::SymbolName for conversion to f64"),
-                }
+                let fact = self.get_fact_from_symbol(symbol)
+                    .ok_or_else(|| EngineError::MissingFact { symbol: symbol.name.clone() })?;
+                self.get_comparable_fact_value(fact)
             },
             ComparableValue::SymbolName(symbol_name) => {
-                let symbol = self.symbols.get(symbol_name).expect("Symbol not found in knowledge base");
-                let fact = self.get_fact_from_symbol(symbol.clone())
-                    .expect("Symbol not found in knowledge base");
-                let fact_value = self.get_comparable_fact_value(fact);
-                match fact_value {
-                    Some(val) => val,
-                    _ => panic!("Unsupported FactValue type from ComparableValue::SymbolName for conversion to f64"),
-                }
-            }
+                let symbol = self.symbols.get(symbol_name)
+                    .ok_or_else(|| EngineError::UnknownSymbol { symbol: symbol_name.clone() })?;
+                let fact = self.get_fact_from_symbol(symbol)
+                    .ok_or_else(|| EngineError::MissingFact { symbol: symbol_name.clone() })?;
+                self.get_comparable_fact_value(fact)
+            },
+            ComparableValue::Arithmetic(expression) => self.evaluate_arithmetic(expression, bindings),
         }
     }
 
-    /// Resolves the value of a specified variable based on the provided bindings.
-    ///
-    /// This method is crucial for the dynamic evaluation of rules and logical expressions within the symbolic reasoning engine.
-    /// It attempts to find the current value of a variable by looking it up in the provided set of variable bindings, which map
-    /// variable names to their respective values. This lookup process enables the engine to work with dynamic data, where the
-    /// value of a variable can influence the outcome of rule evaluations and logical operations.
+    /// Unifies a fact pattern with a known fact, considering variable bindings.
     ///
-    /// The method supports scenarios where variables are used within rules to represent data that may change over time or
-    /// across different contexts. By resolving variables to their current values at the time of evaluation, the engine ensures
-    /// that its reasoning processes are based on the most up-to-date information available.
+    /// Facts about predicates match only if their arguments match position by position, in addition to their values.
+    /// A pattern variable that is already bound must match its bound value, while an unbound pattern variable is bound
+    /// to the corresponding value of the known fact. Pattern variables in `known_fact`, which only occur when it is a
+    /// goal, match any value without binding anything.
     ///
     /// # Arguments
-    /// * `var_name` - A string slice (`&str`) representing the name of the variable whose value is to be resolved.
-    /// * `bindings` - A reference to a `HashMap` containing the current variable bindings. This map provides the context
-    ///   for resolving the variable's value, associating variable names with their current `FactValue`s.
+    /// * `fact` - A reference to the fact pattern being matched.
+    /// * `known_fact` - A reference to a known fact for comparison.
+    /// * `bindings` - The variable bindings established so far.
     ///
     /// # Returns
-    /// * `Option<FactValue>` - An optional `FactValue` representing the resolved value of the variable if it exists within
-    ///   the provided bindings. Returns `None` if the variable name is not found, indicating that the variable has not been
-    ///   bound to a value within the current context.
-    ///
-    /// # Examples
-    /// ```
-    /// // Assuming a set of variable bindings
-    /// //let mut bindings = HashMap::new();
-    /// //bindings.insert("temperature".to_string(), FactValue::Integer(25));
-    ///
-    /// // Resolve the value of the "temperature" variable
-    /// //let temperature_value = engine.resolve_variable_value("temperature", &bindings);
-    ///
-    /// //assert_eq!(temperature_value, Some(FactValue::Integer(25))); // The variable is successfully resolved
-    /// ```
-    ///
-    /// The `resolve_variable_value` method plays a key role in enabling flexible and dynamic rule evaluation, supporting
-    /// the engine's ability to adapt its reasoning based on variable data.
-    fn resolve_variable_value(&self, var_name: &str, bindings: &HashMap<String, Variable>) -> Option<Variable> {
-        bindings.get(var_name).cloned()
+    /// The extended bindings if the pattern unifies with the known fact; `None` otherwise.
+    fn unify_fact(&self, fact: &Fact, known_fact: &Fact, bindings: &HashMap<String, Variable>) -> Option<HashMap<String, Variable>> {
+        // Ensure symbols and arities match before evaluating arguments and values.
+        if fact.symbol != known_fact.symbol || fact.arguments.len() != known_fact.arguments.len() {
+            return None;
+        }
+
+        self.print_debug(&format!("Matching fact: {} against known fact: {}", fact, known_fact));
+
+        let mut unified_bindings = bindings.clone();
+        let terms = fact.arguments.iter().zip(&known_fact.arguments).chain(std::iter::once((&fact.value, &known_fact.value)));
+        for (term, known_term) in terms {
+            match (term, known_term) {
+                (_, FactValue::Variable(_)) => {},
+                (FactValue::Variable(name), _) => match unified_bindings.get(name) {
+                    Some(bound) if !self.match_value(&bound.value, known_term) => return None,
+                    Some(_) => {},
+                    None => {
+                        unified_bindings.insert(name.clone(), Variable::new(name, known_term.clone(), VariableState::Stable));
+                    },
+                },
+                _ if !self.match_value(term, known_term) => return None,
+                _ => {},
+            }
+        }
+
+        Some(unified_bindings)
     }
 
-    /// Matches a single fact against known facts, considering variable bindings and comparisons.
-    ///
-    /// # Arguments
-    /// * `fact` - A reference to the fact being matched.
-    /// * `known_fact` - A reference to a known fact for comparison.
-    /// * `bindings` - A mutable reference to a HashMap representing variable bindings.
-    ///
-    /// # Returns
-    /// True if the fact matches the known fact based on direct equality or comparison operations; false otherwise.
-    fn match_fact(&self, fact: &Fact, known_fact: &Fact) -> bool {
-        // Ensure symbols match before evaluating values.
-        if fact.symbol != known_fact.symbol {
-            // self.print_debug("Symbols do not match.");
-            return false;
+    /// Checks whether a known fact matches a fact pattern, whose pattern variables match any value.
+    fn matches_pattern(&self, pattern: &Fact, known_fact: &Fact) -> bool {
+        self.unify_fact(pattern, known_fact, &HashMap::new()).is_some()
+    }
+
+    /// Returns a copy of the fact with every bound pattern variable replaced by its value.
+    fn substitute_bindings(&self, fact: &Fact, bindings: &HashMap<String, Variable>) -> Fact {
+        let substitute = |term: &FactValue| match term {
+            FactValue::Variable(name) => bindings.get(name).map_or_else(|| term.clone(), |bound| bound.value.clone()),
+            _ => term.clone(),
+        };
+
+        Fact {
+            symbol: fact.symbol.clone(),
+            arguments: fact.arguments.iter().map(substitute).collect(),
+            value: substitute(&fact.value),
         }
+    }
 
-        self.print_debug(&format!("Matching fact: {:?} against known fact: {:?}", fact, known_fact));
+    /// Resolves a direct comparison value, replacing a pattern variable with its bound value.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnboundVariable` if the value is a pattern variable without a binding.
+    fn bound_value<'a>(value: &'a FactValue, bindings: &'a HashMap<String, Variable>) -> Result<&'a FactValue, EngineError> {
+        match value {
+            FactValue::Variable(name) => bindings.get(name)
+                .map(|bound| &bound.value)
+                .ok_or_else(|| EngineError::UnboundVariable { name: name.clone() }),
+            _ => Ok(value),
+        }
+    }
 
-        match (&fact.value, &known_fact.value) {
+    /// Matches a single value or argument of a fact against the corresponding value of a known fact.
+    fn match_value(&self, value: &FactValue, known_value: &FactValue) -> bool {
+        match (value, known_value) {
             // Direct value comparison
             (FactValue::Integer(l), FactValue::Integer(r)) => l == r,
-            (FactValue::Float(l), FactValue::Float(r)) => l == r,
+            (FactValue::Float(l), FactValue::Float(r)) => TotalFloat::new(*l) == TotalFloat::new(*r),
             (FactValue::Boolean(l), FactValue::Boolean(r)) => l == r,
             (FactValue::Text(l), FactValue::Text(r)) => l == r,
             // Default case for non-matching types or unsupported comparisons
@@ -1226,17 +3306,198 @@ impl SymbolicReasoningEngine {
 
     /// Initiates the backward chaining process to try and satisfy a specified goal.
     ///
+    /// Unlike forward chaining, backward chaining does not add any facts to the knowledge base; it only determines
+    /// whether the goal is a known fact or can be derived from the known facts through the defined rules.
+    ///
+    /// # Arguments
+    /// * `goal` - The goal the engine attempts to satisfy, represented as a `Fact`.
+    ///
+    /// # Returns
+    /// * `Result<bool, EngineError>` - Returns `true` if the engine successfully satisfies the goal using backward
+    ///   chaining, or `false` if the goal cannot be satisfied with the current set of rules and facts.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if the premise of a rule tried along the way cannot be evaluated.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// let hiking = engine.define_symbol("Hiking", SymbolType::Boolean)?;
+    ///
+    /// engine.assert_fact(weather.clone(), FactValue::Text("Sunny".into()))?;
+    /// engine.define_rule(
+    ///     LogicalOperator::AtomicFact(Fact::new(weather, FactValue::Text("Sunny".into()))),
+    ///     Fact::new(hiking.clone(), FactValue::Boolean(true)),
+    /// )?;
+    ///
+    /// assert!(engine.specify_goal(&Fact::new(hiking, FactValue::Boolean(true)))?);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn specify_goal(&self, goal: &Fact) -> Result<bool, EngineError> {
+        Ok(self.prove_goal(goal)?.is_some())
+    }
+
+    /// Attempts to satisfy a goal through backward chaining, like `specify_goal`, returning a proof tree.
+    ///
+    /// The proof shows how the goal was established: as a known fact, or through the rule whose premise was satisfied,
+    /// with one sub-proof per condition of the premise. Conditions on facts that are not known are proven recursively
+    /// through the rules, and comparisons record the values they were evaluated on, so that the reasoning behind an
+    /// answer can be presented to the user. The first proof found is returned, trying known facts before rules and
+    /// rules in the order they were defined.
+    ///
     /// # Arguments
     /// * `goal` - The goal the engine attempts to satisfy, represented as a `Fact`.
     ///
     /// # Returns
-    /// * `bool` - Returns `true` if the engine successfully satisfies the goal using backward chaining,
-    ///   or `false` if the goal cannot be satisfied with the current set of rules and facts.
-    pub fn specify_goal(&mut self, goal: &Fact) -> bool {
+    /// * `Result<Option<Proof>, EngineError>` - The proof of the goal, or `None` if it cannot be satisfied with the
+    ///   current set of rules and facts.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if the premise of a rule tried along the way cannot be evaluated.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, Proof, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// let hiking = engine.define_symbol("Hiking", SymbolType::Boolean)?;
+    ///
+    /// let sunny = Fact::new(weather.clone(), FactValue::Text("Sunny".into()));
+    /// engine.assert_fact(weather, FactValue::Text("Sunny".into()))?;
+    /// engine.define_rule(LogicalOperator::AtomicFact(sunny.clone()), Fact::new(hiking.clone(), FactValue::Boolean(true)))?;
+    ///
+    /// let proof = engine.prove_goal(&Fact::new(hiking.clone(), FactValue::Boolean(true)))?.expect("hiking is provable");
+    /// assert!(matches!(&proof, Proof::Rule { rule: 0, premises, .. } if premises == &[Proof::Fact(sunny.clone())]));
+    /// assert_eq!(proof.facts(), vec![&sunny]);
+    /// assert_eq!(engine.prove_goal(&Fact::new(hiking, FactValue::Boolean(false)))?, None);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn prove_goal(&self, goal: &Fact) -> Result<Option<Proof>, EngineError> {
         let mut visited = Vec::new(); // Used to track visited rules for cycle detection
         self.search_for_rules(goal, &mut visited)
     }
 
+    /// Enumerates every solution of a fact pattern, through both the known facts and the rules.
+    ///
+    /// Where `specify_goal` answers whether a single goal holds, a query answers which values make a pattern hold: each
+    /// solution maps the pattern variables of `pattern` to values for which the fact is known or derivable, such as
+    /// every `Activity` derivable from the current facts. Solutions are computed lazily, through Prolog-style
+    /// resolution over the rules, as the returned iterator is consumed; see `Query` for the order of the search.
+    ///
+    /// # Arguments
+    /// * `pattern` - The fact to solve, with pattern variables in the positions to find values for.
+    ///
+    /// # Returns
+    /// * `Result<Query<'_>, EngineError>` - An iterator over the solutions. A ground pattern yields a single empty
+    ///   solution if it holds, and none otherwise.
+    ///
+    /// # Errors
+    /// Returns `EngineError::UnknownSymbol` if the pattern's symbol has not been defined, and
+    /// `EngineError::ArityMismatch` or a type mismatch if the pattern does not fit the symbol's schema. Errors raised
+    /// while evaluating the rules are yielded by the iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// let activity = engine.define_symbol("Activity", SymbolType::Text)?;
+    ///
+    /// engine.assert_fact(weather.clone(), FactValue::Text("Sunny".into()))?;
+    /// engine.assert_fact(activity.clone(), FactValue::Text("Reading".into()))?;
+    /// engine.define_rule(
+    ///     LogicalOperator::AtomicFact(Fact::new(weather, FactValue::Text("Sunny".into()))),
+    ///     Fact::new(activity.clone(), FactValue::Text("Hiking".into())),
+    /// )?;
+    ///
+    /// let activities = engine.query(&Fact::new(activity, FactValue::Variable("what".into())))?
+    ///     .map(|solution| solution.map(|mut bindings| bindings.remove("what")))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(activities, vec![Some(FactValue::Text("Reading".into())), Some(FactValue::Text("Hiking".into()))]);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn query(&self, pattern: &Fact) -> Result<Query<'_>, EngineError> {
+        self.validate_symbol(&pattern.symbol)?;
+        self.validate_fact_value(pattern)?;
+        Ok(Query::new(self, pattern))
+    }
+
+    /// Explains why a goal cannot be satisfied through backward chaining, the counterpart of `prove_goal`.
+    ///
+    /// The search of `specify_goal` is replayed, recording every rule whose conclusion matches the goal along with the
+    /// first condition of its premise that failed: facts that are neither known nor derivable, comparisons with the
+    /// values that did not satisfy their operator, negated conditions that hold, and goals where the cycle check cut
+    /// the search. Conditions on facts that are not known are explained recursively through the rules concluding them.
+    ///
+    /// # Arguments
+    /// * `goal` - The goal the engine attempts to satisfy, represented as a `Fact`.
+    ///
+    /// # Returns
+    /// * `Result<Option<Failure>, EngineError>` - The failure report, or `None` if the goal can be satisfied.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if the premise of a rule tried along the way cannot be evaluated.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    /// let hiking = engine.define_symbol("Hiking", SymbolType::Boolean)?;
+    ///
+    /// let sunny = Fact::new(weather.clone(), FactValue::Text("Sunny".into()));
+    /// engine.assert_fact(weather, FactValue::Text("Rainy".into()))?;
+    /// engine.define_rule(LogicalOperator::AtomicFact(sunny.clone()), Fact::new(hiking.clone(), FactValue::Boolean(true)))?;
+    ///
+    /// let failure = engine.explain_goal(&Fact::new(hiking, FactValue::Boolean(true)))?.expect("hiking is not provable");
+    /// assert_eq!(failure.attempted_rules(), vec![0]);
+    /// assert_eq!(failure.missing_facts(), vec![&sunny]);
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn explain_goal(&self, goal: &Fact) -> Result<Option<Failure>, EngineError> {
+        let mut visited = Vec::new();
+        self.explain_goal_failure(goal, &mut visited)
+    }
+
+    /// Explains why a logical expression does not hold, the counterpart of `is_premise_true`.
+    ///
+    /// The expression is evaluated against the known facts only, as by `is_premise_true`, so facts missing from the
+    /// knowledge base are reported as such without trying the rules that could derive them.
+    ///
+    /// # Arguments
+    /// * `expression` - A reference to the `LogicalOperator` representing the expression to explain.
+    ///
+    /// # Returns
+    /// * `Result<Option<Failure>, EngineError>` - The failure report, or `None` if the expression holds.
+    ///
+    /// # Errors
+    /// Returns an `EngineError` if a comparison within the expression cannot be resolved, as for `is_premise_true`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolic_reasoning_engine::{ComparableValue, Failure, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+    ///
+    /// let mut engine = SymbolicReasoningEngine::new();
+    /// let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+    /// engine.assert_fact(temperature.clone(), FactValue::Integer(14))?;
+    ///
+    /// let warm = LogicalOperator::GreaterThanOrEqualTo(
+    ///     Box::new(ComparableValue::Symbol(temperature)),
+    ///     Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+    /// );
+    /// assert_eq!(engine.explain_premise(&warm)?, Some(Failure::Comparison { operator: ">=".into(), left: 14.0, right: 20.0 }));
+    /// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+    /// ```
+    pub fn explain_premise(&self, expression: &LogicalOperator) -> Result<Option<Failure>, EngineError> {
+        self.explain_failure(expression, &Solution::new(self.variable_bindings.clone()), None)
+    }
+
     /// Attempts to satisfy a specified goal by recursively searching for and applying rules.
     ///
     /// This method forms the core of the backward chaining logic, searching for rules that have conclusions
@@ -1248,43 +3509,198 @@ impl SymbolicReasoningEngine {
     /// * `visited` - A mutable reference to a vector tracking visited goals to prevent cycles.
     ///
     /// # Returns
-    /// * `bool` - True if the goal can be satisfied through backward chaining, false otherwise.
-    fn search_for_rules(&self, goal: &Fact, visited: &mut Vec<Fact>) -> bool {
+    /// * `Result<Option<Proof>, EngineError>` - The proof of the goal if it can be satisfied through backward chaining,
+    ///   `None` otherwise.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if the premise of an applicable rule cannot be evaluated.
+    fn search_for_rules(&self, goal: &Fact, visited: &mut Vec<Fact>) -> Result<Option<Proof>, EngineError> {
         // Step 1: Detect cycle
         if self.detect_cycle(goal, visited) {
-            println!("Cycle detected for goal: {:?}", goal);
-            // Cycle detected, return false to prevent infinite recursion
-            return false;
+            self.print_debug(&format!("Cycle detected for goal: {}", goal));
+            // Cycle detected, return no proof to prevent infinite recursion
+            return Ok(None);
         }
 
         // Step 2: Check if the goal is already a known fact
-        for known_fact in &self.facts {
-            let value = known_fact.value.clone();
-            if value == goal.value {
-                return true;
-            }
+        if let Some(known_fact) = self.facts.candidates(goal).find(|known_fact| self.matches_pattern(goal, known_fact)) {
+            return Ok(Some(Proof::Fact(known_fact.clone())));
         }
 
         visited.push(goal.clone()); // Add the current goal to the visited list
 
-        // Step 2: Search for rules that could lead to the goal
-        let applicable_rules = self.rules.iter().filter(|rule| {
-            // Check if the rule's conclusion matches the goal
-            &rule.conclusion == goal
-        });
+        // Step 3: Attempt to satisfy the conditions of each rule whose conclusion matches the goal
+        let mut proof = None;
+        'rules: for (index, rule) in self.rules.iter().enumerate() {
+            for conclusion in rule.conclusions() {
+                // Bind the variables of the rule's conclusion to the corresponding values of the goal
+                let Some(bindings) = self.unify_fact(conclusion, goal, &self.variable_bindings) else {
+                    continue;
+                };
 
-        // Step 3: Attempt to satisfy the conditions of each applicable rule
-        for rule in applicable_rules {
-            // Recursively apply backward chaining on the rule's conditions
-            if let Some(_) = self.evaluate_logical_expression(&rule.premise, &self.variable_bindings, true, &mut Some(visited)) {
-                return true;
+                // Recursively apply backward chaining on the rule's conditions
+                match self.evaluate_logical_expression(&rule.premise, &Solution::new(bindings), Some(visited)) {
+                    Ok(mut solutions) if !solutions.is_empty() => {
+                        let premises = solutions.swap_remove(0).proofs;
+                        proof = Some(Proof::Rule { goal: goal.clone(), rule: index, premises });
+                        break 'rules;
+                    },
+                    Ok(_) => {},
+                    Err(error) => {
+                        visited.pop();
+                        return Err(error.in_rule(index));
+                    },
+                }
             }
         }
 
         visited.pop(); // Clean up to allow revisiting this goal from different paths
 
-        // If no rules lead to satisfying the goal, return false
-        false
+        Ok(proof)
+    }
+
+    /// Replays the search of `search_for_rules` for a goal, explaining why it fails.
+    ///
+    /// # Arguments
+    /// * `goal` - The goal the system is trying to satisfy, represented as a `Fact`.
+    /// * `visited` - A mutable reference to a vector tracking visited goals to prevent cycles.
+    ///
+    /// # Returns
+    /// * `Result<Option<Failure>, EngineError>` - `Failure::Cycle` if the cycle check cuts the search,
+    ///   `Failure::MissingFact` if the goal is neither known nor concluded by any rule, `Failure::Goal` listing the
+    ///   rules tried otherwise, or `None` if the goal can be satisfied.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if the premise of an applicable rule cannot be evaluated.
+    fn explain_goal_failure(&self, goal: &Fact, visited: &mut Vec<Fact>) -> Result<Option<Failure>, EngineError> {
+        if self.detect_cycle(goal, visited) {
+            return Ok(Some(Failure::Cycle(goal.clone())));
+        }
+        if self.facts.candidates(goal).any(|known_fact| self.matches_pattern(goal, known_fact)) {
+            return Ok(None);
+        }
+
+        visited.push(goal.clone());
+
+        let mut attempts = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            for conclusion in rule.conclusions() {
+                let Some(bindings) = self.unify_fact(conclusion, goal, &self.variable_bindings) else {
+                    continue;
+                };
+
+                match self.explain_failure(&rule.premise, &Solution::new(bindings), Some(visited)) {
+                    Ok(Some(failure)) => attempts.push(RuleAttempt::new(index, failure)),
+                    Ok(None) => {
+                        visited.pop();
+                        return Ok(None);
+                    },
+                    Err(error) => {
+                        visited.pop();
+                        return Err(error.in_rule(index));
+                    },
+                }
+            }
+        }
+
+        visited.pop();
+
+        if attempts.is_empty() {
+            Ok(Some(Failure::MissingFact(goal.clone())))
+        } else {
+            Ok(Some(Failure::Goal { goal: goal.clone(), attempts }))
+        }
+    }
+
+    /// Explains why a logical expression has no solution extending the existing one, mirroring
+    /// `evaluate_logical_expression`.
+    ///
+    /// Conjunctions are explained by their first condition without a solution, given the first solution of the
+    /// conditions before it, and disjunctions by the failure of each alternative.
+    ///
+    /// # Arguments
+    /// * `expression` - The logical expression to explain.
+    /// * `existing` - The solution the expression is evaluated against.
+    /// * `visited` - The goals visited so far when backward chaining, or `None` to evaluate against known facts only.
+    ///
+    /// # Returns
+    /// * `Result<Option<Failure>, EngineError>` - Why the expression fails, or `None` if it holds.
+    ///
+    /// # Errors
+    /// Returns an `EngineError` if a comparison within the expression cannot be resolved.
+    fn explain_failure(&self, expression: &LogicalOperator, existing: &Solution, mut visited: Option<&mut Vec<Fact>>) -> Result<Option<Failure>, EngineError> {
+        let bindings = &existing.bindings;
+        let (holds, proof) = match expression {
+            LogicalOperator::And(expressions) => {
+                let mut solutions = vec![existing.clone()];
+                for expr in expressions {
+                    let mut joined_solutions = Vec::new();
+                    for solution in &solutions {
+                        joined_solutions.extend(self.evaluate_logical_expression(expr, solution, visited.as_deref_mut())?);
+                    }
+                    if joined_solutions.is_empty() {
+                        return self.explain_failure(expr, &solutions[0], visited);
+                    }
+                    solutions = joined_solutions;
+                }
+                return Ok(None);
+            },
+            LogicalOperator::Or(expressions) => {
+                let mut failures = Vec::new();
+                for expr in expressions {
+                    match self.explain_failure(expr, existing, visited.as_deref_mut())? {
+                        Some(failure) => failures.push(failure),
+                        None => return Ok(None),
+                    }
+                }
+                return Ok(Some(Failure::Alternatives(failures)));
+            },
+            LogicalOperator::Not(expression) => {
+                let solutions = self.evaluate_logical_expression(expression, existing, visited)?;
+                let Some(solution) = solutions.first() else {
+                    return Ok(None);
+                };
+
+                // Show the fact that made the negated condition hold
+                let negated = match expression.as_ref() {
+                    LogicalOperator::AtomicFact(fact) => {
+                        let pattern = self.substitute_bindings(&self.interpolate_fact(fact, false), bindings);
+                        LogicalOperator::AtomicFact(self.substitute_bindings(&pattern, &solution.bindings))
+                    },
+                    expression => expression.clone(),
+                };
+                return Ok(Some(Failure::Negation(negated)));
+            },
+            LogicalOperator::AtomicFact(fact) => {
+                let pattern = self.substitute_bindings(&self.interpolate_fact(fact, false), bindings);
+                if self.facts.candidates(&pattern).any(|known_fact| self.unify_fact(&pattern, known_fact, bindings).is_some()) {
+                    return Ok(None);
+                }
+
+                // When backward chaining, explain why the fact cannot be derived through the rules
+                return match visited {
+                    Some(visited_facts) if pattern.is_ground() => self.explain_goal_failure(&pattern, visited_facts),
+                    _ => Ok(Some(Failure::MissingFact(pattern))),
+                };
+            },
+            LogicalOperator::GreaterThan(left, right) => self.compare_values(">", left, right, bindings, |a, b| a > b)?,
+            LogicalOperator::LessThan(left, right) => self.compare_values("<", left, right, bindings, |a, b| a < b)?,
+            LogicalOperator::EqualTo(left, right) => self.compare_equality("==", left, right, bindings)?,
+            LogicalOperator::NotEqualTo(left, right) => {
+                let (equal, proof) = self.compare_equality("!=", left, right, bindings)?;
+                (!equal, proof)
+            },
+            LogicalOperator::GreaterThanOrEqualTo(left, right) => self.compare_values(">=", left, right, bindings, |a, b| a >= b)?,
+            LogicalOperator::LessThanOrEqualTo(left, right) => self.compare_values("<=", left, right, bindings, |a, b| a <= b)?,
+        };
+
+        Ok(match proof {
+            _ if holds => None,
+            Proof::Comparison { operator, left, right } => Some(Failure::Comparison { operator, left, right }),
+            Proof::Equality { operator, left, right } => Some(Failure::Equality { operator, left, right }),
+            // Comparisons only ever produce the proofs above
+            _ => None,
+        })
     }
 
     /// Detects cycles within the rule evaluation process to prevent infinite recursion.
@@ -1311,7 +3727,7 @@ impl SymbolicReasoningEngine {
     /// // Assuming an instance `engine` of `SymbolicReasoningEngine`
     /// // and a setup where "goal_fact" might cause a cyclic rule dependency
     /// //let mut visited = Vec::new();
-    /// //let goal_fact = Fact::new(Symbol::new("example_symbol", "Integer"), FactValue::Integer(1));
+    /// //let goal_fact = Fact::new(Symbol::new("example_symbol", SymbolType::Integer), FactValue::Integer(1));
     ///
     /// // Initially, no cycle is detected
     /// //assert_eq!(engine.detect_cycle(&goal_fact, &visited), false);
@@ -1327,28 +3743,44 @@ impl SymbolicReasoningEngine {
     }
 }
 
+impl Default for SymbolicReasoningEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Returns a pattern variable term.
+    fn var(name: &str) -> FactValue {
+        FactValue::Variable(name.to_string())
+    }
+
+    /// Returns a fact pattern requiring the predicate to hold for the given terms.
+    fn atom(symbol: &Symbol, terms: Vec<FactValue>) -> LogicalOperator {
+        LogicalOperator::AtomicFact(Fact::with_arguments(symbol.clone(), terms, FactValue::Boolean(true)))
+    }
+
     #[test]
     fn fact_value_types() {
-        let symbol = Symbol { name: "Temperature".to_string(), symbol_type: "Integer".to_string() };
+        let symbol = Symbol::new("Temperature", SymbolType::Integer);
 
         // Integer Value
-        let int_fact = Fact { symbol: symbol.clone(), value: FactValue::Integer(30) };
+        let int_fact = Fact { symbol: symbol.clone(), arguments: Vec::new(), value: FactValue::Integer(30) };
         assert_eq!(int_fact.value, FactValue::Integer(30));
 
         // Float Value
-        let float_fact = Fact { symbol: symbol.clone(), value: FactValue::Float(25.5) };
+        let float_fact = Fact { symbol: symbol.clone(), arguments: Vec::new(), value: FactValue::Float(25.5) };
         assert_eq!(float_fact.value, FactValue::Float(25.5));
 
         // Boolean Value
-        let bool_fact = Fact { symbol: symbol.clone(), value: FactValue::Boolean(true) };
+        let bool_fact = Fact { symbol: symbol.clone(), arguments: Vec::new(), value: FactValue::Boolean(true) };
         assert_eq!(bool_fact.value, FactValue::Boolean(true));
 
         // Text Value
-        let text_fact = Fact { symbol, value: FactValue::Text("Warm".to_string()) };
+        let text_fact = Fact { symbol, arguments: Vec::new(), value: FactValue::Text("Warm".to_string()) };
         assert_eq!(text_fact.value, FactValue::Text("Warm".to_string()));
     }
 
@@ -1357,30 +3789,33 @@ mod tests {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define symbols
-        let weather_symbol = engine.define_symbol("Weather", "String");
-        let activity_symbol = engine.define_symbol("Activity", "String");
+        let weather_symbol = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let activity_symbol = engine.define_symbol("Activity", SymbolType::Text).unwrap();
 
         // Assert the fact: It is sunny
-        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Sunny".to_string()));
+        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Sunny".to_string())).unwrap();
 
         // Define the rule: If it is sunny, then it's a good day for outdoor activity
         engine.define_rule(
             LogicalOperator::AtomicFact(Fact {
                 symbol: weather_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("Sunny".to_string()),
             }),
             Fact {
                 symbol: activity_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("Outdoor".to_string()),
             },
-        );
+        ).unwrap();
 
         // Perform forward chaining to infer new facts based on the rules
-        engine.forward_chaining();
+        engine.forward_chaining().unwrap();
 
         // Check if the new fact (good day for outdoor activity) is added to the knowledge base
         assert!(engine.facts.contains(&Fact {
             symbol: activity_symbol,
+            arguments: Vec::new(),
             value: FactValue::Text("Outdoor".to_string()),
         }), "The engine did not infer that it's a good day for outdoor activity when it's sunny.");
     }
@@ -1391,17 +3826,17 @@ mod tests {
         engine.enable_debug();
 
         // Define symbols
-        let location_symbol = engine.define_symbol("Location", "String");
-        let temp_symbol = engine.define_symbol("Temperature", "Integer");
-        let condition_symbol = engine.define_symbol("Condition", "String");
+        let location_symbol = engine.define_symbol("Location", SymbolType::Text).unwrap();
+        let temp_symbol = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
+        let condition_symbol = engine.define_symbol("Condition", SymbolType::Text).unwrap();
 
         // Assert the variable temp = 30
         let temp_variable = Variable { name: "temp".to_string(), value: FactValue::Integer(30), state: VariableState::Stable };
         engine.assert_variable(&temp_variable);
 
         // Assert the fact: Location 'Desert' has a temperature of ${temp} degrees
-        engine.assert_fact(location_symbol.clone(), FactValue::Text("Desert".to_string()));
-        engine.assert_fact(temp_symbol.clone(), FactValue::Text("${temp}".to_string()));
+        engine.assert_fact(location_symbol.clone(), FactValue::Text("Desert".to_string())).unwrap();
+        engine.assert_fact(temp_symbol.clone(), FactValue::Text("${temp}".to_string())).unwrap();
 
         // Define the rule: If a location's temperature is above 25, it's considered hot
         engine.define_rule(
@@ -1411,13 +3846,14 @@ mod tests {
             ),
             Fact {
                 symbol: condition_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("Hot".to_string()),
             }
-        );
+        ).unwrap();
 
         // Simulate matching a variable within the rule's premise to the known facts
         // and applying the conclusion based on this match
-        engine.forward_chaining_with_variables();
+        engine.forward_chaining_with_variables().unwrap();
 
         println!("Defined symbols: {:?}", engine.symbols);
         println!("Asserted facts: {:?}", engine.facts);
@@ -1434,28 +3870,30 @@ mod tests {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define symbols
-        let weather_symbol = engine.define_symbol("Weather", "String");
-        let recommendation_symbol = engine.define_symbol("Recommendation", "String");
+        let weather_symbol = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let recommendation_symbol = engine.define_symbol("Recommendation", SymbolType::Text).unwrap();
 
         // Assert known facts: It is raining, and an umbrella is recommended
-        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Rainy".to_string()));
-        engine.assert_fact(recommendation_symbol.clone(), FactValue::Text("Umbrella".to_string()));
+        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Rainy".to_string())).unwrap();
+        engine.assert_fact(recommendation_symbol.clone(), FactValue::Text("Umbrella".to_string())).unwrap();
 
         // Define the rule: If it is raining, then an umbrella is recommended
         engine.define_rule(
             LogicalOperator::AtomicFact(Fact {
                 symbol: weather_symbol,
+                arguments: Vec::new(),
                 value: FactValue::Text("Rainy".to_string()),
             }),
             Fact {
                 symbol: recommendation_symbol,
+                arguments: Vec::new(),
                 value: FactValue::Text("Umbrella".to_string()),
             }
-        );
+        ).unwrap();
 
         // Perform forward chaining to infer new facts based on the rules
         let initial_fact_count = engine.facts.len();
-        engine.forward_chaining();
+        engine.forward_chaining().unwrap();
 
         // Check if no new facts were added to the knowledge base
         let final_fact_count = engine.facts.len();
@@ -1467,13 +3905,13 @@ mod tests {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define symbols
-        let weather_symbol = engine.define_symbol("Weather", "String");
-        let temp_symbol = engine.define_symbol("Temperature", "Integer");
-        let activity_symbol = engine.define_symbol("Activity", "String");
+        let weather_symbol = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let temp_symbol = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
+        let activity_symbol = engine.define_symbol("Activity", SymbolType::Text).unwrap();
 
         // Assert facts: It is not raining, and the temperature is 25 degrees
-        engine.assert_fact(weather_symbol.clone(), FactValue::Text("NotRaining".to_string()));
-        engine.assert_fact(temp_symbol.clone(), FactValue::Integer(25));
+        engine.assert_fact(weather_symbol.clone(), FactValue::Text("NotRaining".to_string())).unwrap();
+        engine.assert_fact(temp_symbol.clone(), FactValue::Integer(25)).unwrap();
 
         // Define the rule with nested logical expressions
         engine.define_rule(
@@ -1481,10 +3919,12 @@ mod tests {
                 LogicalOperator::Or(vec![
                     LogicalOperator::AtomicFact(Fact {
                         symbol: weather_symbol.clone(),
+                        arguments: Vec::new(),
                         value: FactValue::Text("Sunny".to_string()),
                     }),
                     LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact {
                         symbol: weather_symbol.clone(),
+                        arguments: Vec::new(),
                         value: FactValue::Text("Raining".to_string()),
                     }))),
                 ]),
@@ -1495,12 +3935,13 @@ mod tests {
             ]),
             Fact {
                 symbol: activity_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("GoodForOutdoor".to_string()),
             }
-        );
+        ).unwrap();
 
         // Perform forward chaining to infer new facts based on the rules
-        engine.forward_chaining_with_variables();
+        engine.forward_chaining_with_variables().unwrap();
 
         // Check if the new fact (good day for outdoor activities) is added to the knowledge base
         assert!(engine.facts.iter().any(|fact|
@@ -1509,31 +3950,31 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Symbol already defined")]
-    fn test_adding_duplicate_symbol_panics() {
+    fn test_adding_duplicate_symbol_errors() {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define a symbol "temperature" of type "Integer"
         let symbol_name = "temperature";
-        let symbol_type = "Integer";
-        engine.define_symbol(symbol_name, symbol_type);
+        let symbol_type = SymbolType::Integer;
+        engine.define_symbol(symbol_name, symbol_type.clone()).unwrap();
 
         // Attempt to define the same symbol "temperature" of type "Integer" again
-        // This should panic with the specified message "Symbol already defined"
-        engine.define_symbol(symbol_name, symbol_type);
+        // This should be rejected with a DuplicateSymbol error
+        let result = engine.define_symbol(symbol_name, symbol_type);
+        assert_eq!(result, Err(EngineError::DuplicateSymbol { symbol: symbol_name.to_string() }));
     }
 
     #[test]
     fn test_goal_specification() {
         let mut engine = SymbolicReasoningEngine::new();
 
-        let temp_symbol = engine.define_symbol("Temperature", "String");
-        let weather_symbol = engine.define_symbol("Weather", "String");
-        let hiking_symbol = engine.define_symbol("Hiking", "String");
+        let temp_symbol = engine.define_symbol("Temperature", SymbolType::Text).unwrap();
+        let weather_symbol = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let hiking_symbol = engine.define_symbol("Hiking", SymbolType::Boolean).unwrap();
 
         // Assert facts
-        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Sunny".into()));
-        engine.assert_fact(temp_symbol.clone(), FactValue::Text("Moderate".into()));
+        engine.assert_fact(weather_symbol.clone(), FactValue::Text("Sunny".into())).unwrap();
+        engine.assert_fact(temp_symbol.clone(), FactValue::Text("Moderate".into())).unwrap();
 
         // Define rule for hiking suitability
         engine.define_rule(
@@ -1542,16 +3983,16 @@ mod tests {
                 LogicalOperator::AtomicFact(Fact::new(temp_symbol.clone(), FactValue::Text("Moderate".into()))),
             ]),
             Fact::new(hiking_symbol.clone(), FactValue::Boolean(true))
-        );
+        ).unwrap();
 
         // Specify the goal
         let goal = Fact::new(hiking_symbol.clone(), FactValue::Boolean(true));
 
         // Test if the engine can determine the goal
-        let result = engine.specify_goal(&goal);
+        let result = engine.specify_goal(&goal).unwrap();
 
         // Assert that the engine successfully finds the solution to the specified goal
-        assert_eq!(result, true, "The engine should successfully determine that hiking is suitable.");
+        assert!(result, "The engine should successfully determine that hiking is suitable.");
     }
 
     #[test]
@@ -1560,38 +4001,37 @@ mod tests {
         engine.enable_debug();
 
         // Define symbols for the test
-        let weather = engine.define_symbol("Weather", "String");
-        let temperature = engine.define_symbol("Temperature", "Integer");
+        let weather = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let temperature = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
 
         // Assert known facts into the engine's knowledge base
-        engine.assert_fact(temperature.clone(), FactValue::Integer(25));
-        engine.assert_fact(weather.clone(), FactValue::Text(String::from("Sunny")));
+        engine.assert_fact(temperature.clone(), FactValue::Integer(25)).unwrap();
+        engine.assert_fact(weather.clone(), FactValue::Text(String::from("Sunny"))).unwrap();
 
         // Define rules that require recursion to satisfy the goal
         // Rule 1: If temperature > 20, then it's warm
-        let warm = engine.define_symbol("warm", "Boolean");
+        let warm = engine.define_symbol("warm", SymbolType::Boolean).unwrap();
         engine.define_rule(
             LogicalOperator::GreaterThan(
                 Box::new(ComparableValue::Symbol(temperature.clone())),
                 Box::new(ComparableValue::Direct(FactValue::Integer(20)))
             ),
-            // Fact::new(warm.clone(), FactValue::Boolean(true))
-            Fact::new(warm.clone(), FactValue::Text("Warm".into()))
-        );
+            Fact::new(warm.clone(), FactValue::Boolean(true))
+        ).unwrap();
 
         // Rule 2: If it's warm and sunny, then it's a good day for a picnic
-        let picnic_day = engine.define_symbol("picnic_advisable", "Boolean");
+        let picnic_day = engine.define_symbol("picnic_advisable", SymbolType::Boolean).unwrap();
         engine.define_rule(
             LogicalOperator::And(vec![
-                LogicalOperator::AtomicFact(Fact::new(warm.clone(), FactValue::Text("Warm".into()))),
+                LogicalOperator::AtomicFact(Fact::new(warm.clone(), FactValue::Boolean(true))),
                 LogicalOperator::AtomicFact(Fact::new(weather.clone(), FactValue::Text(String::from("Sunny"))))
             ]),
             Fact::new(picnic_day.clone(), FactValue::Boolean(true))
-        );
+        ).unwrap();
 
         // Specify the goal: To determine if it's a picnic day
         let goal = Fact::new(picnic_day, FactValue::Boolean(true));
-        let is_picnic_day = engine.specify_goal(&goal);
+        let is_picnic_day = engine.specify_goal(&goal).unwrap();
 
         // Assert that the engine successfully determines it's a beach day through backward chaining
         assert!(is_picnic_day, "The engine should successfully determine it's a picnic day through backward chaining.");
@@ -1601,44 +4041,44 @@ mod tests {
     fn test_cycle_detection() {
         let mut engine = SymbolicReasoningEngine::new();
 
-        let a_symbol = engine.define_symbol("A", "Boolean");
-        let b_symbol = engine.define_symbol("B", "Boolean");
-        let c_symbol = engine.define_symbol("C", "Boolean");
+        let a_symbol = engine.define_symbol("A", SymbolType::Boolean).unwrap();
+        let b_symbol = engine.define_symbol("B", SymbolType::Boolean).unwrap();
+        let c_symbol = engine.define_symbol("C", SymbolType::Boolean).unwrap();
 
         engine.define_rule(
             LogicalOperator::AtomicFact(Fact::new(b_symbol.clone(), FactValue::Boolean(true))),
             Fact::new(a_symbol.clone(), FactValue::Boolean(true))
-        );
+        ).unwrap();
         engine.define_rule(
             LogicalOperator::AtomicFact(Fact::new(c_symbol.clone(), FactValue::Boolean(true))),
             Fact::new(b_symbol.clone(), FactValue::Boolean(true))
-        );
+        ).unwrap();
         engine.define_rule(
             LogicalOperator::AtomicFact(Fact::new(a_symbol.clone(), FactValue::Boolean(true))),
             Fact::new(c_symbol.clone(), FactValue::Boolean(true))
-        );
+        ).unwrap();
 
         // Define a goal that would trigger the cycle
         let goal = Fact::new(a_symbol.clone(), FactValue::Boolean(true));
 
         // Attempting to satisfy this goal should not cause an infinite loop and should return false,
         // indicating that the cycle was detected and the engine continued operation.
-        let result = engine.specify_goal(&goal);
+        let result = engine.specify_goal(&goal).unwrap();
 
-        assert_eq!(result, false, "The engine should detect the cycle and not satisfy the goal.");
+        assert!(!result, "The engine should detect the cycle and not satisfy the goal.");
     }
 
     #[test]
-    #[should_panic(expected = "Symbol not found in knowledge base")]
-    fn test_symbol_not_found_panic() {
+    fn test_symbol_not_found_error() {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define symbols
-        let a_symbol = engine.define_symbol("A", "Integer");
-        let b_symbol = engine.define_symbol("B", "String");
+        let a_symbol = engine.define_symbol("A", SymbolType::Integer).unwrap();
+        let b_symbol = engine.define_symbol("B", SymbolType::Integer).unwrap();
+        let c_symbol = engine.define_symbol("C", SymbolType::Text).unwrap();
 
         // Assert the fact: A = 0
-        engine.assert_fact(a_symbol.clone(), FactValue::Integer(0));
+        engine.assert_fact(a_symbol.clone(), FactValue::Integer(0)).unwrap();
 
         // Define the [invalid] rule: If non-existent symbol is greater than 0
         engine.define_rule(
@@ -1647,27 +4087,29 @@ mod tests {
                 Box::new(ComparableValue::Direct(FactValue::Integer(0)))
             ),
             Fact {
-                symbol: b_symbol.clone(),
+                symbol: c_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("C".to_string()),
             },
-        );
+        ).unwrap();
 
         // Perform forward chaining to infer new facts based on the rules.
-        // This should invoke a panic based on our invalid rules.
-        engine.forward_chaining();
+        // This should report the missing fact for the rule's symbol.
+        let result = engine.forward_chaining();
+        assert_eq!(result, Err(EngineError::MissingFact { symbol: "B".to_string() }.in_rule(0)));
+        assert_eq!(engine.facts.len(), 1, "No facts should be added when forward chaining fails.");
     }
 
     #[test]
-    #[should_panic(expected = "Symbol not found in knowledge base")]
-    fn test_symbol_name_not_found_panic() {
+    fn test_symbol_name_not_found_error() {
         let mut engine = SymbolicReasoningEngine::new();
 
         // Define symbols
-        let a_symbol = engine.define_symbol("A", "Integer");
-        let b_symbol = engine.define_symbol("B", "String");
+        let a_symbol = engine.define_symbol("A", SymbolType::Integer).unwrap();
+        let b_symbol = engine.define_symbol("B", SymbolType::Text).unwrap();
 
         // Assert the fact: A = 0
-        engine.assert_fact(a_symbol.clone(), FactValue::Integer(0));
+        engine.assert_fact(a_symbol.clone(), FactValue::Integer(0)).unwrap();
 
         // Define the [invalid] rule: If non-existent symbol name is greater than 0
         engine.define_rule(
@@ -1677,12 +4119,1154 @@ mod tests {
             ),
             Fact {
                 symbol: b_symbol.clone(),
+                arguments: Vec::new(),
                 value: FactValue::Text("C".to_string()),
             },
-        );
+        ).unwrap();
 
         // Perform forward chaining to infer new facts based on the rules.
-        // This should invoke a panic based on our invalid rules.
-        engine.forward_chaining();
+        // This should report the undefined symbol name.
+        let result = engine.forward_chaining();
+        assert_eq!(result, Err(EngineError::UnknownSymbol { symbol: "InvalidSymbol".to_string() }.in_rule(0)));
+    }
+
+    #[test]
+    fn test_non_numeric_comparison_error() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let flag_symbol = engine.define_symbol("Flag", SymbolType::Boolean).unwrap();
+        let weather_symbol = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let temp_symbol = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
+
+        engine.assert_fact(flag_symbol.clone(), FactValue::Boolean(true)).unwrap();
+
+        // Booleans cannot be compared numerically
+        let result = engine.is_premise_true(&LogicalOperator::GreaterThan(
+            Box::new(ComparableValue::Symbol(flag_symbol.clone())),
+            Box::new(ComparableValue::Direct(FactValue::Integer(0)))
+        ));
+        assert_eq!(result, Err(EngineError::NonNumericComparison {
+            symbol: Some("Flag".to_string()),
+            value: FactValue::Boolean(true),
+        }));
+
+        // A numeric symbol cannot hold non-numeric text
+        let result = engine.assert_fact(temp_symbol.clone(), FactValue::Text("warm".into()));
+        assert_eq!(result, Err(EngineError::TypeMismatch {
+            symbol: "Temperature".to_string(),
+            expected: SymbolType::Integer,
+            found: FactValue::Text("warm".into()),
+        }));
+        assert_eq!(engine.facts.len(), 1);
+
+        // Ordering text against a number is rejected when the rule is defined
+        let result = engine.define_rule(
+            LogicalOperator::GreaterThan(
+                Box::new(ComparableValue::Symbol(weather_symbol.clone())),
+                Box::new(ComparableValue::Symbol(temp_symbol.clone()))
+            ),
+            Fact::new(flag_symbol.clone(), FactValue::Boolean(false))
+        );
+        assert_eq!(result, Err(EngineError::IncompatibleComparison {
+            operator: ">".to_string(),
+            left: SymbolType::Text,
+            right: SymbolType::Integer,
+        }));
+
+        // Rules referencing undefined symbols are rejected up front
+        let undefined = Symbol::new("Undefined", SymbolType::Integer);
+        let result = engine.define_rule(
+            LogicalOperator::AtomicFact(Fact::new(undefined, FactValue::Integer(1))),
+            Fact::new(weather_symbol, FactValue::Text("Sunny".into()))
+        );
+        assert_eq!(result, Err(EngineError::UnknownSymbol { symbol: "Undefined".to_string() }));
+        assert!(engine.rules.is_empty());
+    }
+
+    #[test]
+    fn test_predicate_facts_with_arguments() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let temperature = engine.define_predicate("Temperature", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let heating = engine.define_predicate("Heating", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let room = |name: &str| vec![FactValue::Text(name.to_string())];
+
+        engine.assert_predicate(temperature.clone(), room("room1"), FactValue::Integer(22)).unwrap();
+        engine.assert_predicate(temperature.clone(), room("room2"), FactValue::Integer(16)).unwrap();
+
+        // Arguments are checked against the predicate's signature
+        let result = engine.assert_fact(temperature.clone(), FactValue::Integer(20));
+        assert_eq!(result, Err(EngineError::ArityMismatch { symbol: "Temperature".to_string(), expected: 1, found: 0 }));
+        let result = engine.assert_predicate(temperature.clone(), vec![FactValue::Integer(3)], FactValue::Integer(20));
+        assert_eq!(result, Err(EngineError::ArgumentTypeMismatch {
+            symbol: "Temperature".to_string(),
+            position: 0,
+            expected: SymbolType::Text,
+            found: FactValue::Integer(3),
+        }));
+
+        // Facts with the same symbol but different arguments are distinct
+        engine.define_rule(
+            LogicalOperator::AtomicFact(Fact::with_arguments(temperature.clone(), room("room2"), FactValue::Integer(16))),
+            Fact::with_arguments(heating.clone(), room("room2"), FactValue::Boolean(true))
+        ).unwrap();
+        engine.forward_chaining().unwrap();
+
+        assert!(engine.facts.contains(&Fact::with_arguments(heating.clone(), room("room2"), FactValue::Boolean(true))));
+        assert!(!engine.specify_goal(&Fact::with_arguments(heating.clone(), room("room1"), FactValue::Boolean(true))).unwrap());
+        assert!(engine.specify_goal(&Fact::with_arguments(temperature.clone(), room("room1"), FactValue::Integer(22))).unwrap());
+
+        // Comparisons read the value of plain symbols, so predicates cannot be compared without arguments
+        let result = engine.define_rule(
+            LogicalOperator::GreaterThan(
+                Box::new(ComparableValue::Symbol(temperature)),
+                Box::new(ComparableValue::Direct(FactValue::Integer(20)))
+            ),
+            Fact::with_arguments(heating, room("room1"), FactValue::Boolean(false))
+        );
+        assert!(matches!(result, Err(EngineError::ArityMismatch { expected: 1, found: 0, .. })));
+    }
+
+    #[test]
+    fn test_pattern_variables_unify_with_facts() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let grandparent = engine.define_predicate("Grandparent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let temperature = engine.define_predicate("Temperature", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let warm = engine.define_predicate("Warm", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let text = |value: &str| FactValue::Text(value.to_string());
+
+        for (older, younger) in [("alice", "bob"), ("bob", "carol"), ("bob", "dave")] {
+            engine.assert_predicate(parent.clone(), vec![text(older), text(younger)], FactValue::Boolean(true)).unwrap();
+        }
+        engine.assert_predicate(temperature.clone(), vec![text("room1")], FactValue::Integer(24)).unwrap();
+        engine.assert_predicate(temperature.clone(), vec![text("room2")], FactValue::Integer(17)).unwrap();
+
+        // Pattern variables can only appear in rules and goals
+        let result = engine.assert_predicate(parent.clone(), vec![var("x"), text("bob")], FactValue::Boolean(true));
+        assert_eq!(result, Err(EngineError::UnboundVariable { name: "x".to_string() }));
+
+        // Variables shared between conjuncts must unify with the same value
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                atom(&parent, vec![var("x"), var("y")]),
+                atom(&parent, vec![var("y"), var("z")]),
+            ]),
+            Fact::with_arguments(grandparent.clone(), vec![var("x"), var("z")], FactValue::Boolean(true))
+        ).unwrap();
+
+        // Comparisons use the values bound by earlier conjuncts
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(Fact::with_arguments(temperature.clone(), vec![var("room")], var("t"))),
+                LogicalOperator::GreaterThan(
+                    Box::new(ComparableValue::Direct(var("t"))),
+                    Box::new(ComparableValue::Direct(FactValue::Integer(20)))
+                ),
+            ]),
+            Fact::with_arguments(warm.clone(), vec![var("room")], FactValue::Boolean(true))
+        ).unwrap();
+
+        // Conclusions cannot introduce variables the premise does not bind
+        let result = engine.define_rule(
+            LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(
+                Fact::with_arguments(parent.clone(), vec![var("x"), text("alice")], FactValue::Boolean(true))
+            ))),
+            Fact::with_arguments(warm.clone(), vec![var("x")], FactValue::Boolean(true))
+        );
+        assert_eq!(result, Err(EngineError::UnboundVariable { name: "x".to_string() }));
+
+        // Backward chaining binds the conclusion's variables from the goal
+        let goal = Fact::with_arguments(grandparent.clone(), vec![text("alice"), text("dave")], FactValue::Boolean(true));
+        assert!(engine.specify_goal(&goal).unwrap());
+        let goal = Fact::with_arguments(grandparent.clone(), vec![text("bob"), text("dave")], FactValue::Boolean(true));
+        assert!(!engine.specify_goal(&goal).unwrap());
+
+        engine.forward_chaining_with_variables().unwrap();
+
+        let derived: Vec<_> = engine.facts.iter().filter(|fact| fact.symbol == grandparent).map(|fact| fact.arguments.clone()).collect();
+        assert_eq!(derived, vec![vec![text("alice"), text("carol")], vec![text("alice"), text("dave")]]);
+        assert!(engine.facts.contains(&Fact::with_arguments(warm.clone(), vec![text("room1")], FactValue::Boolean(true))));
+        assert!(!engine.facts.contains(&Fact::with_arguments(warm, vec![text("room2")], FactValue::Boolean(true))));
+    }
+
+    #[test]
+    fn test_conclusion_templates_use_match_bindings() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let celsius = engine.define_predicate("Celsius", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let fahrenheit = engine.define_predicate("Fahrenheit", vec![SymbolType::Text], SymbolType::Float).unwrap();
+        let report = engine.define_predicate("Report", vec![SymbolType::Text], SymbolType::Text).unwrap();
+        let text = |value: &str| FactValue::Text(value.to_string());
+
+        engine.assert_predicate(celsius.clone(), vec![text("Oslo")], FactValue::Integer(10)).unwrap();
+        engine.assert_predicate(celsius.clone(), vec![text("Lyon")], FactValue::Integer(25)).unwrap();
+
+        let premise = LogicalOperator::AtomicFact(Fact::with_arguments(celsius.clone(), vec![var("city")], var("t")));
+        engine.define_rule(premise.clone(), Fact::with_arguments(fahrenheit.clone(), vec![var("city")], text("${t * 9 / 5 + 32}"))).unwrap();
+        engine.define_rule(premise.clone(), Fact::with_arguments(report.clone(), vec![var("city")], text("${city} is ${t} degrees"))).unwrap();
+
+        // Malformed placeholders are rejected when the rule is defined
+        let result = engine.define_rule(premise, Fact::with_arguments(report.clone(), vec![var("city")], text("${t +}")));
+        assert!(matches!(result, Err(EngineError::InvalidTemplate { .. })));
+
+        engine.forward_chaining_with_variables().unwrap();
+
+        // One fact is derived per match, with computed values converted to the declared type
+        assert!(engine.facts.contains(&Fact::with_arguments(fahrenheit.clone(), vec![text("Oslo")], FactValue::Float(50.0))));
+        assert!(engine.facts.contains(&Fact::with_arguments(fahrenheit, vec![text("Lyon")], FactValue::Float(77.0))));
+        assert!(engine.facts.contains(&Fact::with_arguments(report.clone(), vec![text("Oslo")], text("Oslo is 10 degrees"))));
+        assert!(engine.facts.contains(&Fact::with_arguments(report, vec![text("Lyon")], text("Lyon is 25 degrees"))));
+        assert_eq!(engine.facts.len(), 6);
+    }
+
+    #[test]
+    fn test_rule_actions_execute_atomically() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let reading = engine.define_predicate("Reading", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let alert = engine.define_predicate("Alert", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let pending = engine.define_predicate("Pending", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let status = engine.define_symbol("Status", SymbolType::Text).unwrap();
+        let text = |value: &str| FactValue::Text(value.to_string());
+
+        engine.assert_predicate(reading.clone(), vec![text("s1")], FactValue::Integer(90)).unwrap();
+        engine.assert_predicate(pending.clone(), vec![text("s1")], FactValue::Boolean(true)).unwrap();
+        engine.assert_fact(status.clone(), text("Normal")).unwrap();
+
+        let premise = LogicalOperator::And(vec![
+            LogicalOperator::AtomicFact(Fact::with_arguments(reading, vec![var("sensor")], var("value"))),
+            LogicalOperator::GreaterThan(
+                Box::new(ComparableValue::Direct(var("value"))),
+                Box::new(ComparableValue::Direct(FactValue::Integer(80))),
+            ),
+        ]);
+        let actions = vec![
+            Action::Assert(Fact::with_arguments(alert.clone(), vec![var("sensor")], FactValue::Boolean(true))),
+            Action::Retract(Fact::with_arguments(pending.clone(), vec![var("sensor")], var("any"))),
+            Action::Modify(Fact::new(status.clone(), text("Alarm"))),
+            Action::Call { callback: "page".into(), arguments: vec![var("sensor"), text("${value - 80} over")] },
+        ];
+        engine.define_rule_with_actions(premise.clone(), actions.clone()).unwrap();
+
+        // Calling an unregistered callback fails before any action is executed
+        let error = engine.forward_chaining_with_variables().unwrap_err();
+        assert_eq!(error, EngineError::UnknownCallback { name: "page".into() }.in_rule(0));
+        assert_eq!(engine.facts.len(), 3);
+
+        let calls = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = calls.clone();
+        engine.register_callback("page", move |arguments| sink.borrow_mut().push(arguments.to_vec()));
+        engine.forward_chaining_with_variables().unwrap();
+
+        assert!(engine.facts.contains(&Fact::with_arguments(alert, vec![text("s1")], FactValue::Boolean(true))));
+        assert!(!engine.facts.iter().any(|fact| fact.symbol == pending));
+        assert!(engine.facts.contains(&Fact::new(status, text("Alarm"))));
+        assert_eq!(engine.facts.len(), 3);
+        assert_eq!(*calls.borrow(), vec![vec![text("s1"), text("10 over")]]);
+
+        // Asserted and called variables must be bound by the premise, retracted ones need not be
+        let unbound = vec![Action::Call { callback: "page".into(), arguments: vec![var("other")] }];
+        assert_eq!(engine.define_rule_with_actions(premise, unbound), Err(EngineError::UnboundVariable { name: "other".into() }));
+        assert_eq!(engine.rules[0].conclusions().count(), 2);
+    }
+
+    #[test]
+    fn test_retraction_withdraws_dependent_conclusions() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let sunny = engine.define_predicate("Sunny", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let warm = engine.define_predicate("Warm", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let hiking = engine.define_predicate("GoodForHiking", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let picnic = engine.define_predicate("Picnic", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let fact = |symbol: &Symbol, city: &str| Fact::with_arguments(symbol.clone(), vec![FactValue::Text(city.into())], FactValue::Boolean(true));
+        let pattern = |symbol: &Symbol| atom(symbol, vec![var("city")]);
+
+        for (symbol, city) in [(&sunny, "Oslo"), (&warm, "Oslo"), (&sunny, "Lyon")] {
+            engine.assert_predicate(symbol.clone(), vec![FactValue::Text(city.into())], FactValue::Boolean(true)).unwrap();
+        }
+        // Hiking is supported by either sunny or warm weather, and picnics by hiking
+        engine.define_rule(pattern(&sunny), Fact::with_arguments(hiking.clone(), vec![var("city")], FactValue::Boolean(true))).unwrap();
+        engine.define_rule(pattern(&warm), Fact::with_arguments(hiking.clone(), vec![var("city")], FactValue::Boolean(true))).unwrap();
+        engine.define_rule(pattern(&hiking), Fact::with_arguments(picnic.clone(), vec![var("city")], FactValue::Boolean(true))).unwrap();
+        engine.forward_chaining_with_variables().unwrap();
+        assert_eq!(engine.facts.len(), 7);
+
+        // Oslo keeps its conclusions while warm weather still supports them
+        assert_eq!(engine.retract_fact(&fact(&sunny, "Oslo")).unwrap(), vec![fact(&sunny, "Oslo")]);
+        assert!(engine.facts.contains(&fact(&picnic, "Oslo")));
+
+        assert_eq!(
+            engine.retract_fact(&fact(&warm, "Oslo")).unwrap(),
+            vec![fact(&warm, "Oslo"), fact(&hiking, "Oslo"), fact(&picnic, "Oslo")]
+        );
+
+        // Directly asserted facts are kept even if a retracted fact also supported them
+        engine.assert_predicate(picnic.clone(), vec![FactValue::Text("Lyon".into())], FactValue::Boolean(true)).unwrap();
+        let removed = engine.retract_fact(&Fact::with_arguments(sunny.clone(), vec![var("any")], FactValue::Boolean(true))).unwrap();
+        assert_eq!(removed, vec![fact(&sunny, "Lyon"), fact(&hiking, "Lyon")]);
+        assert_eq!(engine.facts().collect::<Vec<_>>(), vec![&fact(&picnic, "Lyon")]);
+        assert_eq!(engine.retract_fact(&fact(&sunny, "Lyon")).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_truth_maintenance_labels_follow_assertions() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let bird = engine.define_predicate("Bird", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let penguin = engine.define_predicate("Penguin", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let flies = engine.define_predicate("Flies", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let fact = |symbol: &Symbol, name: &str| Fact::with_arguments(symbol.clone(), vec![FactValue::Text(name.into())], FactValue::Boolean(true));
+
+        engine.assert_predicate(bird.clone(), vec![FactValue::Text("tweety".into())], FactValue::Boolean(true)).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                atom(&bird, vec![var("x")]),
+                LogicalOperator::Not(Box::new(atom(&penguin, vec![var("x")]))),
+            ]),
+            Fact::with_arguments(flies.clone(), vec![var("x")], FactValue::Boolean(true)),
+        ).unwrap();
+        engine.forward_chaining_with_variables().unwrap();
+
+        let justifications = engine.why(&fact(&flies, "tweety"));
+        assert_eq!(justifications.len(), 1);
+        assert_eq!(justifications[0].rule(), Some(0));
+        assert_eq!(justifications[0].antecedents(), &[fact(&bird, "tweety")]);
+        assert_eq!(justifications[0].exclusions(), &[fact(&penguin, "tweety")]);
+
+        // Learning that tweety is a penguin defeats the conclusion, and forgetting it restores it
+        engine.assert_predicate(penguin.clone(), vec![FactValue::Text("tweety".into())], FactValue::Boolean(true)).unwrap();
+        assert_eq!(engine.label(&fact(&flies, "tweety")), Some(Label::Out));
+        assert!(engine.why(&fact(&flies, "tweety")).is_empty());
+
+        engine.retract_fact(&fact(&penguin, "tweety")).unwrap();
+        assert_eq!(engine.label(&fact(&flies, "tweety")), Some(Label::In));
+        assert_eq!(engine.label(&fact(&penguin, "tweety")), None);
+
+        // Asserting an inferred fact adds a premise justification alongside the rule's
+        engine.assert_predicate(flies.clone(), vec![FactValue::Text("tweety".into())], FactValue::Boolean(true)).unwrap();
+        assert_eq!(engine.why(&fact(&flies, "tweety")).len(), 2);
+        assert!(engine.why(&fact(&flies, "tweety"))[1].is_premise());
+    }
+
+    #[test]
+    fn test_proof_trees_from_backward_chaining() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let temperature = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
+        let weather = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let rain = engine.define_symbol("Rain", SymbolType::Boolean).unwrap();
+        let warm = engine.define_symbol("Warm", SymbolType::Boolean).unwrap();
+        let picnic = engine.define_symbol("PicnicDay", SymbolType::Boolean).unwrap();
+
+        engine.assert_fact(temperature.clone(), FactValue::Integer(24)).unwrap();
+        engine.assert_fact(weather.clone(), FactValue::Text("Sunny".into())).unwrap();
+        engine.define_rule(
+            LogicalOperator::GreaterThanOrEqualTo(
+                Box::new(ComparableValue::Symbol(temperature)),
+                Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+            ),
+            Fact::new(warm.clone(), FactValue::Boolean(true)),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(Fact::new(warm.clone(), FactValue::Boolean(true))),
+                LogicalOperator::Or(vec![
+                    LogicalOperator::EqualTo(
+                        Box::new(ComparableValue::Symbol(weather.clone())),
+                        Box::new(ComparableValue::Direct(FactValue::Text("Cloudy".into()))),
+                    ),
+                    LogicalOperator::AtomicFact(Fact::new(weather.clone(), FactValue::Text("Sunny".into()))),
+                ]),
+                LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(rain.clone(), FactValue::Boolean(true))))),
+            ]),
+            Fact::new(picnic.clone(), FactValue::Boolean(true)),
+        ).unwrap();
+
+        let goal = Fact::new(picnic.clone(), FactValue::Boolean(true));
+        let expected = Proof::Rule {
+            goal: goal.clone(),
+            rule: 1,
+            premises: vec![
+                Proof::Rule {
+                    goal: Fact::new(warm.clone(), FactValue::Boolean(true)),
+                    rule: 0,
+                    premises: vec![Proof::Comparison { operator: ">=".into(), left: 24.0, right: 20.0 }],
+                },
+                Proof::Fact(Fact::new(weather.clone(), FactValue::Text("Sunny".into()))),
+                Proof::Negation(LogicalOperator::AtomicFact(Fact::new(rain.clone(), FactValue::Boolean(true)))),
+            ],
+        };
+        assert_eq!(engine.prove_goal(&goal).unwrap(), Some(expected));
+
+        // Equality comparisons record the values they compared
+        engine.define_rule(
+            LogicalOperator::NotEqualTo(
+                Box::new(ComparableValue::Symbol(weather)),
+                Box::new(ComparableValue::Direct(FactValue::Text("Rainy".into()))),
+            ),
+            Fact::new(rain.clone(), FactValue::Boolean(false)),
+        ).unwrap();
+        let proof = engine.prove_goal(&Fact::new(rain.clone(), FactValue::Boolean(false))).unwrap().unwrap();
+        assert!(matches!(&proof, Proof::Rule { premises, .. } if premises == &[Proof::Equality {
+            operator: "!=".into(),
+            left: FactValue::Text("Sunny".into()),
+            right: FactValue::Text("Rainy".into()),
+        }]));
+        assert_eq!(engine.prove_goal(&Fact::new(rain, FactValue::Boolean(true))).unwrap(), None);
+    }
+
+    #[test]
+    fn test_failure_reports_explain_unsatisfied_goals() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let temperature = engine.define_symbol("Temperature", SymbolType::Integer).unwrap();
+        let weather = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let rain = engine.define_symbol("Rain", SymbolType::Boolean).unwrap();
+        let warm = engine.define_symbol("Warm", SymbolType::Boolean).unwrap();
+        let picnic = engine.define_symbol("PicnicDay", SymbolType::Boolean).unwrap();
+        let yes = |symbol: &Symbol| Fact::new(symbol.clone(), FactValue::Boolean(true));
+
+        engine.assert_fact(temperature.clone(), FactValue::Integer(14)).unwrap();
+        engine.assert_fact(weather.clone(), FactValue::Text("Cloudy".into())).unwrap();
+        engine.assert_fact(rain.clone(), FactValue::Boolean(true)).unwrap();
+        engine.define_rule(
+            LogicalOperator::GreaterThanOrEqualTo(
+                Box::new(ComparableValue::Symbol(temperature.clone())),
+                Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+            ),
+            yes(&warm),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(yes(&warm)),
+                LogicalOperator::Or(vec![
+                    LogicalOperator::EqualTo(
+                        Box::new(ComparableValue::Symbol(weather.clone())),
+                        Box::new(ComparableValue::Direct(FactValue::Text("Sunny".into()))),
+                    ),
+                    LogicalOperator::AtomicFact(Fact::new(weather.clone(), FactValue::Text("Clear".into()))),
+                ]),
+                LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(rain.clone(), FactValue::Variable("any".into()))))),
+            ]),
+            yes(&picnic),
+        ).unwrap();
+
+        // The first conjunct fails because the rule deriving it fails on its comparison
+        let failure = engine.explain_goal(&yes(&picnic)).unwrap().unwrap();
+        assert_eq!(failure, Failure::Goal {
+            goal: yes(&picnic),
+            attempts: vec![RuleAttempt::new(1, Failure::Goal {
+                goal: yes(&warm),
+                attempts: vec![RuleAttempt::new(0, Failure::Comparison { operator: ">=".into(), left: 14.0, right: 20.0 })],
+            })],
+        });
+        assert_eq!(failure.attempted_rules(), vec![1, 0]);
+
+        // Each alternative of a disjunction explains its own failure
+        engine.modify_fact(Fact::new(temperature, FactValue::Integer(24))).unwrap();
+        let Some(Failure::Goal { attempts, .. }) = engine.explain_goal(&yes(&picnic)).unwrap() else {
+            panic!("the picnic should not be provable");
+        };
+        assert_eq!(attempts[0].failure(), &Failure::Alternatives(vec![
+            Failure::Equality { operator: "==".into(), left: FactValue::Text("Cloudy".into()), right: FactValue::Text("Sunny".into()) },
+            Failure::MissingFact(Fact::new(weather.clone(), FactValue::Text("Clear".into()))),
+        ]));
+
+        // Negated conditions report the fact that holds
+        engine.modify_fact(Fact::new(weather, FactValue::Text("Sunny".into()))).unwrap();
+        let Some(Failure::Goal { attempts, .. }) = engine.explain_goal(&yes(&picnic)).unwrap() else {
+            panic!("the picnic should not be provable");
+        };
+        assert_eq!(attempts[0].failure(), &Failure::Negation(LogicalOperator::AtomicFact(yes(&rain))));
+        engine.retract_fact(&yes(&rain)).unwrap();
+        assert_eq!(engine.explain_goal(&yes(&picnic)).unwrap(), None);
+
+        // The report shows where the cycle check cut the search
+        let chicken = engine.define_symbol("Chicken", SymbolType::Boolean).unwrap();
+        let egg = engine.define_symbol("Egg", SymbolType::Boolean).unwrap();
+        engine.define_rule(LogicalOperator::AtomicFact(yes(&chicken)), yes(&egg)).unwrap();
+        engine.define_rule(LogicalOperator::AtomicFact(yes(&egg)), yes(&chicken)).unwrap();
+        assert_eq!(engine.explain_goal(&yes(&egg)).unwrap(), Some(Failure::Goal {
+            goal: yes(&egg),
+            attempts: vec![RuleAttempt::new(2, Failure::Goal {
+                goal: yes(&chicken),
+                attempts: vec![RuleAttempt::new(3, Failure::Cycle(yes(&egg)))],
+            })],
+        }));
+        assert_eq!(engine.explain_premise(&LogicalOperator::AtomicFact(yes(&egg))).unwrap(), Some(Failure::MissingFact(yes(&egg))));
+    }
+
+    #[test]
+    fn test_queries_enumerate_all_solutions() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let ancestor = engine.define_predicate("Ancestor", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let age = engine.define_predicate("Age", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let adult = engine.define_predicate("Adult", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let unrelated = engine.define_predicate("Unrelated", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let text = |value: &str| FactValue::Text(value.to_string());
+        let yes = |symbol: &Symbol, arguments: Vec<FactValue>| Fact::with_arguments(symbol.clone(), arguments, FactValue::Boolean(true));
+
+        for (older, younger) in [("alice", "bob"), ("bob", "carol"), ("carol", "dave")] {
+            engine.assert_predicate(parent.clone(), vec![text(older), text(younger)], FactValue::Boolean(true)).unwrap();
+        }
+        for (person, years) in [("alice", 70), ("bob", 45), ("carol", 17)] {
+            engine.assert_predicate(age.clone(), vec![text(person)], FactValue::Integer(years)).unwrap();
+        }
+
+        // Left recursion terminates thanks to the cycle check
+        engine.define_rule(
+            LogicalOperator::AtomicFact(yes(&parent, vec![var("x"), var("y")])),
+            yes(&ancestor, vec![var("x"), var("y")]),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(yes(&ancestor, vec![var("x"), var("z")])),
+                LogicalOperator::AtomicFact(yes(&parent, vec![var("z"), var("y")])),
+            ]),
+            yes(&ancestor, vec![var("x"), var("y")]),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(Fact::with_arguments(age.clone(), vec![var("x")], var("years"))),
+                LogicalOperator::GreaterThanOrEqualTo(
+                    Box::new(ComparableValue::Direct(var("years"))),
+                    Box::new(ComparableValue::Direct(FactValue::Integer(18))),
+                ),
+                LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(yes(&parent, vec![text("alice"), var("x")])))),
+            ]),
+            yes(&adult, vec![var("x")]),
+        ).unwrap();
+
+        let descendants: Vec<_> = engine.query(&yes(&ancestor, vec![text("alice"), var("who")])).unwrap()
+            .map(|solution| solution.unwrap()["who"].clone())
+            .collect();
+        assert_eq!(descendants, vec![text("bob"), text("carol"), text("dave")]);
+
+        // Every solution binds all the pattern's variables
+        let pairs = engine.query(&yes(&ancestor, vec![var("a"), var("b")])).unwrap().count();
+        assert_eq!(pairs, 6);
+
+        // Comparisons and negation filter solutions
+        let adults: Vec<_> = engine.query(&yes(&adult, vec![var("x")])).unwrap().map(Result::unwrap).collect();
+        assert_eq!(adults, vec![HashMap::from([("x".to_string(), text("alice"))])]);
+
+        // Negated recursive goals are searched for completely
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(Fact::with_arguments(age.clone(), vec![var("x")], var("years"))),
+                LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(yes(&ancestor, vec![text("alice"), var("x")])))),
+            ]),
+            yes(&unrelated, vec![var("x")]),
+        ).unwrap();
+        let unrelated_people: Vec<_> = engine.query(&yes(&unrelated, vec![var("x")])).unwrap().map(|solution| solution.unwrap()["x"].clone()).collect();
+        assert_eq!(unrelated_people, vec![text("alice")]);
+
+        // Ground queries yield a single empty solution when they hold
+        assert_eq!(engine.query(&yes(&ancestor, vec![text("bob"), text("dave")])).unwrap().count(), 1);
+        assert_eq!(engine.query(&yes(&ancestor, vec![text("dave"), text("bob")])).unwrap().count(), 0);
+
+        // Solutions are computed lazily
+        let mut solutions = engine.query(&yes(&ancestor, vec![var("a"), var("b")])).unwrap();
+        assert!(solutions.next().is_some());
+    }
+
+    #[test]
+    fn test_rete_network_matches_follow_the_knowledge_base() {
+        let mut engine = SymbolicReasoningEngine::new();
+
+        let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let grandparent = engine.define_predicate("Grandparent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean).unwrap();
+        let age = engine.define_predicate("Age", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let retired = engine.define_predicate("Retired", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let weather = engine.define_symbol("Weather", SymbolType::Text).unwrap();
+        let text = |value: &str| FactValue::Text(value.to_string());
+        let family = |older: &str, younger: &str| Fact::with_arguments(parent.clone(), vec![text(older), text(younger)], FactValue::Boolean(true));
+
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                atom(&parent, vec![var("x"), var("y")]),
+                atom(&parent, vec![var("y"), var("z")]),
+            ]),
+            Fact::with_arguments(grandparent.clone(), vec![var("x"), var("z")], FactValue::Boolean(true)),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(Fact::with_arguments(age.clone(), vec![var("x")], var("years"))),
+                LogicalOperator::GreaterThan(Box::new(ComparableValue::Direct(var("years"))), Box::new(ComparableValue::Direct(FactValue::Integer(65)))),
+                LogicalOperator::Or(vec![
+                    LogicalOperator::Not(Box::new(atom(&parent, vec![var("x"), var("anyone")]))),
+                    LogicalOperator::EqualTo(Box::new(ComparableValue::Symbol(weather.clone())), Box::new(ComparableValue::Direct(text("Sunny")))),
+                ]),
+            ]),
+            Fact::with_arguments(retired.clone(), vec![var("x")], FactValue::Boolean(true)),
+        ).unwrap();
+
+        // The network must find the same matches, in the same order, as evaluating the premises from scratch
+        let check = |engine: &mut SymbolicReasoningEngine| {
+            let mut network = std::mem::take(&mut engine.network);
+            network.update(engine).unwrap();
+            for (index, rule) in engine.rules.iter().enumerate() {
+                let expected = engine.evaluate_logical_expression(&rule.premise, &Solution::new(engine.variable_bindings.clone()), None).unwrap();
+                let found = network.matches(index);
+                let bindings = |solutions: &[Solution]| solutions.iter().map(|solution| solution.bindings.clone()).collect::<Vec<_>>();
+                assert_eq!(bindings(&found), bindings(&expected), "matches of rule {}", index);
+            }
+            engine.network = network;
+        };
+
+        engine.assert_fact(weather.clone(), text("Rainy")).unwrap();
+        for (older, younger) in [("alice", "bob"), ("bob", "carol"), ("carol", "dave"), ("bob", "erin")] {
+            engine.assert_predicate(parent.clone(), vec![text(older), text(younger)], FactValue::Boolean(true)).unwrap();
+        }
+        for (person, years) in [("alice", 90), ("bob", 67), ("frank", 70)] {
+            engine.assert_predicate(age.clone(), vec![text(person)], FactValue::Integer(years)).unwrap();
+        }
+        check(&mut engine);
+
+        engine.forward_chaining_with_variables().unwrap();
+        assert!(engine.contains_fact(&Fact::with_arguments(grandparent.clone(), vec![text("alice"), text("carol")], FactValue::Boolean(true))));
+        assert!(engine.contains_fact(&Fact::with_arguments(retired.clone(), vec![text("frank")], FactValue::Boolean(true))));
+        assert!(!engine.contains_fact(&Fact::with_arguments(retired.clone(), vec![text("bob")], FactValue::Boolean(true))));
+
+        // Facts irrelevant to every rule leave the matches untouched
+        let unrelated = engine.define_symbol("Unrelated", SymbolType::Integer).unwrap();
+        engine.assert_fact(unrelated, FactValue::Integer(1)).unwrap();
+        check(&mut engine);
+        assert!(engine.network.changed().is_empty());
+
+        // Retractions drop partial matches, and re-enable negated conditions
+        engine.retract_fact(&family("bob", "carol")).unwrap();
+        engine.retract_fact(&family("bob", "erin")).unwrap();
+        check(&mut engine);
+        engine.modify_fact(Fact::new(weather.clone(), text("Sunny"))).unwrap();
+        engine.assert_predicate(parent.clone(), vec![text("frank"), text("alice")], FactValue::Boolean(true)).unwrap();
+        check(&mut engine);
+
+        engine.forward_chaining_with_variables().unwrap();
+        assert!(!engine.contains_fact(&Fact::with_arguments(grandparent, vec![text("alice"), text("carol")], FactValue::Boolean(true))));
+        assert!(engine.contains_fact(&Fact::with_arguments(retired, vec![text("bob")], FactValue::Boolean(true))));
+        check(&mut engine);
+
+        // Modifying a fact to the value it already has does not activate the rules matching it again
+        engine.modify_fact(Fact::new(weather.clone(), text("Sunny"))).unwrap();
+        assert!(engine.agenda().unwrap().is_empty());
+        assert_eq!(engine.facts().filter(|fact| fact.symbol == weather).count(), 1);
+    }
+
+    #[test]
+    fn test_working_memory_indexes_facts_by_symbol_and_value() {
+        let mut engine = SymbolicReasoningEngine::new();
+        let reading = engine.define_predicate("Reading", vec![SymbolType::Integer], SymbolType::Float).unwrap();
+        let faulty = engine.define_predicate("Faulty", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+        let fact = |sensor: i32, value: f64| Fact::with_arguments(reading.clone(), vec![FactValue::Integer(sensor)], FactValue::Float(value));
+
+        for sensor in 0..200 {
+            engine.assert_predicate(reading.clone(), vec![FactValue::Integer(sensor)], FactValue::Float(f64::from(sensor % 4))).unwrap();
+        }
+        engine.assert_predicate(reading.clone(), vec![FactValue::Integer(0)], FactValue::Float(-0.0)).unwrap();
+        engine.assert_predicate(reading.clone(), vec![FactValue::Integer(200)], FactValue::Float(f64::NAN)).unwrap();
+        engine.assert_predicate(reading.clone(), vec![FactValue::Integer(200)], FactValue::Float(f64::NAN)).unwrap();
+        assert_eq!(engine.facts.len(), 201);
+        assert!(engine.contains_fact(&fact(200, f64::NAN)));
+
+        // Retracting most facts compacts the store without disturbing the order of the others
+        for sensor in 0..200 {
+            if sensor % 4 != 3 {
+                engine.retract_fact(&fact(sensor, f64::from(sensor % 4))).unwrap();
+            }
+        }
+        let expected: Vec<Fact> = (0..200).filter(|sensor| sensor % 4 == 3).map(|sensor| fact(sensor, 3.0)).chain([fact(200, f64::NAN)]).collect();
+        assert_eq!(engine.facts().cloned().collect::<Vec<_>>(), expected);
+
+        engine.define_rule(
+            LogicalOperator::AtomicFact(Fact::with_arguments(reading.clone(), vec![FactValue::Variable("s".into())], FactValue::Float(f64::NAN))),
+            Fact::with_arguments(faulty.clone(), vec![FactValue::Variable("s".into())], FactValue::Boolean(true)),
+        ).unwrap();
+        engine.forward_chaining_with_variables().unwrap();
+        let found: Vec<_> = engine.facts.with_symbol("Faulty").cloned().collect();
+        assert_eq!(found, vec![Fact::with_arguments(faulty, vec![FactValue::Integer(200)], FactValue::Boolean(true))]);
+    }
+
+    #[test]
+    fn test_agenda_orders_and_refracts_activations() {
+        let mut engine = SymbolicReasoningEngine::new();
+        let order = engine.define_predicate("Order", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let suspicious = engine.define_predicate("Suspicious", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let pattern = |symbol: &Symbol| atom(symbol, vec![var("id")]);
+        let fact = |symbol: &Symbol, id: &str| Fact::with_arguments(symbol.clone(), vec![FactValue::Text(id.into())], FactValue::Boolean(true));
+
+        let shipped = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = shipped.clone();
+        engine.register_callback("ship", move |arguments| sink.borrow_mut().push(arguments[0].clone()));
+        engine.define_rule_with_actions(pattern(&order), vec![Action::Call { callback: "ship".into(), arguments: vec![var("id")] }]).unwrap();
+        engine.define_rule_with_actions(
+            LogicalOperator::And(vec![pattern(&order), pattern(&suspicious)]),
+            vec![Action::Retract(Fact::with_arguments(order.clone(), vec![var("id")], FactValue::Boolean(true)))],
+        ).unwrap();
+        assert_eq!(engine.set_salience(2, 1), Err(EngineError::UnknownRule { rule: 2 }));
+        engine.set_salience(1, 10).unwrap();
+
+        for id in ["a", "b"] {
+            engine.assert_predicate(order.clone(), vec![FactValue::Text(id.into())], FactValue::Boolean(true)).unwrap();
+        }
+        engine.assert_predicate(suspicious.clone(), vec![FactValue::Text("a".into())], FactValue::Boolean(true)).unwrap();
+        let agenda: Vec<(usize, i32)> = engine.agenda().unwrap().iter().map(|activation| (activation.rule(), activation.salience())).collect();
+        assert_eq!(agenda, vec![(1, 10), (0, 0), (0, 0)]);
+
+        // The retraction fires first and cancels the shipment of the suspicious order
+        engine.forward_chaining_with_variables().unwrap();
+        assert_eq!(*shipped.borrow(), vec![FactValue::Text("b".into())]);
+        assert!(!engine.contains_fact(&fact(&order, "a")));
+
+        // Matches fire once, until one of their facts is asserted anew
+        engine.forward_chaining().unwrap();
+        engine.forward_chaining_with_variables().unwrap();
+        assert_eq!(shipped.borrow().len(), 1);
+        engine.retract_fact(&fact(&order, "b")).unwrap();
+        engine.assert_predicate(order.clone(), vec![FactValue::Text("b".into())], FactValue::Boolean(true)).unwrap();
+        assert_eq!(engine.fire_next().unwrap().map(|activation| activation.antecedents().to_vec()), Some(vec![fact(&order, "b")]));
+        assert_eq!(shipped.borrow().len(), 2);
+        assert!(engine.fire_next().unwrap().is_none());
+        assert_eq!(engine.move_activation(0, 0), Err(EngineError::UnknownActivation { index: 0 }));
+    }
+
+    #[test]
+    fn test_datalog_evaluation_reaches_the_minimal_model() {
+        let build = || {
+            let mut engine = SymbolicReasoningEngine::new();
+            let node = engine.define_predicate("Node", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+            let edge = engine.define_predicate("Edge", vec![SymbolType::Integer, SymbolType::Integer], SymbolType::Boolean).unwrap();
+            let path = engine.define_predicate("Path", vec![SymbolType::Integer, SymbolType::Integer], SymbolType::Boolean).unwrap();
+            let isolated = engine.define_predicate("Isolated", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+
+            // Isolated nodes are defined before the paths they negate, so rule order cannot matter
+            engine.define_rule(
+                LogicalOperator::And(vec![
+                    atom(&node, vec![var("x")]),
+                    LogicalOperator::Not(Box::new(atom(&path, vec![var("x"), var("any")]))),
+                ]),
+                Fact::with_arguments(isolated, vec![var("x")], FactValue::Boolean(true)),
+            ).unwrap();
+            engine.define_rule(
+                LogicalOperator::Or(vec![
+                    atom(&edge, vec![var("x"), var("y")]),
+                    LogicalOperator::And(vec![atom(&path, vec![var("x"), var("z")]), atom(&path, vec![var("z"), var("y")])]),
+                ]),
+                Fact::with_arguments(path, vec![var("x"), var("y")], FactValue::Boolean(true)),
+            ).unwrap();
+            for index in 0..12 {
+                engine.assert_predicate(node.clone(), vec![FactValue::Integer(index)], FactValue::Boolean(true)).unwrap();
+                if index < 10 {
+                    engine.assert_predicate(edge.clone(), vec![FactValue::Integer(index), FactValue::Integer(index + 1)], FactValue::Boolean(true)).unwrap();
+                }
+            }
+            engine
+        };
+
+        let mut engine = build();
+        let derived = engine.evaluate_datalog().unwrap();
+        assert_eq!(derived.iter().filter(|fact| fact.symbol.name == "Path").count(), 55);
+        let isolated: Vec<_> = derived.iter().filter(|fact| fact.symbol.name == "Isolated").map(|fact| fact.arguments[0].clone()).collect();
+        assert_eq!(isolated, vec![FactValue::Integer(10), FactValue::Integer(11)]);
+
+        // Every derivation of a fact is recorded, whichever round found it
+        let path = |from: i32, to: i32| Fact::with_arguments(engine.symbols["Path"].clone(), vec![FactValue::Integer(from), FactValue::Integer(to)], FactValue::Boolean(true));
+        assert_eq!(engine.why(&path(0, 4)).len(), 3);
+
+        // Forward chaining finds the same model once strata are ordered by hand
+        let mut chained = build();
+        chained.set_salience(1, 1).unwrap();
+        chained.forward_chaining_with_variables().unwrap();
+        let model = |engine: &SymbolicReasoningEngine| engine.facts().cloned().collect::<HashSet<_>>();
+        assert_eq!(model(&chained), model(&engine));
+
+        // Rules negating their own conclusions or doing more than asserting facts are rejected up front
+        let unreachable = engine.define_symbol("Unreachable", SymbolType::Boolean).unwrap();
+        let rule_count = engine.rules.len();
+        engine.define_rule_with_actions(
+            LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(unreachable.clone(), FactValue::Boolean(true))))),
+            vec![Action::Retract(Fact::new(unreachable.clone(), FactValue::Boolean(true)))],
+        ).unwrap();
+        let error = engine.evaluate_datalog().unwrap_err();
+        assert_eq!(error, EngineError::NonDatalogRule { reason: "it retracts facts".into() }.in_rule(rule_count));
+        engine.define_rule(
+            LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(unreachable.clone(), FactValue::Boolean(true))))),
+            Fact::new(unreachable, FactValue::Boolean(true)),
+        ).unwrap();
+        assert_eq!(engine.evaluate_datalog(), Err(EngineError::Unstratifiable { symbol: "Unreachable".into() }));
+    }
+
+    #[test]
+    fn test_forward_chaining_fires_strata_in_order() {
+        let mut engine = SymbolicReasoningEngine::new();
+        let task = engine.define_predicate("Task", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let blocked = engine.define_predicate("Blocked", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let ready = engine.define_predicate("Ready", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let waits = engine.define_predicate("Waits", vec![SymbolType::Text], SymbolType::Boolean).unwrap();
+        let priority = engine.define_predicate("Priority", vec![SymbolType::Text], SymbolType::Integer).unwrap();
+        let truth = FactValue::Boolean(true);
+
+        // Readiness is defined before the blocking rules it negates, and with a higher salience
+        engine.define_rule(
+            LogicalOperator::And(vec![atom(&task, vec![var("t")]), LogicalOperator::Not(Box::new(atom(&blocked, vec![var("t")])))]),
+            Fact::with_arguments(ready.clone(), vec![var("t")], truth.clone()),
+        ).unwrap();
+        engine.set_salience(0, 10).unwrap();
+        engine.define_rule(atom(&waits, vec![var("t")]), Fact::with_arguments(blocked.clone(), vec![var("t")], truth.clone())).unwrap();
+        assert_eq!(engine.strata().unwrap(), vec![vec![1], vec![0]]);
+
+        for name in ["build", "deploy"] {
+            engine.assert_predicate(task.clone(), vec![FactValue::Text(name.into())], truth.clone()).unwrap();
+        }
+        engine.assert_predicate(waits.clone(), vec![FactValue::Text("deploy".into())], truth.clone()).unwrap();
+        assert_eq!(engine.agenda().unwrap().iter().map(Activation::stratum).collect::<Vec<_>>(), vec![0, 1, 1]);
+
+        engine.forward_chaining().unwrap();
+        let ready_tasks: Vec<_> = engine.facts().filter(|fact| fact.symbol == ready).map(|fact| fact.arguments[0].clone()).collect();
+        assert_eq!(ready_tasks, vec![FactValue::Text("build".into())]);
+
+        // Comparisons may only read variables bound by a fact before them
+        let error = engine.define_rule(
+            LogicalOperator::And(vec![
+                LogicalOperator::GreaterThan(Box::new(ComparableValue::Direct(var("p"))), Box::new(ComparableValue::Direct(FactValue::Integer(1)))),
+                LogicalOperator::AtomicFact(Fact::with_arguments(priority.clone(), vec![var("t")], var("p"))),
+            ]),
+            Fact::with_arguments(ready.clone(), vec![var("t")], truth.clone()),
+        ).unwrap_err();
+        assert_eq!(error, EngineError::UnboundVariable { name: "p".into() });
+
+        // Variables bound by only some alternatives of a disjunction cannot be concluded
+        let error = engine.define_rule(
+            LogicalOperator::Or(vec![atom(&task, vec![var("t")]), LogicalOperator::AtomicFact(Fact::with_arguments(priority.clone(), vec![var("t")], var("p")))]),
+            Fact::with_arguments(priority.clone(), vec![var("t")], var("p")),
+        ).unwrap_err();
+        assert_eq!(error, EngineError::UnboundVariable { name: "p".into() });
+
+        // Blocking tasks that are ready closes a cycle through negation
+        engine.define_rule(atom(&ready, vec![var("t")]), Fact::with_arguments(blocked, vec![var("t")], truth)).unwrap();
+        assert_eq!(engine.forward_chaining(), Err(EngineError::Unstratifiable { symbol: "Blocked".into() }));
+        assert!(engine.strata().is_err());
+    }
+
+    #[test]
+    fn test_forward_chaining_settles_each_stratum() {
+        let known = |engine: &SymbolicReasoningEngine| {
+            let mut names: Vec<_> = engine.facts().map(|fact| fact.symbol.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        // Rain is derived through two rules of its stratum before the negation reads it
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program(r#"
+            symbol Cloudy: Boolean
+            symbol Overcast: Boolean
+            symbol Rain: Boolean
+            symbol Dry: Boolean
+            fact Cloudy
+            rule: not Rain => Dry
+            rule: Overcast => Rain
+            rule: Cloudy => Overcast
+        "#).unwrap();
+        engine.forward_chaining().unwrap();
+        assert_eq!(known(&engine), vec!["Cloudy", "Overcast", "Rain"]);
+
+        // Conclusions fire the rules defined before them in the same stratum
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program(r#"
+            symbol A: Boolean
+            symbol B: Boolean
+            symbol C: Boolean
+            fact A
+            rule: B => C
+            rule: A => B
+        "#).unwrap();
+        engine.forward_chaining().unwrap();
+        assert_eq!(known(&engine), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_stable_models_of_mutual_negation() {
+        let mut engine = SymbolicReasoningEngine::new();
+        let node = engine.define_predicate("Node", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+        let edge = engine.define_predicate("Edge", vec![SymbolType::Integer, SymbolType::Integer], SymbolType::Boolean).unwrap();
+        let chosen = engine.define_predicate("Chosen", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+        let excluded = engine.define_predicate("Excluded", vec![SymbolType::Integer], SymbolType::Boolean).unwrap();
+        let not = |expression: LogicalOperator| LogicalOperator::Not(Box::new(expression));
+
+        // Independent sets of a path: a node is chosen unless it is excluded, and excluded if a neighbour is chosen
+        engine.define_rule(
+            LogicalOperator::And(vec![atom(&node, vec![var("x")]), not(atom(&excluded, vec![var("x")]))]),
+            Fact::with_arguments(chosen.clone(), vec![var("x")], FactValue::Boolean(true)),
+        ).unwrap();
+        engine.define_rule(
+            LogicalOperator::And(vec![atom(&edge, vec![var("x"), var("y")]), atom(&chosen, vec![var("y")])]),
+            Fact::with_arguments(excluded.clone(), vec![var("x")], FactValue::Boolean(true)),
+        ).unwrap();
+        for index in 0..4 {
+            engine.assert_predicate(node.clone(), vec![FactValue::Integer(index)], FactValue::Boolean(true)).unwrap();
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 3)] {
+            for (x, y) in [(from, to), (to, from)] {
+                engine.assert_predicate(edge.clone(), vec![FactValue::Integer(x), FactValue::Integer(y)], FactValue::Boolean(true)).unwrap();
+            }
+        }
+        let facts = engine.facts.len();
+
+        let mut chosen_sets: Vec<Vec<FactValue>> = engine.stable_models().unwrap().iter()
+            .map(|model| model.iter().filter(|fact| fact.symbol == chosen).map(|fact| fact.arguments[0].clone()).collect())
+            .collect();
+        chosen_sets.iter_mut().for_each(|set| set.sort_by_key(|value| format!("{:?}", value)));
+        chosen_sets.sort_by_key(|set| format!("{:?}", set));
+        let expected: Vec<Vec<FactValue>> = vec![vec![0, 2], vec![0, 3], vec![1, 3]].into_iter()
+            .map(|set| set.into_iter().map(FactValue::Integer).collect())
+            .collect();
+        assert_eq!(chosen_sets, expected);
+        assert_eq!(engine.facts.len(), facts);
+
+        // A node chosen unless it is chosen has no stable model
+        engine.define_rule(not(atom(&chosen, vec![FactValue::Integer(9)])), Fact::with_arguments(chosen, vec![FactValue::Integer(9)], FactValue::Boolean(true))).unwrap();
+        assert_eq!(engine.stable_models(), Err(EngineError::NoStableModel));
+    }
+
+    #[test]
+    fn test_load_program_reports_positions() {
+        let mut engine = SymbolicReasoningEngine::new();
+        let program = engine.load_program(r#"
+            symbol Edge(Integer, Integer): Boolean
+            symbol Path(Integer, Integer): Boolean
+            symbol Weight: Float[0..=1.5]
+            fact Edge(1, 2)
+            fact Weight = 1
+            rule closure salience -3: Edge(?x, ?y) or Path(?x, ?z) and Edge(?z, ?y) => Path(?x, ?y)
+        "#).unwrap();
+        assert_eq!(program.symbols().count(), 3);
+        assert_eq!(engine.rules[0].name(), Some("closure"));
+        assert_eq!(engine.rules[0].salience(), -3);
+        assert!(engine.contains_fact(&Fact::new(engine.symbols["Weight"].clone(), FactValue::Float(1.0))));
+
+        engine.forward_chaining_with_variables().unwrap();
+        let path = Fact::with_arguments(engine.symbols["Path"].clone(), vec![FactValue::Integer(1), FactValue::Integer(2)], FactValue::Boolean(true));
+        assert!(engine.contains_fact(&path));
+
+        // Syntax errors point at the offending token and load nothing
+        let error = engine.load_program("symbol Done: Boolean\nrule: Path(?x, ?y) => Done,\n").unwrap_err();
+        assert_eq!(error, EngineError::Syntax { line: 3, column: 1, message: "expected a symbol name, found end of input".into() });
+        assert!(engine.symbol("Done").is_none());
+
+        // Names and statements the engine rejects are located as well
+        assert_eq!(engine.load_program("fact Edge(1)").unwrap_err(), EngineError::ArityMismatch { symbol: "Edge".into(), expected: 2, found: 1 }.at(1, 6));
+        assert_eq!(engine.load_program("rule: Edges(?x, ?y) => Edge(?y, ?x)").unwrap_err(), EngineError::UnknownSymbol { symbol: "Edges".into() }.at(1, 7));
+        let error = engine.load_program("symbol Done: Boolean\n  rule: Edge(?x, ?y) => Path(?x, ?w)").unwrap_err();
+        assert_eq!(error, EngineError::UnboundVariable { name: "w".into() }.at(2, 3));
+        assert!(engine.symbol("Done").is_some());
+        assert_eq!(error.to_string(), "at line 2, column 3: pattern variable '?w' is not bound");
+    }
+
+    #[test]
+    fn test_display_reparses_to_equal_rules() {
+        let declarations = r#"
+            symbol Level: Float
+            symbol Mode: Text
+            symbol Alarm(Text): Boolean
+        "#;
+        let symbols: Program = declarations.parse().unwrap();
+        let symbol = |name: &str| symbols.symbols().find(|symbol| symbol.name() == name).unwrap().clone();
+        let direct = |value: FactValue| Box::new(ComparableValue::Direct(value));
+        let alarm = |zone: FactValue, value: FactValue| Fact::with_arguments(symbol("Alarm"), vec![zone], value);
+
+        let premise = LogicalOperator::And(vec![
+            LogicalOperator::Or(vec![
+                LogicalOperator::GreaterThanOrEqualTo(Box::new(ComparableValue::Symbol(symbol("Level"))), direct(FactValue::Float(f64::NEG_INFINITY))),
+                LogicalOperator::Or(vec![LogicalOperator::AtomicFact(Fact::new(symbol("Mode"), FactValue::Variable("mode".into())))]),
+            ]),
+            LogicalOperator::Not(Box::new(LogicalOperator::And(vec![
+                LogicalOperator::AtomicFact(alarm(FactValue::Text("tab\t\"quoted\"".into()), FactValue::Boolean(false))),
+                LogicalOperator::NotEqualTo(direct(FactValue::Integer(-4)), Box::new(ComparableValue::SymbolName("Later".into()))),
+            ]))),
+            LogicalOperator::And(Vec::new()),
+            LogicalOperator::LessThan(direct(FactValue::Float(1e-9)), direct(FactValue::Boolean(true))),
+        ]);
+        let mut rule = Rule::with_actions(premise, vec![
+            Action::Assert(alarm(FactValue::Text("a".into()), FactValue::Boolean(true))),
+            Action::Retract(alarm(FactValue::Variable("zone".into()), FactValue::Boolean(false))),
+            Action::Modify(Fact::new(symbol("Level"), FactValue::Float(0.5))),
+            Action::Call { callback: "notify".into(), arguments: vec![FactValue::Variable("mode".into()), FactValue::Float(f64::INFINITY)] },
+        ]);
+        rule.name = Some("watch".into());
+        rule.salience = -2;
+
+        let text = rule.to_string();
+        assert!(text.starts_with("rule watch salience -2: (Level >= -inf or any(Mode = ?mode)) and not (Alarm(\"tab\\t\\\"quoted\\\"\") = false"));
+        let program: Program = format!("{}\n{}", declarations, text).parse().unwrap();
+        let reparsed = program.rules().next().unwrap();
+        assert_eq!(reparsed, &rule);
+        assert_eq!(reparsed.to_string(), text);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program(r#"
+            symbol `if`: Float[-inf..=1.5]
+            symbol `Outside temperature`(Integer[-5..=5], Enum("a b", "not")): Text
+            symbol Ready: Boolean
+            symbol Reading: Float
+            variable `for all` = "x\ny" unstable
+            variable limit = 7
+            fact `if` = -inf
+            fact Reading = NaN
+            fact `Outside temperature`(-5, "not") = "cold \"\\\""
+            fact Ready = false
+            rule `the rule` salience 3: `if` > ?limit or not Ready = ?`and` => modify Ready, call `log it`(?limit, -0.25)
+        "#).unwrap();
+
+        let path = std::env::temp_dir().join(format!("sre_round_trip_{}.kb", std::process::id()));
+        engine.save_to(&path).unwrap();
+        let loaded = SymbolicReasoningEngine::load_from(&path).unwrap();
+
+        let mut symbols: Vec<&Symbol> = engine.symbols.values().collect();
+        let mut loaded_symbols: Vec<&Symbol> = loaded.symbols.values().collect();
+        symbols.sort_by_key(|symbol| symbol.name.clone());
+        loaded_symbols.sort_by_key(|symbol| symbol.name.clone());
+        assert_eq!(loaded_symbols, symbols);
+        assert_eq!(loaded.variable("for all"), Some(&Variable::new("for all", FactValue::Text("x\ny".into()), VariableState::Unstable)));
+        assert_eq!(loaded.facts().collect::<Vec<_>>(), engine.facts().collect::<Vec<_>>());
+        let parts = |rule: &Rule| (rule.name.clone(), rule.salience, rule.premise.clone(), rule.actions.clone());
+        assert_eq!(loaded.rules().iter().map(parts).collect::<Vec<_>>(), engine.rules().iter().map(parts).collect::<Vec<_>>());
+        assert_eq!(loaded.program().to_string(), engine.program().to_string());
+
+        // A file from a newer format is only loaded through a migration hook
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, saved.replacen("format 1", "format 2", 1)).unwrap();
+        assert_eq!(SymbolicReasoningEngine::load_from(&path).err(), Some(EngineError::UnsupportedFormat { version: 2 }));
+        let migrated = SymbolicReasoningEngine::load_from_with_migration(&path, |version, source| {
+            assert_eq!(version, 2);
+            Ok(source.replace("fact Ready = false", "fact Ready"))
+        }).unwrap();
+        let ready = Fact::new(migrated.symbol("Ready").unwrap().clone(), FactValue::Boolean(true));
+        assert!(migrated.contains_fact(&ready));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_json_round_trip_and_error_paths() {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program(r#"
+            symbol Level: Float[0.0..=inf]
+            symbol Zone(Text, Integer[1..=9]): Enum("open", "closed")
+            symbol Flag: Boolean
+            variable mode = "auto" unstable
+            fact Level = 2.5
+            fact Zone("east", 3) = "open"
+            rule guard salience -1: Zone(?z, ?n) = "open" and not Flag and (Level >= @Limit or ?n != 4) => modify Flag, retract Zone(?z, ?n) = "open", call log(?z, NaN, ?mode)
+            rule: all() => Flag = false
+        "#).unwrap();
+
+        let mut copy = SymbolicReasoningEngine::new();
+        copy.load_json(&engine.to_json().to_string()).unwrap();
+        assert_eq!(copy.program().to_string(), engine.program().to_string());
+        assert_eq!(copy.to_json(), engine.to_json());
+
+        let rules = |premise: serde_json::Value| serde_json::json!({ "rules": [{ "premise": premise, "actions": [] }] }).to_string();
+        let flag = serde_json::json!({ "AtomicFact": { "symbol": "Flag" } });
+        let error = copy.load_json(&rules(serde_json::json!({ "And": [flag, { "Not": [] }] }))).unwrap_err();
+        assert_eq!(error, EngineError::InvalidJson { path: "rules[0].premise.And[1].Not".into(), message: "expected an object with a single key naming a variant".into() });
+        let error = copy.load_json(&rules(serde_json::json!({ "Or": [{ "LessThan": [{ "Symbol": "Depth" }, { "Direct": 1 }] }] }))).unwrap_err();
+        assert_eq!(error.to_string(), "at rules[0].premise.Or[0].LessThan[0].Symbol: symbol 'Depth' is not defined");
+        let error = copy.load_json(r#"{ "symbols": [{ "name": "Flag", "symbol_type": "Boolean" }] }"#).unwrap_err();
+        assert_eq!(error, EngineError::DuplicateSymbol { symbol: "Flag".into() }.at_path("symbols[0]"));
+        let error = copy.load_json("{ \"facts\": [\n  {,] }").unwrap_err();
+        assert!(matches!(error, EngineError::Syntax { line: 2, column: 4, .. }), "{:?}", error);
+        let error = copy.load_json(r#"{ "facts": [{ "symbol": "Level", "value": { "Float": "many" } }], "extra": 1 }"#).unwrap_err();
+        assert_eq!(error.to_string(), "invalid JSON at extra: unknown field");
+    }
+
+    #[test]
+    fn test_json_logic_conversion_in_both_directions() {
+        let condition = serde_json::json!({ "or": [
+            { "and": [{ ">=": [{ "var": "Level" }, 2.5] }, { "!==": [{ "var": "Mode" }, "off"] }] },
+            { "!": [{ "<": [0, { "var": "Level" }, 1] }] },
+            false,
+        ] });
+        let expression = LogicalOperator::from_json_logic(&condition).unwrap();
+        assert_eq!(expression.to_string(), "@Level >= 2.5 and @Mode != \"off\" or not (0 < @Level and @Level < 1) or any()");
+        let converted = expression.to_json_logic().unwrap();
+        assert_eq!(LogicalOperator::from_json_logic(&converted).unwrap(), expression);
+
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program("symbol Level: Float\nsymbol Mode: Text\nsymbol Alert: Boolean\nfact Level = 3.0\nfact Mode = \"auto\"").unwrap();
+        engine.add_rule(Rule::new(expression, Fact::new(engine.symbol("Alert").unwrap().clone(), FactValue::Boolean(true)))).unwrap();
+        engine.forward_chaining().unwrap();
+        assert!(engine.contains_fact(&Fact::new(engine.symbol("Alert").unwrap().clone(), FactValue::Boolean(true))));
+
+        let pattern = LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(engine.symbol("Alert").unwrap().clone(), FactValue::Boolean(true)))));
+        let error = LogicalOperator::And(vec![LogicalOperator::And(Vec::new()), pattern]).to_json_logic().unwrap_err();
+        assert_eq!(error, EngineError::UnsupportedJsonLogic { operation: "fact pattern Alert".into() }.at_path("And[1].Not"));
+        let error = LogicalOperator::from_json_logic(&serde_json::json!({ "and": [{ "==": [{ "cat": ["a", "b"] }, "ab"] }] })).unwrap_err();
+        assert_eq!(error.to_string(), "at and[0].==[0]: JsonLogic operation 'cat' is not supported");
+    }
+
+    #[test]
+    fn test_parse_fact_resolves_engine_symbols() {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.load_program("symbol Reading(Text): Float").unwrap();
+        let reading = engine.symbol("Reading").unwrap().clone();
+
+        let fact = engine.parse_fact("Reading(\"pump\") = 3").unwrap();
+        assert_eq!(fact, Fact::with_arguments(reading, vec![FactValue::Text("pump".into())], FactValue::Float(3.0)));
+        assert!(engine.facts().next().is_none());
+
+        let error = engine.parse_fact("Reading(\"pump\") = 3 extra").unwrap_err();
+        assert_eq!(error.to_string(), "syntax error at line 1, column 21: expected end of input, found 'extra'");
+        assert!(matches!(engine.parse_fact("Level = 1"), Err(EngineError::Located { line: 1, column: 1, .. })));
+
+        engine.enable_debug();
+        engine.disable_debug();
+        assert!(!engine.debug);
+    }
+
+    #[test]
+    fn test_arithmetic_comparisons_and_division_by_zero() {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.register_function("average", |arguments| Ok(arguments.iter().sum::<f64>() / arguments.len() as f64));
+        engine.load_program("
+            symbol Reading(Text): Integer
+            symbol Count: Integer
+            symbol Spread: Boolean
+            symbol Stable: Boolean
+            fact Reading(\"a\") = 7
+            fact Reading(\"b\") = 2
+            fact Count = 0
+            rule: Reading(\"a\") = ?a and Reading(\"b\") = ?b and abs(?a - ?b) % 3 == 2 => Spread
+            rule: Reading(\"a\") = ?a and average(?a, 2 * ?a) == 10.5 => Stable
+        ").unwrap();
+        engine.forward_chaining().unwrap();
+        assert!(engine.contains_fact(&engine.parse_fact("Spread").unwrap()));
+        assert!(engine.contains_fact(&engine.parse_fact("Stable").unwrap()));
+
+        let rule = engine.rule_from_json(&engine.rules()[0].to_json()).unwrap();
+        assert_eq!(&rule, &engine.rules()[0]);
+
+        let error = engine.load_program("rule: max(?a, 1) > 3 => Stable").unwrap_err();
+        assert_eq!(error.to_string(), "at line 1, column 1: pattern variable '?a' is not bound");
+        let error = engine.load_program("symbol Label: Text\nrule: Count + Label > 3 => Stable").unwrap_err();
+        assert_eq!(error, EngineError::NonNumericOperand { operator: "+".into(), operand: SymbolType::Text }.at(2, 1));
+        assert_eq!(error.to_string(), "at line 2, column 1: arithmetic operator '+' cannot take an operand of type Text");
+        let error = engine.load_program("rule: abs(Label) == 3 => Stable").unwrap_err();
+        assert_eq!(error, EngineError::NonNumericOperand { operator: "abs".into(), operand: SymbolType::Text }.at(1, 1));
+        let premise = LogicalOperator::GreaterThan(
+            Box::new(ComparableValue::Arithmetic(Box::new(Arithmetic::Max(Vec::new())))),
+            Box::new(ComparableValue::Direct(FactValue::Integer(3))),
+        );
+        let error = engine.define_rule(premise, engine.parse_fact("Stable").unwrap()).unwrap_err();
+        assert_eq!(error, EngineError::MissingOperand { operator: "max".into() });
+        engine.load_program("rule: Reading(\"a\") = ?a and ?a / Count > 1 => Stable").unwrap();
+        let error = engine.forward_chaining().unwrap_err();
+        assert_eq!(error, EngineError::DivisionByZero { expression: "?a / Count".into() }.in_rule(2));
     }
 }
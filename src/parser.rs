@@ -0,0 +1,884 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Action, Arithmetic, ComparableValue, EngineError, Fact, FactValue, LogicalOperator, Rule, Symbol, SymbolType, Variable, VariableState};
+
+/// The words with a meaning in the rule language, which cannot be used as symbol or rule names.
+pub(crate) const KEYWORDS: [&str; 21] = [
+    "symbol", "variable", "fact", "rule", "salience", "and", "or", "not", "all", "any", "true", "false", "inf", "NaN", "assert", "retract", "modify", "call",
+    "abs", "min", "max",
+];
+
+/// Punctuation tokens, longest first so that the lexer prefers `>=` to `>`.
+const PUNCTUATION: [&str; 20] = ["..=", "=>", "==", "!=", ">=", "<=", "(", ")", "[", "]", ",", ":", "=", ">", "<", "+", "-", "*", "/", "%"];
+
+/// The constructor of a comparison between two operands.
+pub(crate) type Comparison = fn(Box<ComparableValue>, Box<ComparableValue>) -> LogicalOperator;
+
+/// A line and column in the source text, both counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Position {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl Position {
+    fn error(self, message: String) -> EngineError {
+        EngineError::Syntax { line: self.line, column: self.column, message }
+    }
+
+    pub(crate) fn locate(self, error: EngineError) -> EngineError {
+        error.at(self.line, self.column)
+    }
+}
+
+/// A statement of a program: a symbol definition, a variable, a fact or a rule.
+#[derive(Debug, Clone)]
+pub(crate) enum Statement {
+    Symbol(Symbol),
+    Variable(Variable),
+    Fact(Fact),
+    Rule(Rule),
+}
+
+/// Represents a program of the rule language: symbol definitions, variables, facts and rules, in the order they are
+/// written.
+///
+/// Programs are written one statement after another, with `#` starting a comment that runs to the end of the line:
+///
+/// ```text
+/// # Symbols are declared with their type, and predicates with the types of their arguments
+/// symbol Weather: Text
+/// symbol Temperature: Float
+/// symbol Activity: Enum("Hiking", "Reading")
+/// symbol Friend(Text, Text): Boolean
+///
+/// # Facts omitting their value are true
+/// fact Weather = "Sunny"
+/// fact Friend("ann", "bob")
+///
+/// # Variables are bound to values, and may be marked unstable
+/// variable threshold = 20 unstable
+///
+/// # Rules have an optional name and salience, a premise and comma-separated actions, which may be omitted
+/// rule hiking salience 10: Weather == "Sunny" and Temperature >= 20 => Activity = "Hiking"
+/// rule: Friend(?x, ?y) and not Friend(?y, ?x) => Friend(?y, ?x), call notify(?y)
+/// ```
+///
+/// In a premise, `Symbol(arguments) = value` is a fact pattern, which binds the pattern variables written `?name`,
+/// while `==`, `!=`, `>`, `<`, `>=` and `<=` compare the current values of symbols with other symbols, values or bound
+/// variables. Symbols referenced as `@name` in comparisons are resolved when the rule is evaluated, so they may be
+/// defined later. Comparison operands may be arithmetic expressions, as in `Temperature - Dewpoint < 3`, combining
+/// operands with `+`, `-`, `*`, `/` and `%` in the usual order of precedence, and calling `abs`, `min`, `max` and the
+/// functions registered through `SymbolicReasoningEngine::register_function`. Conditions combine with `not`, `and` and
+/// `or`, in decreasing order of precedence, and parentheses; `all(...)` and `any(...)` are the conjunction and
+/// disjunction of any number of conditions. Actions assert facts unless prefixed with `retract`, `modify` or `call`.
+/// Integer literals are accepted where floats are expected, and `inf`, `-inf` and `NaN` are floats.
+///
+/// Names that are keywords or not identifiers are quoted with backticks, as in ``symbol `Outside temperature`: Float``
+/// or ``?`if` ``. Programs, rules, facts, expressions and values are displayed in this syntax, and the text re-parses
+/// to an equal structure.
+///
+/// A program parsed on its own may only use the symbols it defines; `SymbolicReasoningEngine::load_program` also
+/// resolves the symbols the engine defines.
+///
+/// # Examples
+/// ```
+/// use symbolic_reasoning_engine::Program;
+///
+/// let program: Program = "
+///     symbol Weather: Text
+///     symbol Hiking: Boolean
+///     rule hiking: Weather == \"Sunny\" => Hiking
+/// ".parse()?;
+/// assert_eq!(program.symbols().count(), 2);
+/// assert_eq!(program.rules().next().and_then(|rule| rule.name()), Some("hiking"));
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub(crate) statements: Vec<(Statement, Position)>,
+}
+
+impl Program {
+    /// Builds a program from statements, positioned on consecutive lines as they are displayed.
+    pub(crate) fn from_statements(statements: impl IntoIterator<Item = Statement>) -> Self {
+        let statements = statements.into_iter().enumerate().map(|(index, statement)| (statement, Position { line: index + 1, column: 1 }));
+        Self { statements: statements.collect() }
+    }
+
+    /// Returns the symbols the program defines, in order.
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.statements.iter().filter_map(|(statement, _)| match statement {
+            Statement::Symbol(symbol) => Some(symbol),
+            _ => None,
+        })
+    }
+
+    /// Returns the variables the program binds, in order.
+    pub fn variables(&self) -> impl Iterator<Item = &Variable> {
+        self.statements.iter().filter_map(|(statement, _)| match statement {
+            Statement::Variable(variable) => Some(variable),
+            _ => None,
+        })
+    }
+
+    /// Returns the facts the program asserts, in order.
+    pub fn facts(&self) -> impl Iterator<Item = &Fact> {
+        self.statements.iter().filter_map(|(statement, _)| match statement {
+            Statement::Fact(fact) => Some(fact),
+            _ => None,
+        })
+    }
+
+    /// Returns the rules the program defines, in order.
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.statements.iter().filter_map(|(statement, _)| match statement {
+            Statement::Rule(rule) => Some(rule),
+            _ => None,
+        })
+    }
+}
+
+/// Parses a program that only uses the symbols it defines.
+///
+/// # Errors
+/// Returns `EngineError::Syntax` with the line and column of the first token that does not fit the grammar, and
+/// `EngineError::Located` wrapping `EngineError::UnknownSymbol`, `EngineError::DuplicateSymbol`,
+/// `EngineError::UnknownSymbolType` or `EngineError::ArityMismatch` with the position of the offending name.
+impl FromStr for Program {
+    type Err = EngineError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        parse(source, &HashMap::new())
+    }
+}
+
+/// Parses a program, resolving symbols among the given ones and those the program defines.
+pub(crate) fn parse(source: &str, symbols: &HashMap<String, Symbol>) -> Result<Program, EngineError> {
+    let tokens = Lexer { chars: source.chars().collect(), index: 0, position: Position { line: 1, column: 1 } }.tokenize()?;
+    Parser { tokens, index: 0, symbols: symbols.clone() }.program()
+}
+
+/// Parses a fact or fact pattern, written as in a `fact` statement, resolving its symbol among the given symbols.
+pub(crate) fn parse_fact(source: &str, symbols: &HashMap<String, Symbol>) -> Result<Fact, EngineError> {
+    let tokens = Lexer { chars: source.chars().collect(), index: 0, position: Position { line: 1, column: 1 } }.tokenize()?;
+    let mut parser = Parser { tokens, index: 0, symbols: symbols.clone() };
+    let fact = parser.fact()?;
+    match parser.peek() {
+        Token::End => Ok(fact),
+        _ => Err(parser.unexpected("end of input")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Quoted(String),
+    Variable(String),
+    Reference(String),
+    Integer(i32),
+    Float(f64),
+    Text(String),
+    Punctuation(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "'{}'", name),
+            Token::Quoted(name) => write!(f, "'`{}`'", name),
+            Token::Variable(name) => write!(f, "'?{}'", name),
+            Token::Reference(name) => write!(f, "'@{}'", name),
+            Token::Integer(value) => write!(f, "'{}'", value),
+            Token::Float(value) => write!(f, "'{:?}'", value),
+            Token::Text(text) => write!(f, "{:?}", text),
+            Token::Punctuation(punctuation) => write!(f, "'{}'", punctuation),
+            Token::End => write!(f, "end of input"),
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    index: usize,
+    position: Position,
+}
+
+impl Lexer {
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.index += 1;
+        if c == '\n' {
+            self.position = Position { line: self.position.line + 1, column: 1 };
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(Token, Position)>, EngineError> {
+        let mut tokens = Vec::new();
+        while let Some(c) = self.peek(0) {
+            let position = self.position;
+            let token = match c {
+                c if c.is_whitespace() => {
+                    self.bump();
+                    continue;
+                },
+                '#' => {
+                    while self.peek(0).is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                    continue;
+                },
+                '"' => Token::Text(self.delimited(position)?),
+                '`' => Token::Quoted(self.delimited(position)?),
+                '?' | '@' => {
+                    self.bump();
+                    let name = match self.peek(0) {
+                        Some('`') => self.delimited(self.position)?,
+                        Some(next) if next.is_alphabetic() || next == '_' => self.word(),
+                        _ => return Err(position.error(format!("expected a name after '{}'", c))),
+                    };
+                    match c {
+                        '?' => Token::Variable(name),
+                        _ => Token::Reference(name),
+                    }
+                },
+                '-' if self.chars[self.index..].starts_with(&['-', 'i', 'n', 'f'])
+                    && !self.peek(4).is_some_and(|c| c.is_alphanumeric() || c == '_') => {
+                    (0..4).for_each(|_| {
+                        self.bump();
+                    });
+                    Token::Float(f64::NEG_INFINITY)
+                },
+                c if c.is_alphabetic() || c == '_' => Token::Identifier(self.word()),
+                c if c.is_ascii_digit() || (c == '-' && self.peek(1).is_some_and(|c| c.is_ascii_digit())) => self.number(position)?,
+                _ => {
+                    let punctuation = PUNCTUATION.iter()
+                        .find(|punctuation| punctuation.chars().enumerate().all(|(offset, p)| self.peek(offset) == Some(p)))
+                        .ok_or_else(|| position.error(format!("unexpected character '{}'", c)))?;
+                    punctuation.chars().for_each(|_| {
+                        self.bump();
+                    });
+                    Token::Punctuation(punctuation)
+                },
+            };
+            tokens.push((token, position));
+        }
+        tokens.push((Token::End, self.position));
+        Ok(tokens)
+    }
+
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek(0).filter(|c| c.is_alphanumeric() || *c == '_') {
+            word.push(c);
+            self.bump();
+        }
+        word
+    }
+
+    fn number(&mut self, position: Position) -> Result<Token, EngineError> {
+        let mut literal = String::new();
+        let mut float = false;
+        if self.peek(0) == Some('-') {
+            literal.extend(self.bump());
+        }
+        while let Some(c) = self.peek(0) {
+            let fraction = c == '.' && !float && self.peek(1).is_some_and(|c| c.is_ascii_digit());
+            let exponent = matches!(c, 'e' | 'E') && (self.peek(1).is_some_and(|c| c.is_ascii_digit())
+                || (matches!(self.peek(1), Some('+' | '-')) && self.peek(2).is_some_and(|c| c.is_ascii_digit())));
+            if exponent {
+                literal.extend(self.bump());
+                literal.extend(self.bump());
+                float = true;
+            } else if fraction || c.is_ascii_digit() {
+                literal.extend(self.bump());
+                float |= fraction;
+            } else {
+                break;
+            }
+        }
+
+        match float {
+            true => literal.parse().map(Token::Float).map_err(|_| position.error(format!("invalid number '{}'", literal))),
+            false => literal.parse().map(Token::Integer).map_err(|_| position.error(format!("integer '{}' is out of range", literal))),
+        }
+    }
+
+    /// Reads text delimited by the current character, `"` for text literals and `` ` `` for quoted names.
+    fn delimited(&mut self, position: Position) -> Result<String, EngineError> {
+        let delimiter = self.bump();
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                c if c == delimiter => return Ok(text),
+                Some('\\') => {
+                    let escape = self.position;
+                    text.push(match self.bump() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some(c @ ('"' | '`' | '\\')) => c,
+                        _ => return Err(escape.error("expected one of 'n', 't', 'r', '\"', '`' or '\\' after '\\'".into())),
+                    });
+                },
+                Some(c) => text.push(c),
+                None if delimiter == Some('"') => return Err(position.error("unterminated text literal".into())),
+                None => return Err(position.error("unterminated quoted name".into())),
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+    symbols: HashMap<String, Symbol>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.index].1
+    }
+
+    fn next(&mut self) -> (Token, Position) {
+        let (token, position) = self.tokens[self.index].clone();
+        if token != Token::End {
+            self.index += 1;
+        }
+        (token, position)
+    }
+
+    /// Returns a syntax error at the current token, which was not what the grammar expected.
+    fn unexpected(&self, expected: &str) -> EngineError {
+        self.position().error(format!("expected {}, found {}", expected, self.peek()))
+    }
+
+    fn eat(&mut self, punctuation: &str) -> bool {
+        let found = matches!(self.peek(), Token::Punctuation(p) if *p == punctuation);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Token::Identifier(name) if name == keyword);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn expect(&mut self, punctuation: &str) -> Result<(), EngineError> {
+        match self.eat(punctuation) {
+            true => Ok(()),
+            false => Err(self.unexpected(&format!("'{}'", punctuation))),
+        }
+    }
+
+    /// Checks whether the current token is a name: an identifier other than a keyword, or a quoted name.
+    fn at_name(&self) -> bool {
+        match self.peek() {
+            Token::Identifier(name) => !KEYWORDS.contains(&name.as_str()),
+            Token::Quoted(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks whether the current token ends a statement: the end of the program or the keyword starting the next one.
+    fn at_statement_end(&self) -> bool {
+        match self.peek() {
+            Token::Identifier(name) => ["symbol", "variable", "fact", "rule"].contains(&name.as_str()),
+            token => *token == Token::End,
+        }
+    }
+
+    fn name(&mut self, what: &str) -> Result<(String, Position), EngineError> {
+        match self.peek() {
+            Token::Identifier(name) | Token::Quoted(name) if self.at_name() => {
+                let name = name.clone();
+                Ok((name, self.next().1))
+            },
+            _ => Err(self.unexpected(what)),
+        }
+    }
+
+    /// Parses a comma-separated list up to a closing punctuation, the opening one having been consumed.
+    fn list<T>(&mut self, close: &str, mut item: impl FnMut(&mut Self) -> Result<T, EngineError>) -> Result<Vec<T>, EngineError> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            if !self.eat(",") {
+                return Err(self.unexpected(&format!("',' or '{}'", close)));
+            }
+        }
+    }
+
+    fn program(mut self) -> Result<Program, EngineError> {
+        let mut statements = Vec::new();
+        while *self.peek() != Token::End {
+            let position = self.position();
+            let statement = if self.eat_keyword("symbol") {
+                Statement::Symbol(self.symbol_definition()?)
+            } else if self.eat_keyword("variable") {
+                Statement::Variable(self.variable()?)
+            } else if self.eat_keyword("fact") {
+                Statement::Fact(self.fact()?)
+            } else if self.eat_keyword("rule") {
+                Statement::Rule(self.rule()?)
+            } else {
+                return Err(self.unexpected("'symbol', 'variable', 'fact' or 'rule'"));
+            };
+            statements.push((statement, position));
+        }
+        Ok(Program { statements })
+    }
+
+    fn symbol_definition(&mut self) -> Result<Symbol, EngineError> {
+        let (name, position) = self.name("a symbol name")?;
+        if self.symbols.contains_key(&name) {
+            return Err(position.locate(EngineError::DuplicateSymbol { symbol: name }));
+        }
+        let signature = match self.eat("(") {
+            true => self.list(")", Self::symbol_type)?,
+            false => Vec::new(),
+        };
+        self.expect(":")?;
+        let symbol = Symbol::predicate(&name, signature, self.symbol_type()?);
+        self.symbols.insert(name, symbol.clone());
+        Ok(symbol)
+    }
+
+    fn variable(&mut self) -> Result<Variable, EngineError> {
+        let (name, _) = self.name("a variable name")?;
+        self.expect("=")?;
+        let value = self.term()?;
+        let state = match self.eat_keyword("unstable") {
+            true => VariableState::Unstable,
+            false => {
+                self.eat_keyword("stable");
+                VariableState::Stable
+            },
+        };
+        Ok(Variable::new(&name, value, state))
+    }
+
+    fn symbol_type(&mut self) -> Result<SymbolType, EngineError> {
+        let (name, position) = match self.next() {
+            (Token::Identifier(name), position) => (name, position),
+            (token, position) => return Err(position.error(format!("expected a symbol type, found {}", token))),
+        };
+        match name.as_str() {
+            "Integer" if self.eat("[") => {
+                let min = self.integer()?;
+                self.expect("..=")?;
+                let max = self.integer()?;
+                self.expect("]")?;
+                Ok(SymbolType::IntegerRange { min, max })
+            },
+            "Float" if self.eat("[") => {
+                let min = self.float()?;
+                self.expect("..=")?;
+                let max = self.float()?;
+                self.expect("]")?;
+                Ok(SymbolType::FloatRange { min, max })
+            },
+            "Enum" => {
+                self.expect("(")?;
+                let alternatives = self.list(")", |parser| match parser.next() {
+                    (Token::Text(text) | Token::Identifier(text), _) => Ok(text),
+                    (token, position) => Err(position.error(format!("expected an enumeration value, found {}", token))),
+                })?;
+                Ok(SymbolType::Enum(alternatives))
+            },
+            _ => name.parse().map_err(|error| position.locate(error)),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i32, EngineError> {
+        match self.next() {
+            (Token::Integer(value), _) => Ok(value),
+            (token, position) => Err(position.error(format!("expected an integer, found {}", token))),
+        }
+    }
+
+    fn float(&mut self) -> Result<f64, EngineError> {
+        let error = self.unexpected("a number");
+        match self.term() {
+            Ok(FactValue::Integer(value)) => Ok(value.into()),
+            Ok(FactValue::Float(value)) => Ok(value),
+            _ => Err(error),
+        }
+    }
+
+    /// Parses a value or a pattern variable.
+    fn term(&mut self) -> Result<FactValue, EngineError> {
+        match self.next() {
+            (Token::Integer(value), _) => Ok(FactValue::Integer(value)),
+            (Token::Float(value), _) => Ok(FactValue::Float(value)),
+            (Token::Text(text), _) => Ok(FactValue::Text(text)),
+            (Token::Variable(name), _) => Ok(FactValue::Variable(name)),
+            (Token::Identifier(name), _) if name == "true" => Ok(FactValue::Boolean(true)),
+            (Token::Identifier(name), _) if name == "false" => Ok(FactValue::Boolean(false)),
+            (Token::Identifier(name), _) if name == "inf" => Ok(FactValue::Float(f64::INFINITY)),
+            (Token::Identifier(name), _) if name == "NaN" => Ok(FactValue::Float(f64::NAN)),
+            (token, position) => Err(position.error(format!("expected a value, found {}", token))),
+        }
+    }
+
+    /// Parses the name of a known symbol.
+    fn symbol(&mut self) -> Result<(Symbol, Position), EngineError> {
+        let (name, position) = self.name("a symbol name")?;
+        match self.symbols.get(&name) {
+            Some(symbol) => Ok((symbol.clone(), position)),
+            None => Err(position.locate(EngineError::UnknownSymbol { symbol: name })),
+        }
+    }
+
+    fn fact(&mut self) -> Result<Fact, EngineError> {
+        let (symbol, position) = self.symbol()?;
+        self.fact_of(symbol, position)
+    }
+
+    /// Parses the arguments and value of a fact about a symbol, whose name has been consumed.
+    fn fact_of(&mut self, symbol: Symbol, position: Position) -> Result<Fact, EngineError> {
+        let arguments = match self.eat("(") {
+            true => self.list(")", Self::term)?,
+            false => Vec::new(),
+        };
+        if arguments.len() != symbol.arity() {
+            let error = EngineError::ArityMismatch { symbol: symbol.name.clone(), expected: symbol.arity(), found: arguments.len() };
+            return Err(position.locate(error));
+        }
+        let value = match self.eat("=") {
+            true => coerce(self.term()?, &symbol.symbol_type),
+            false => FactValue::Boolean(true),
+        };
+        let arguments = arguments.into_iter().zip(&symbol.signature).map(|(argument, expected)| coerce(argument, expected)).collect();
+        Ok(Fact::with_arguments(symbol, arguments, value))
+    }
+
+    fn rule(&mut self) -> Result<Rule, EngineError> {
+        let mut rule = Rule::with_actions(LogicalOperator::And(Vec::new()), Vec::new());
+        if self.at_name() {
+            rule.name = Some(self.name("a rule name")?.0);
+        }
+        if self.eat_keyword("salience") {
+            rule.salience = self.integer()?;
+        }
+        if !self.eat(":") {
+            return Err(self.unexpected("a rule name, 'salience' or ':'"));
+        }
+        rule.premise = self.disjunction()?;
+        self.expect("=>")?;
+        // A rule without actions is followed by the next statement or the end of the program
+        if self.at_statement_end() {
+            return Ok(rule);
+        }
+        loop {
+            rule.actions.push(self.action()?);
+            if !self.eat(",") {
+                return Ok(rule);
+            }
+        }
+    }
+
+    fn action(&mut self) -> Result<Action, EngineError> {
+        if self.eat_keyword("retract") {
+            Ok(Action::Retract(self.fact()?))
+        } else if self.eat_keyword("modify") {
+            Ok(Action::Modify(self.fact()?))
+        } else if self.eat_keyword("call") {
+            let (callback, _) = self.name("a callback name")?;
+            self.expect("(")?;
+            Ok(Action::Call { callback, arguments: self.list(")", Self::term)? })
+        } else {
+            self.eat_keyword("assert");
+            Ok(Action::Assert(self.fact()?))
+        }
+    }
+
+    fn disjunction(&mut self) -> Result<LogicalOperator, EngineError> {
+        let mut alternatives = vec![self.conjunction()?];
+        while self.eat_keyword("or") {
+            alternatives.push(self.conjunction()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => LogicalOperator::Or(alternatives),
+        })
+    }
+
+    fn conjunction(&mut self) -> Result<LogicalOperator, EngineError> {
+        let mut conditions = vec![self.negation()?];
+        while self.eat_keyword("and") {
+            conditions.push(self.negation()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => LogicalOperator::And(conditions),
+        })
+    }
+
+    fn negation(&mut self) -> Result<LogicalOperator, EngineError> {
+        match self.eat_keyword("not") {
+            true => Ok(LogicalOperator::Not(Box::new(self.negation()?))),
+            false => self.condition(),
+        }
+    }
+
+    /// Parses a parenthesized expression, a conjunction or disjunction written as a call, a fact pattern or a
+    /// comparison.
+    fn condition(&mut self) -> Result<LogicalOperator, EngineError> {
+        if *self.peek() == Token::Punctuation("(") {
+            // Parentheses enclose either an expression or the arithmetic operand of a comparison
+            let start = self.index;
+            self.next();
+            let grouped = self.disjunction().and_then(|expression| self.expect(")").map(|_| expression));
+            if grouped.is_ok() && self.comparison().is_none() && !self.at_arithmetic() {
+                return grouped;
+            }
+            self.index = start;
+            return match self.operand().and_then(|left| self.comparison_with(left)) {
+                Ok(comparison) => Ok(comparison),
+                Err(error) => grouped.and(Err(error)),
+            };
+        }
+        for (keyword, operator) in [("all", LogicalOperator::And as fn(_) -> _), ("any", LogicalOperator::Or)] {
+            if self.eat_keyword(keyword) {
+                self.expect("(")?;
+                return Ok(operator(self.list(")", Self::disjunction)?));
+            }
+        }
+
+        if self.at_call() {
+            let start = self.index;
+            match self.operand() {
+                Ok(left) if self.comparison().is_some() => return self.comparison_with(left),
+                // Without a comparison, the name is that of a fact pattern about an undefined symbol
+                _ => self.index = start,
+            }
+        }
+        if self.at_name() {
+            let (symbol, position) = self.symbol()?;
+            if self.comparison().is_none() && !self.at_arithmetic() {
+                return Ok(LogicalOperator::AtomicFact(self.fact_of(symbol, position)?));
+            }
+            let left = self.arithmetic_from(ComparableValue::Symbol(symbol))?;
+            return self.comparison_with(left);
+        }
+        let left = self.operand()?;
+        self.comparison_with(left)
+    }
+
+    /// Parses the operator and right operand of a comparison.
+    fn comparison_with(&mut self, left: ComparableValue) -> Result<LogicalOperator, EngineError> {
+        let comparison = self.comparison().ok_or_else(|| self.unexpected("a comparison operator"))?;
+        self.next();
+        Ok(comparison(Box::new(left), Box::new(self.operand()?)))
+    }
+
+    /// Parses a comparison operand: an arithmetic expression, or a single factor of one.
+    fn operand(&mut self) -> Result<ComparableValue, EngineError> {
+        let first = self.factor()?;
+        self.arithmetic_from(first)
+    }
+
+    /// Parses the rest of an arithmetic expression whose first factor has been parsed, with `*`, `/` and `%` binding
+    /// tighter than `+` and `-`, and operators of the same precedence associating to the left.
+    fn arithmetic_from(&mut self, first: ComparableValue) -> Result<ComparableValue, EngineError> {
+        let mut left = self.product_from(first)?;
+        loop {
+            let operation = match self.peek() {
+                Token::Punctuation("+") => Arithmetic::Add,
+                Token::Punctuation("-") => Arithmetic::Subtract,
+                _ => match self.negative_literal() {
+                    // `Level -1` lexes as a negative literal, which subtracts its magnitude
+                    Some(magnitude) => {
+                        self.next();
+                        let right = self.product_from(ComparableValue::Direct(magnitude))?;
+                        left = ComparableValue::Arithmetic(Box::new(Arithmetic::Subtract(Box::new(left), Box::new(right))));
+                        continue;
+                    },
+                    None => return Ok(left),
+                },
+            };
+            self.next();
+            let first = self.factor()?;
+            let right = self.product_from(first)?;
+            left = ComparableValue::Arithmetic(Box::new(operation(Box::new(left), Box::new(right))));
+        }
+    }
+
+    /// Parses the rest of a product whose first factor has been parsed.
+    fn product_from(&mut self, first: ComparableValue) -> Result<ComparableValue, EngineError> {
+        let mut left = first;
+        loop {
+            let operation = match self.peek() {
+                Token::Punctuation("*") => Arithmetic::Multiply,
+                Token::Punctuation("/") => Arithmetic::Divide,
+                Token::Punctuation("%") => Arithmetic::Modulo,
+                _ => return Ok(left),
+            };
+            self.next();
+            left = ComparableValue::Arithmetic(Box::new(operation(Box::new(left), Box::new(self.factor()?))));
+        }
+    }
+
+    /// Parses a factor of an arithmetic expression: a parenthesized arithmetic expression, a call of `abs`, `min`,
+    /// `max` or a registered function, a symbol, a symbol referenced by name, or a value.
+    fn factor(&mut self) -> Result<ComparableValue, EngineError> {
+        let arithmetic = |expression| ComparableValue::Arithmetic(Box::new(expression));
+        if self.eat("(") {
+            let expression = self.operand()?;
+            self.expect(")")?;
+            return Ok(expression);
+        }
+        if self.eat_keyword("abs") {
+            self.expect("(")?;
+            let operand = self.operand()?;
+            self.expect(")")?;
+            return Ok(arithmetic(Arithmetic::Abs(Box::new(operand))));
+        }
+        for (keyword, operation) in [("min", Arithmetic::Min as fn(_) -> _), ("max", Arithmetic::Max)] {
+            if self.eat_keyword(keyword) {
+                self.expect("(")?;
+                if matches!(self.peek(), Token::Punctuation(p) if *p == ")") {
+                    return Err(self.unexpected("an operand"));
+                }
+                return Ok(arithmetic(operation(self.list(")", Self::operand)?)));
+            }
+        }
+        if self.at_call() {
+            let (function, _) = self.name("a function name")?;
+            self.next();
+            return Ok(arithmetic(Arithmetic::Call { function, arguments: self.list(")", Self::operand)? }));
+        }
+
+        match self.peek() {
+            _ if self.at_name() => Ok(ComparableValue::Symbol(self.symbol()?.0)),
+            Token::Reference(name) => {
+                let name = name.clone();
+                self.next();
+                Ok(ComparableValue::SymbolName(name))
+            },
+            _ => Ok(ComparableValue::Direct(self.term()?)),
+        }
+    }
+
+    /// Checks whether the current token starts the call of a function: a name that is not a known symbol, followed by
+    /// `(`.
+    fn at_call(&self) -> bool {
+        match self.peek() {
+            Token::Identifier(name) | Token::Quoted(name) if self.at_name() => {
+                !self.symbols.contains_key(name) && matches!(self.tokens.get(self.index + 1), Some((Token::Punctuation("("), _)))
+            },
+            _ => false,
+        }
+    }
+
+    /// Checks whether the current token continues an arithmetic expression.
+    fn at_arithmetic(&self) -> bool {
+        matches!(self.peek(), Token::Punctuation("+" | "-" | "*" | "/" | "%")) || self.negative_literal().is_some()
+    }
+
+    /// Returns the magnitude of the current token if it is a negative number, which lexes as a single literal.
+    fn negative_literal(&self) -> Option<FactValue> {
+        match *self.peek() {
+            Token::Integer(value) if value < 0 => Some(value.checked_neg().map_or(FactValue::Float(-f64::from(value)), FactValue::Integer)),
+            Token::Float(value) if value.is_sign_negative() && !value.is_nan() => Some(FactValue::Float(-value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the constructor of the comparison whose operator is the current token, if any.
+    fn comparison(&self) -> Option<Comparison> {
+        match self.peek() {
+            Token::Punctuation("==") => Some(LogicalOperator::EqualTo),
+            Token::Punctuation("!=") => Some(LogicalOperator::NotEqualTo),
+            Token::Punctuation(">") => Some(LogicalOperator::GreaterThan),
+            Token::Punctuation("<") => Some(LogicalOperator::LessThan),
+            Token::Punctuation(">=") => Some(LogicalOperator::GreaterThanOrEqualTo),
+            Token::Punctuation("<=") => Some(LogicalOperator::LessThanOrEqualTo),
+            _ => None,
+        }
+    }
+}
+
+/// Converts integer literals written where floats are expected.
+pub(crate) fn coerce(value: FactValue, expected: &SymbolType) -> FactValue {
+    match (value, expected) {
+        (FactValue::Integer(value), SymbolType::Float | SymbolType::FloatRange { .. }) => FactValue::Float(value.into()),
+        (value, _) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::atom;
+
+    #[test]
+    fn tokens_carry_their_position() {
+        let lexer = Lexer { chars: "rule r:\n  Level >= -2.5e1 # comment\n  => ?x".chars().collect(), index: 0, position: Position { line: 1, column: 1 } };
+        let tokens: Vec<(Token, usize, usize)> = lexer.tokenize().unwrap().into_iter().map(|(token, position)| (token, position.line, position.column)).collect();
+        assert_eq!(tokens, vec![
+            (Token::Identifier("rule".into()), 1, 1),
+            (Token::Identifier("r".into()), 1, 6),
+            (Token::Punctuation(":"), 1, 7),
+            (Token::Identifier("Level".into()), 2, 3),
+            (Token::Punctuation(">="), 2, 9),
+            (Token::Float(-25.0), 2, 12),
+            (Token::Punctuation("=>"), 3, 3),
+            (Token::Variable("x".into()), 3, 6),
+            (Token::End, 3, 8),
+        ]);
+    }
+
+    #[test]
+    fn precedence_binds_not_then_and_then_or() {
+        let program: Program = "symbol A: Boolean symbol B: Boolean symbol C: Boolean rule: not A and B or C => A".parse().unwrap();
+        let expected = LogicalOperator::Or(vec![
+            LogicalOperator::And(vec![LogicalOperator::Not(Box::new(atom("A"))), atom("B")]),
+            atom("C"),
+        ]);
+        assert_eq!(program.rules().next().unwrap().premise(), &expected);
+    }
+
+    #[test]
+    fn arithmetic_binds_tighter_than_comparisons_and_reparses() {
+        let source = "symbol A: Float symbol B: Float symbol C: Boolean rule: (A - B) * 2 > A-1 and (C or A % 3 == 0) => C";
+        let program: Program = source.parse().unwrap();
+        let premise = program.rules().next().unwrap().premise();
+        assert_eq!(premise.to_string(), "(A - B) * 2 > A - 1 and (C or A % 3 == 0)");
+
+        let operand = |source: &str| match format!("symbol A: Float symbol B: Float symbol C: Boolean rule: {} => C", source).parse::<Program>() {
+            Ok(program) => program.rules().next().unwrap().premise().to_string(),
+            Err(error) => error.to_string(),
+        };
+        assert_eq!(operand("A - (B - 1) < A / (B * 2)"), "A - (B - 1) < A / (B * 2)");
+        assert_eq!(operand("abs(A - -2.5) >= max(A, min(B), hypot(B, 1))"), "abs(A - -2.5) >= max(A, min(B), hypot(B, 1))");
+        assert_eq!(operand("max(A, min()) > 1"), "syntax error at line 1, column 68: expected an operand, found ')'");
+        assert_eq!(operand("Friend(?x)"), "at line 1, column 57: symbol 'Friend' is not defined");
+    }
+}
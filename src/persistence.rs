@@ -0,0 +1,75 @@
+//! Reading and writing knowledge base files in the versioned format described by `FORMAT_VERSION`.
+
+use std::fs;
+use std::path::Path;
+
+use crate::EngineError;
+
+/// The version of the knowledge base format written by `SymbolicReasoningEngine::save_to`.
+///
+/// A knowledge base file is a UTF-8 program of the rule language, as described by `Program`, whose first line is a
+/// header comment naming the version of the format:
+///
+/// ```text
+/// # symbolic_reasoning_engine knowledge base, format 1
+/// symbol Temperature: Float
+/// symbol Weather: Enum("Sunny", "Rainy")
+/// variable threshold = 20
+/// fact Temperature = 24.5
+/// fact Weather = "Sunny"
+/// rule hiking: Weather == "Sunny" and Temperature >= ?threshold => ...
+/// ```
+///
+/// Symbols come first, sorted by name, followed by variables sorted by name, then facts and rules in the order the
+/// engine holds them. Format 1 is the only version so far. Files of other versions are handed to the migration hook
+/// of `SymbolicReasoningEngine::load_from_with_migration`, which rewrites them into the current format.
+pub const FORMAT_VERSION: u32 = 1;
+
+const HEADER: &str = "# symbolic_reasoning_engine knowledge base, format ";
+
+/// Writes a knowledge base file, with the header of the current format version.
+pub(crate) fn write(path: &Path, program: &str) -> Result<(), EngineError> {
+    fs::write(path, format!("{}{}\n{}", HEADER, FORMAT_VERSION, program)).map_err(|error| io_error(path, error))
+}
+
+/// Reads a knowledge base file, passing files of another format version through a migration hook.
+///
+/// # Errors
+/// Returns `EngineError::Io` if the file cannot be read, `EngineError::Syntax` if it does not start with a valid
+/// header, and the errors of the migration hook.
+pub(crate) fn read(path: &Path, migrate: impl FnOnce(u32, String) -> Result<String, EngineError>) -> Result<String, EngineError> {
+    let source = fs::read_to_string(path).map_err(|error| io_error(path, error))?;
+    let header = source.lines().next().unwrap_or_default();
+    let version = header.strip_prefix(HEADER).and_then(|version| version.trim().parse().ok()).ok_or_else(|| EngineError::Syntax {
+        line: 1,
+        column: 1,
+        message: format!("expected the header '{}{}'", HEADER, FORMAT_VERSION),
+    })?;
+
+    match version {
+        FORMAT_VERSION => Ok(source),
+        version => migrate(version, source),
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> EngineError {
+    EngineError::Io { path: path.display().to_string(), message: error.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_of_other_versions_are_migrated() {
+        let path = std::env::temp_dir().join(format!("sre_persistence_{}.kb", std::process::id()));
+        fs::write(&path, format!("{}0\nfact Old\n", HEADER)).unwrap();
+        let migrated = read(&path, |version, source| Ok(format!("# migrated from {}\n{}", version, source.replace("Old", "New"))));
+        assert_eq!(migrated.unwrap(), format!("# migrated from 0\n{}0\nfact New\n", HEADER));
+
+        fs::write(&path, "fact Headless\n").unwrap();
+        assert!(matches!(read(&path, |_, source| Ok(source)), Err(EngineError::Syntax { line: 1, column: 1, .. })));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(read(&path, |_, source| Ok(source)), Err(EngineError::Io { .. })));
+    }
+}
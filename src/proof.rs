@@ -0,0 +1,62 @@
+use crate::{Fact, FactValue, LogicalOperator};
+
+/// Represents a proof tree explaining how backward chaining established a goal.
+///
+/// The root of the tree is the goal itself, either found among the known facts or derived by a rule. Each rule node
+/// holds one sub-proof per condition of the rule's premise that had to hold, in the order they were checked:
+/// conjunctions are flattened into their conditions, and disjunctions contribute the proof of the alternative that
+/// held. The leaves are known facts, comparisons with the values they were evaluated on, and negated conditions.
+///
+/// Variants:
+/// - `Fact`: The goal or condition is a known fact of the knowledge base.
+/// - `Rule`: The `goal` was derived by the rule at index `rule`, whose premise holds as shown by `premises`.
+/// - `Comparison`: An ordering comparison, such as `>` or `<=`, held between the numeric values `left` and `right`, as
+///   resolved from the symbols, variables or values the comparison refers to.
+/// - `Equality`: An `==` or `!=` comparison held between the resolved values `left` and `right`.
+/// - `Negation`: The negated condition could not be established. Atomic facts are shown with their pattern variables
+///   replaced by the values bound at that point.
+///
+/// # Examples
+/// ```
+/// use symbolic_reasoning_engine::{ComparableValue, Fact, FactValue, LogicalOperator, Proof, SymbolType, SymbolicReasoningEngine};
+///
+/// let mut engine = SymbolicReasoningEngine::new();
+/// let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+/// let picnic = engine.define_symbol("PicnicDay", SymbolType::Boolean)?;
+///
+/// engine.assert_fact(temperature.clone(), FactValue::Integer(24))?;
+/// engine.define_rule(
+///     LogicalOperator::GreaterThan(
+///         Box::new(ComparableValue::Symbol(temperature)),
+///         Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+///     ),
+///     Fact::new(picnic.clone(), FactValue::Boolean(true)),
+/// )?;
+///
+/// let goal = Fact::new(picnic, FactValue::Boolean(true));
+/// assert_eq!(engine.prove_goal(&goal)?, Some(Proof::Rule {
+///     goal: goal.clone(),
+///     rule: 0,
+///     premises: vec![Proof::Comparison { operator: ">".into(), left: 24.0, right: 20.0 }],
+/// }));
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Proof {
+    Fact(Fact),
+    Rule { goal: Fact, rule: usize, premises: Vec<Proof> },
+    Comparison { operator: String, left: f64, right: f64 },
+    Equality { operator: String, left: FactValue, right: FactValue },
+    Negation(LogicalOperator),
+}
+
+impl Proof {
+    /// Returns the known facts the proof ultimately relies on, in the order they appear in the tree.
+    pub fn facts(&self) -> Vec<&Fact> {
+        match self {
+            Proof::Fact(fact) => vec![fact],
+            Proof::Rule { premises, .. } => premises.iter().flat_map(Proof::facts).collect(),
+            Proof::Comparison { .. } | Proof::Equality { .. } | Proof::Negation(_) => Vec::new(),
+        }
+    }
+}
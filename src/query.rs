@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+
+use crate::{ComparableValue, EngineError, Fact, FactValue, LogicalOperator, Solution, SymbolicReasoningEngine, Variable, VariableState};
+
+/// A condition left to satisfy on a branch of the search, along with the goals it was derived for.
+#[derive(Debug, Clone)]
+struct Goal {
+    condition: LogicalOperator,
+    /// The index of the rule whose premise the condition comes from, if any, to report evaluation errors.
+    rule: Option<usize>,
+    /// The goals resolved through rules on the way to the condition, used to cut cycles.
+    ancestors: Vec<Fact>,
+}
+
+/// A step left on a branch of the search.
+///
+/// Variants:
+/// - `Prove`: A condition to satisfy.
+/// - `Record`: Marks the end of a rule's premise, recording the instance of the goal the rule derived as an answer.
+#[derive(Debug, Clone)]
+enum Step {
+    Prove(Goal),
+    Record(Fact),
+}
+
+/// A partial solution of a query: the steps left to take, the next one last, and the bindings so far.
+#[derive(Debug, Clone)]
+struct Branch {
+    steps: Vec<Step>,
+    bindings: HashMap<String, Variable>,
+}
+
+/// A lazy iterator over every solution of a query, as returned by `SymbolicReasoningEngine::query`.
+///
+/// Solutions are enumerated depth-first, in the order Prolog would find them: known facts before rules, rules in the
+/// order they were defined, and the conditions of a premise from left to right. Each call to `next` resumes the search
+/// where the previous solution was found, so only as much of the search space is explored as solutions are consumed.
+///
+/// Each solution maps the names of the pattern variables of the query to their values, and is only yielded once even
+/// if it can be derived in several ways. Negated conditions hold when no solution of the condition can be found.
+///
+/// Recursive rules are handled through tabling: as with `specify_goal`, the rules are not tried again for a goal that
+/// is already being searched for, up to the renaming of its pattern variables. Such a goal is instead satisfied by
+/// the answers derived for it so far, and the search is repeated while doing so yields new answers, so that left
+/// recursive rules both terminate and find every solution. Negated conditions are searched for with the answers
+/// tabled so far, and the answers they derive are tabled for the rest of the search, which is repeated as well if a
+/// negated condition was satisfied by answers of a goal that gained new ones.
+///
+/// The iterator yields an `EngineError` if a comparison along the way cannot be evaluated, wrapped in
+/// `EngineError::RuleEvaluation` when the comparison belongs to a rule's premise, and stops after it.
+pub struct Query<'a> {
+    engine: &'a SymbolicReasoningEngine,
+    variables: Vec<String>,
+    start: Branch,
+    branches: Vec<Branch>,
+    found: Vec<HashMap<String, FactValue>>,
+    /// The answers derived for each goal resolved through the rules.
+    tables: Vec<(Fact, Vec<Fact>)>,
+    /// Whether the current pass of the search used a table, and whether it added answers to any.
+    consumed: bool,
+    grown: bool,
+    renamed: usize,
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(engine: &'a SymbolicReasoningEngine, pattern: &Fact) -> Self {
+        let variables = pattern.variables().fold(Vec::new(), |mut variables, name| {
+            if !variables.iter().any(|variable| variable == name) {
+                variables.push(name.to_string());
+            }
+            variables
+        });
+        let goal = Goal { condition: LogicalOperator::AtomicFact(pattern.clone()), rule: None, ancestors: Vec::new() };
+        Self::with_branch(engine, variables, Branch { steps: vec![Step::Prove(goal)], bindings: engine.variable_bindings.clone() })
+    }
+
+    fn with_branch(engine: &'a SymbolicReasoningEngine, variables: Vec<String>, start: Branch) -> Self {
+        Self {
+            engine,
+            variables,
+            branches: vec![start.clone()],
+            start,
+            found: Vec::new(),
+            tables: Vec::new(),
+            consumed: false,
+            grown: false,
+            renamed: 0,
+        }
+    }
+
+    /// Expands the next condition of a branch into the branches satisfying it, in the order they are to be explored.
+    fn expand(&mut self, goal: Goal, branch: Branch) -> Result<Vec<Branch>, EngineError> {
+        let engine = self.engine;
+        let bindings = &branch.bindings;
+        let holds = match &goal.condition {
+            LogicalOperator::And(expressions) => {
+                let mut branch = branch.clone();
+                branch.steps.extend(expressions.iter().rev().map(|expression| Step::Prove(Goal { condition: expression.clone(), ..goal.clone() })));
+                return Ok(vec![branch]);
+            },
+            LogicalOperator::Or(expressions) => {
+                return Ok(expressions.iter()
+                    .map(|expression| {
+                        let mut branch = branch.clone();
+                        branch.steps.push(Step::Prove(Goal { condition: expression.clone(), ..goal.clone() }));
+                        branch
+                    })
+                    .collect());
+            },
+            LogicalOperator::Not(expression) => {
+                // Negation as failure: look for a single solution of the negated condition
+                let negated = Goal { condition: expression.as_ref().clone(), ..goal.clone() };
+                let mut query = Query::with_branch(engine, Vec::new(), Branch { steps: vec![Step::Prove(negated)], bindings: bindings.clone() });
+                query.tables = self.tables.clone();
+                query.renamed = self.renamed;
+                let solution = query.next();
+
+                // Keep the answers the nested search derived, and repeat this search if it relied on tables that grew
+                let answers = |tables: &[(Fact, Vec<Fact>)]| tables.iter().map(|(_, answers)| answers.len()).sum::<usize>();
+                self.grown |= answers(&query.tables) > answers(&self.tables);
+                self.consumed |= query.consumed;
+                self.tables = query.tables;
+                self.renamed = query.renamed;
+                match solution {
+                    Some(Err(error)) => return Err(error),
+                    Some(Ok(_)) => false,
+                    None => true,
+                }
+            },
+            LogicalOperator::AtomicFact(fact) => return Ok(self.resolve_fact(fact, &goal, &branch)),
+            comparison => {
+                let comparison = map_terms(comparison, &|term| resolve(term, bindings));
+                !engine.evaluate_logical_expression(&comparison, &Solution::new(bindings.clone()), None)
+                    .map_err(|error| in_rule(error, &goal))?
+                    .is_empty()
+            },
+        };
+
+        Ok(if holds { vec![branch] } else { Vec::new() })
+    }
+
+    /// Satisfies a fact pattern through the known facts, then through the rules concluding it.
+    fn resolve_fact(&mut self, fact: &Fact, goal: &Goal, branch: &Branch) -> Vec<Branch> {
+        let engine = self.engine;
+        let pattern = map_fact_terms(&engine.interpolate_fact(fact, false), &|term| resolve(term, &branch.bindings));
+        let unify_with = |known_fact: &Fact| {
+            let bindings = unify(engine, &pattern, known_fact, &branch.bindings)?;
+            Some(Branch { steps: branch.steps.clone(), bindings })
+        };
+
+        let mut branches: Vec<Branch> = engine.facts.candidates(&pattern).filter_map(unify_with).collect();
+
+        // A goal already being searched for is satisfied by the answers derived for it so far
+        if goal.ancestors.iter().any(|ancestor| is_variant(ancestor, &pattern)) {
+            self.consumed = true;
+            if let Some((_, answers)) = self.tables.iter().find(|(tabled, _)| is_variant(tabled, &pattern)) {
+                branches.extend(answers.iter().filter_map(unify_with));
+            }
+            return branches;
+        }
+
+        let mut ancestors = goal.ancestors.clone();
+        ancestors.push(pattern.clone());
+        for (index, rule) in engine.rules.iter().enumerate() {
+            for conclusion in rule.conclusions() {
+                // Rename the rule's pattern variables apart from the ones already in use
+                self.renamed += 1;
+                let suffix = format!("#{}", self.renamed);
+                let conclusion = self.rename_fact(conclusion, &suffix);
+                let Some(bindings) = unify(engine, &conclusion, &pattern, &branch.bindings) else {
+                    continue;
+                };
+
+                let mut steps = branch.steps.clone();
+                steps.push(Step::Record(pattern.clone()));
+                let premise = map_terms(&rule.premise, &|term| self.rename_term(term, &suffix));
+                steps.push(Step::Prove(Goal { condition: premise, rule: Some(index), ancestors: ancestors.clone() }));
+                branches.push(Branch { steps, bindings });
+            }
+        }
+
+        branches
+    }
+
+    /// Records an answer derived for a goal, noting whether it is new.
+    fn record(&mut self, goal: Fact, answer: Fact) {
+        let index = match self.tables.iter().position(|(tabled, _)| is_variant(tabled, &goal)) {
+            Some(index) => index,
+            None => {
+                self.tables.push((goal, Vec::new()));
+                self.tables.len() - 1
+            },
+        };
+        let answers = &mut self.tables[index].1;
+        if !answers.contains(&answer) {
+            answers.push(answer);
+            self.grown = true;
+        }
+    }
+
+    /// Renames the pattern variables of a fact by appending a suffix, except for the engine's variables.
+    fn rename_fact(&self, fact: &Fact, suffix: &str) -> Fact {
+        map_fact_terms(fact, &|term| self.rename_term(term, suffix))
+    }
+
+    fn rename_term(&self, term: &FactValue, suffix: &str) -> FactValue {
+        match term {
+            FactValue::Variable(name) if !self.engine.variable_bindings.contains_key(name) => FactValue::Variable(format!("{}{}", name, suffix)),
+            _ => term.clone(),
+        }
+    }
+}
+
+impl Iterator for Query<'_> {
+    type Item = Result<HashMap<String, FactValue>, EngineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(mut branch) = self.branches.pop() else {
+                // Repeat the search while tabled goals gain answers
+                if !(self.consumed && self.grown) {
+                    return None;
+                }
+                self.consumed = false;
+                self.grown = false;
+                self.branches.push(self.start.clone());
+                continue;
+            };
+
+            match branch.steps.pop() {
+                None => {
+                    let solution: HashMap<String, FactValue> = self.variables.iter()
+                        .filter_map(|name| match resolve(&FactValue::Variable(name.clone()), &branch.bindings) {
+                            FactValue::Variable(_) => None,
+                            value => Some((name.clone(), value)),
+                        })
+                        .collect();
+                    if !self.found.contains(&solution) {
+                        self.found.push(solution.clone());
+                        return Some(Ok(solution));
+                    }
+                },
+                Some(Step::Record(goal)) => {
+                    let answer = map_fact_terms(&goal, &|term| resolve(term, &branch.bindings));
+                    self.record(goal, answer);
+                    self.branches.push(branch);
+                },
+                Some(Step::Prove(goal)) => match self.expand(goal, branch) {
+                    Ok(branches) => self.branches.extend(branches.into_iter().rev()),
+                    Err(error) => {
+                        self.branches.clear();
+                        return Some(Err(error));
+                    },
+                },
+            }
+        }
+    }
+}
+
+fn in_rule(error: EngineError, goal: &Goal) -> EngineError {
+    match goal.rule {
+        Some(rule) => error.in_rule(rule),
+        None => error,
+    }
+}
+
+/// Follows the bindings of a term until reaching a value or an unbound pattern variable.
+fn resolve(term: &FactValue, bindings: &HashMap<String, Variable>) -> FactValue {
+    let mut term = term;
+    while let FactValue::Variable(name) = term {
+        match bindings.get(name) {
+            Some(bound) => term = &bound.value,
+            None => break,
+        }
+    }
+    term.clone()
+}
+
+/// Applies a function to every term of a comparison operand, including the operands of arithmetic expressions.
+fn map_operand_terms(value: &ComparableValue, map: &impl Fn(&FactValue) -> FactValue) -> ComparableValue {
+    match value {
+        ComparableValue::Direct(term) => ComparableValue::Direct(map(term)),
+        ComparableValue::Arithmetic(expression) => {
+            ComparableValue::Arithmetic(Box::new(expression.map_operands(|operand| map_operand_terms(operand, map))))
+        },
+        value => value.clone(),
+    }
+}
+
+/// Applies a function to every term of the facts and comparisons of an expression.
+fn map_terms(expression: &LogicalOperator, map: &impl Fn(&FactValue) -> FactValue) -> LogicalOperator {
+    let value = |value: &ComparableValue| Box::new(map_operand_terms(value, map));
+    match expression {
+        LogicalOperator::And(expressions) => LogicalOperator::And(expressions.iter().map(|expression| map_terms(expression, map)).collect()),
+        LogicalOperator::Or(expressions) => LogicalOperator::Or(expressions.iter().map(|expression| map_terms(expression, map)).collect()),
+        LogicalOperator::Not(expression) => LogicalOperator::Not(Box::new(map_terms(expression, map))),
+        LogicalOperator::AtomicFact(fact) => LogicalOperator::AtomicFact(map_fact_terms(fact, map)),
+        LogicalOperator::GreaterThan(left, right) => LogicalOperator::GreaterThan(value(left), value(right)),
+        LogicalOperator::LessThan(left, right) => LogicalOperator::LessThan(value(left), value(right)),
+        LogicalOperator::EqualTo(left, right) => LogicalOperator::EqualTo(value(left), value(right)),
+        LogicalOperator::NotEqualTo(left, right) => LogicalOperator::NotEqualTo(value(left), value(right)),
+        LogicalOperator::GreaterThanOrEqualTo(left, right) => LogicalOperator::GreaterThanOrEqualTo(value(left), value(right)),
+        LogicalOperator::LessThanOrEqualTo(left, right) => LogicalOperator::LessThanOrEqualTo(value(left), value(right)),
+    }
+}
+
+fn map_fact_terms(fact: &Fact, map: &impl Fn(&FactValue) -> FactValue) -> Fact {
+    Fact {
+        symbol: fact.symbol.clone(),
+        arguments: fact.arguments.iter().map(map).collect(),
+        value: map(&fact.value),
+    }
+}
+
+/// Unifies two facts that may both hold pattern variables, binding variables to values or to each other.
+fn unify(engine: &SymbolicReasoningEngine, fact: &Fact, other: &Fact, bindings: &HashMap<String, Variable>) -> Option<HashMap<String, Variable>> {
+    if fact.symbol != other.symbol || fact.arguments.len() != other.arguments.len() {
+        return None;
+    }
+
+    let mut unified_bindings = bindings.clone();
+    let terms = fact.arguments.iter().zip(&other.arguments).chain(std::iter::once((&fact.value, &other.value)));
+    for (term, other_term) in terms {
+        match (resolve(term, &unified_bindings), resolve(other_term, &unified_bindings)) {
+            (FactValue::Variable(name), FactValue::Variable(other_name)) if name == other_name => {},
+            (FactValue::Variable(name), value) | (value, FactValue::Variable(name)) => {
+                unified_bindings.insert(name.clone(), Variable::new(&name, value, VariableState::Stable));
+            },
+            (value, other_value) if !engine.match_value(&value, &other_value) => return None,
+            _ => {},
+        }
+    }
+
+    Some(unified_bindings)
+}
+
+/// Checks whether two facts are equal up to a consistent renaming of their pattern variables.
+fn is_variant(fact: &Fact, other: &Fact) -> bool {
+    if fact.symbol != other.symbol || fact.arguments.len() != other.arguments.len() {
+        return false;
+    }
+
+    let mut renaming: Vec<(&str, &str)> = Vec::new();
+    let terms = fact.arguments.iter().zip(&other.arguments).chain(std::iter::once((&fact.value, &other.value)));
+    for (term, other_term) in terms {
+        match (term, other_term) {
+            (FactValue::Variable(name), FactValue::Variable(other_name)) => {
+                match renaming.iter().find(|(from, to)| from == name || to == other_name) {
+                    Some(&(from, to)) if from != name || to != other_name => return false,
+                    Some(_) => {},
+                    None => renaming.push((name, other_name)),
+                }
+            },
+            _ if term != other_term => return false,
+            _ => {},
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbol, SymbolType};
+
+    #[test]
+    fn variants_rename_variables_consistently() {
+        let parent = Symbol::predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean);
+        let var = |name: &str| FactValue::Variable(name.to_string());
+        let fact = |a: FactValue, b: FactValue| Fact::with_arguments(parent.clone(), vec![a, b], FactValue::Boolean(true));
+
+        assert!(is_variant(&fact(var("x"), var("y")), &fact(var("a"), var("b"))));
+        assert!(!is_variant(&fact(var("x"), var("x")), &fact(var("a"), var("b"))));
+        assert!(!is_variant(&fact(var("x"), var("y")), &fact(var("a"), var("a"))));
+        assert!(!is_variant(&fact(FactValue::Text("alice".into()), var("y")), &fact(var("a"), var("b"))));
+    }
+}
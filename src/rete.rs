@@ -0,0 +1,508 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{ComparableValue, EngineError, Fact, LogicalOperator, Solution, SymbolicReasoningEngine};
+
+/// A partial match of a rule's premise, along with the time tags ordering it among the other matches.
+#[derive(Debug, Clone)]
+struct Token {
+    solution: Solution,
+    /// One tag per condition matched so far: the time tag of the fact matched by a pattern, or the position of the
+    /// solution among those of an evaluated condition. Sorting tokens by their tags lists the matches in the order a
+    /// scan of the knowledge base would find them, and no two matches of a rule have the same tags.
+    tags: Vec<u64>,
+    /// The time tags of the facts the solution relies on, once the token matches the whole premise. They identify the
+    /// match on the agenda, where a match fires at most once.
+    key: Vec<u64>,
+}
+
+/// The facts of the working memory matching a fact pattern, shared by every condition testing that pattern.
+#[derive(Debug, Clone)]
+struct AlphaMemory {
+    pattern: Fact,
+    /// The matching facts, in the order they were inserted.
+    facts: Vec<(u64, Fact)>,
+    /// The conditions testing the pattern, as the index of their rule and their position in its premise.
+    successors: Vec<(usize, usize)>,
+}
+
+/// A condition of a rule's premise, joined with the conditions before it.
+///
+/// Variants:
+/// - `Pattern`: A fact pattern, joined with the facts of the alpha memory at index `alpha`.
+/// - `Evaluate`: Any other condition, such as a comparison, a negation or a disjunction, evaluated for every token by
+///   the engine. Its results depend on the facts of the symbols it refers to, so the condition is re-evaluated whenever
+///   one of them changes.
+#[derive(Debug, Clone)]
+enum Condition {
+    Pattern { pattern: Fact, alpha: usize },
+    Evaluate { expression: LogicalOperator },
+}
+
+/// The join network of a single rule: a chain of conditions, each with the beta memory of the tokens matching the
+/// conditions before it.
+#[derive(Debug, Clone)]
+struct RuleNetwork {
+    conditions: Vec<Condition>,
+    /// `memories[k]` holds the tokens matching the first `k` conditions, so the last one holds the rule's matches.
+    memories: Vec<Vec<Token>>,
+    /// The index of the first condition that must be re-evaluated from scratch, if any.
+    dirty: Option<usize>,
+    /// The number of matches with each key.
+    keys: HashMap<Vec<u64>, usize>,
+    /// The matches dropped and added since the agenda last took them, by the tags of their token. A match dropped and
+    /// added again with the same key is unchanged.
+    dropped: HashMap<Vec<u64>, Vec<u64>>,
+    added: HashMap<Vec<u64>, Token>,
+}
+
+/// The changes to the matches of a rule since the agenda last took them.
+#[derive(Debug, Default)]
+pub(crate) struct MatchChanges<'a> {
+    /// The tags of the matches no longer found.
+    pub(crate) dropped: HashSet<&'a [u64]>,
+    /// The keys of the dropped matches that no remaining match shares, which may fire again once matched.
+    pub(crate) unmatched: Vec<&'a [u64]>,
+    /// The new matches, as their tags, their key and their solution, in the order a scan of the knowledge base would
+    /// find them.
+    pub(crate) added: Vec<(&'a [u64], &'a [u64], &'a Solution)>,
+}
+
+/// A change to the working memory, waiting to be propagated through the network.
+#[derive(Debug, Clone)]
+enum Change {
+    Insert(u64, Fact),
+    Remove(u64, Fact),
+}
+
+/// A Rete match network over the premises of the engine's rules, used by forward chaining.
+///
+/// Every fact pattern of the rules has an alpha memory holding the facts matching it, and every rule has a chain of
+/// join nodes, one per condition of its premise, whose beta memories hold the partial matches of the conditions so
+/// far. Asserting a fact only joins it with the partial matches of the conditions whose pattern it matches, and
+/// retracting one only drops the partial matches that relied on it, so the cost of an update depends on the rules
+/// the fact is relevant to rather than on the size of the knowledge base. Conditions other than fact patterns, such as
+/// negations and comparisons, are re-evaluated when a fact of a symbol they refer to changes.
+///
+/// The network mirrors the knowledge base through `inserted` and `removed`, which record changes to be propagated by
+/// the next call to `update`, and records the matches each update drops and adds until the agenda takes them. Facts
+/// are tagged in the order they are inserted, so that matches are reported in the order a scan of the knowledge base
+/// would find them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Network {
+    working_memory: HashMap<Fact, u64>,
+    next_tag: u64,
+    alphas: Vec<AlphaMemory>,
+    /// The alpha memories of the patterns of each symbol.
+    alphas_by_symbol: HashMap<String, Vec<usize>>,
+    /// The evaluated conditions reading the facts of each symbol, as the index of their rule and their position in
+    /// its premise.
+    dependents: HashMap<String, Vec<(usize, usize)>>,
+    rules: Vec<Option<RuleNetwork>>,
+    /// The rules whose join nodes must be built or re-evaluated by the next update.
+    dirty: BTreeSet<usize>,
+    /// The rules whose matches may have changed since the agenda last took them.
+    changed: BTreeSet<usize>,
+    pending: Vec<Change>,
+    stale: bool,
+}
+
+impl Network {
+    /// Adds a rule to the network. Its join nodes are built by the next call to `update`.
+    pub(crate) fn add_rule(&mut self) {
+        self.dirty.insert(self.rules.len());
+        self.rules.push(None);
+    }
+
+    /// Records a fact added to the knowledge base.
+    pub(crate) fn inserted(&mut self, fact: &Fact) {
+        self.next_tag += 1;
+        self.working_memory.insert(fact.clone(), self.next_tag);
+        self.pending.push(Change::Insert(self.next_tag, fact.clone()));
+    }
+
+    /// Records a fact removed from the knowledge base.
+    pub(crate) fn removed(&mut self, fact: &Fact) {
+        if let Some(tag) = self.working_memory.remove(fact) {
+            self.pending.push(Change::Remove(tag, fact.clone()));
+        }
+    }
+
+    /// Marks the whole network for rebuilding, as needed when the engine's variables change the meaning of patterns.
+    pub(crate) fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Propagates the recorded changes through the network, building the join nodes of new rules.
+    ///
+    /// # Errors
+    /// Returns `EngineError::RuleEvaluation` if a condition of a rule cannot be evaluated. The network is rebuilt on
+    /// the next update.
+    pub(crate) fn update(&mut self, engine: &SymbolicReasoningEngine) -> Result<(), EngineError> {
+        let result = self.propagate(engine);
+        if result.is_err() {
+            self.stale = true;
+        }
+        result
+    }
+
+    /// Returns the indices of the rules whose matches changed since the agenda last took them.
+    pub(crate) fn changed(&self) -> Vec<usize> {
+        self.changed.iter()
+            .copied()
+            .filter(|&index| self.rules[index].as_ref().is_some_and(|network| !network.dropped.is_empty() || !network.added.is_empty()))
+            .collect()
+    }
+
+    /// Returns the changes to the matches of a rule since the agenda last took them.
+    pub(crate) fn changes(&self, rule: usize) -> MatchChanges<'_> {
+        let Some(Some(network)) = self.rules.get(rule) else {
+            return MatchChanges::default();
+        };
+        let mut added: Vec<&Token> = network.added.values().collect();
+        added.sort_by(|left, right| left.tags.cmp(&right.tags));
+        MatchChanges {
+            dropped: network.dropped.keys().map(Vec::as_slice).collect(),
+            unmatched: network.dropped.values().filter(|key| !network.keys.contains_key(*key)).map(Vec::as_slice).collect(),
+            added: added.into_iter().map(|token| (token.tags.as_slice(), token.key.as_slice(), &token.solution)).collect(),
+        }
+    }
+
+    /// Forgets the changes to the matches of a rule, once the agenda has taken them.
+    pub(crate) fn clear_changes(&mut self, rule: usize) {
+        if let Some(Some(network)) = self.rules.get_mut(rule) {
+            network.dropped.clear();
+            network.added.clear();
+        }
+        self.changed.remove(&rule);
+    }
+
+    /// Returns the matches of a rule's premise, in the order a scan of the knowledge base would find them.
+    #[cfg(test)]
+    pub(crate) fn matches(&self, rule: usize) -> Vec<Solution> {
+        let Some(Some(network)) = self.rules.get(rule) else {
+            return Vec::new();
+        };
+        let mut tokens: Vec<&Token> = network.memories.last().into_iter().flatten().collect();
+        tokens.sort_by(|left, right| left.tags.cmp(&right.tags));
+        tokens.into_iter().map(|token| token.solution.clone()).collect()
+    }
+
+    fn propagate(&mut self, engine: &SymbolicReasoningEngine) -> Result<(), EngineError> {
+        let stale = std::mem::take(&mut self.stale);
+        if stale {
+            self.pending.clear();
+            self.alphas.clear();
+            self.alphas_by_symbol.clear();
+            self.dependents.clear();
+            self.dirty.extend(0..self.rules.len());
+        }
+
+        for change in std::mem::take(&mut self.pending) {
+            match change {
+                Change::Insert(tag, fact) => self.insert(engine, tag, fact)?,
+                Change::Remove(tag, fact) => self.remove(tag, &fact),
+            }
+        }
+
+        for index in std::mem::take(&mut self.dirty) {
+            let (network, result) = match self.rules[index].take() {
+                Some(mut network) if !stale => {
+                    let result = match network.dirty {
+                        Some(condition) => self.reevaluate(engine, &mut network, condition),
+                        None => Ok(()),
+                    };
+                    (network, result)
+                },
+                previous => self.build(engine, index, previous),
+            };
+            self.rules[index] = Some(network);
+            self.changed.insert(index);
+            result.map_err(|error| error.in_rule(index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a fact to the alpha memories it matches and joins it with the partial matches of their conditions.
+    fn insert(&mut self, engine: &SymbolicReasoningEngine, tag: u64, fact: Fact) -> Result<(), EngineError> {
+        let mut successors = Vec::new();
+        for &alpha in self.alphas_by_symbol.get(&fact.symbol.name).into_iter().flatten() {
+            let memory = &mut self.alphas[alpha];
+            if engine.matches_pattern(&memory.pattern, &fact) {
+                memory.facts.push((tag, fact.clone()));
+                successors.extend(memory.successors.iter().copied());
+            }
+        }
+        self.mark_dependents(&fact.symbol.name);
+
+        // Join the deepest conditions of a rule first, so that a fact matching several of them is not joined twice
+        successors.sort_unstable_by(|(rule, condition), (other_rule, other_condition)| {
+            rule.cmp(other_rule).then(other_condition.cmp(condition))
+        });
+        for (index, position) in successors {
+            let Some(network) = self.rules[index].as_mut() else {
+                continue;
+            };
+            let Condition::Pattern { pattern, .. } = &network.conditions[position] else {
+                continue;
+            };
+            let tokens: Vec<Token> = network.memories[position].iter()
+                .filter_map(|token| join(engine, token, pattern, tag, &fact))
+                .collect();
+            self.changed.insert(index);
+            network.extend(engine, &self.alphas, &self.working_memory, position + 1, tokens).map_err(|error| error.in_rule(index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a fact from the alpha memories and drops the partial matches relying on it.
+    fn remove(&mut self, tag: u64, fact: &Fact) {
+        let mut successors = Vec::new();
+        for &alpha in self.alphas_by_symbol.get(&fact.symbol.name).into_iter().flatten() {
+            let memory = &mut self.alphas[alpha];
+            if let Ok(position) = memory.facts.binary_search_by_key(&tag, |(known_tag, _)| *known_tag) {
+                memory.facts.remove(position);
+                successors.extend(memory.successors.iter().copied());
+            }
+        }
+        self.mark_dependents(&fact.symbol.name);
+
+        // Only the memories after the first condition matching the fact hold tokens relying on it
+        successors.sort_unstable();
+        successors.dedup_by_key(|(rule, _)| *rule);
+        for (index, position) in successors {
+            let Some(network) = self.rules[index].as_mut() else {
+                continue;
+            };
+            let last = network.conditions.len();
+            for memory in &mut network.memories[position + 1..last] {
+                memory.retain(|token| !token.solution.antecedents.contains(fact));
+            }
+            let (dropped, kept) = std::mem::take(&mut network.memories[last]).into_iter()
+                .partition::<Vec<Token>, _>(|token| token.solution.antecedents.contains(fact));
+            network.memories[last] = kept;
+            dropped.iter().for_each(|token| network.drop_match(token));
+            self.changed.insert(index);
+        }
+    }
+
+    /// Marks the evaluated conditions depending on a symbol's facts for re-evaluation.
+    fn mark_dependents(&mut self, symbol: &str) {
+        for &(index, position) in self.dependents.get(symbol).into_iter().flatten() {
+            if let Some(network) = &mut self.rules[index] {
+                network.dirty = Some(network.dirty.map_or(position, |dirty| dirty.min(position)));
+                self.dirty.insert(index);
+            }
+        }
+    }
+
+    /// Compiles the premise of a rule into a chain of conditions and computes their memories from the current working
+    /// memory, dropping the matches of the network it replaces, if any.
+    fn build(&mut self, engine: &SymbolicReasoningEngine, index: usize, previous: Option<RuleNetwork>) -> (RuleNetwork, Result<(), EngineError>) {
+        let mut conditions = Vec::new();
+        self.compile(engine, index, &engine.rules[index].premise, &mut conditions);
+
+        let mut network = RuleNetwork {
+            memories: vec![Vec::new(); conditions.len() + 1],
+            conditions,
+            dirty: None,
+            keys: HashMap::new(),
+            dropped: HashMap::new(),
+            added: HashMap::new(),
+        };
+        if let Some(mut previous) = previous {
+            previous.memories.pop().into_iter().flatten().for_each(|token| previous.drop_match(&token));
+            network.dropped = previous.dropped;
+            network.added = previous.added;
+        }
+
+        let token = Token { solution: Solution::new(engine.variable_bindings.clone()), tags: Vec::new(), key: Vec::new() };
+        let result = network.extend(engine, &self.alphas, &self.working_memory, 0, vec![token]);
+        (network, result)
+    }
+
+    /// Flattens the conjunctions of a premise into conditions, sharing alpha memories between identical patterns.
+    fn compile(&mut self, engine: &SymbolicReasoningEngine, rule: usize, expression: &LogicalOperator, conditions: &mut Vec<Condition>) {
+        let position = conditions.len();
+        match expression {
+            LogicalOperator::And(expressions) => {
+                for expression in expressions {
+                    self.compile(engine, rule, expression, conditions);
+                }
+            },
+            LogicalOperator::AtomicFact(fact) => {
+                let pattern = engine.interpolate_fact(fact, false);
+                let alpha = match self.alphas.iter().position(|alpha| alpha.pattern == pattern) {
+                    Some(alpha) => alpha,
+                    None => {
+                        let mut facts: Vec<(u64, Fact)> = self.working_memory.iter()
+                            .filter(|(known_fact, _)| engine.matches_pattern(&pattern, known_fact))
+                            .map(|(known_fact, tag)| (*tag, known_fact.clone()))
+                            .collect();
+                        facts.sort_unstable_by_key(|(tag, _)| *tag);
+                        self.alphas_by_symbol.entry(pattern.symbol.name.clone()).or_default().push(self.alphas.len());
+                        self.alphas.push(AlphaMemory { pattern: pattern.clone(), facts, successors: Vec::new() });
+                        self.alphas.len() - 1
+                    },
+                };
+                self.alphas[alpha].successors.push((rule, position));
+                conditions.push(Condition::Pattern { pattern, alpha });
+            },
+            expression => {
+                let mut symbols = Vec::new();
+                referenced_symbols(expression, &mut symbols);
+                for symbol in symbols {
+                    self.dependents.entry(symbol).or_default().push((rule, position));
+                }
+                conditions.push(Condition::Evaluate { expression: expression.clone() });
+            },
+        }
+    }
+
+    /// Recomputes the memories following a condition from the memory before it.
+    fn reevaluate(&self, engine: &SymbolicReasoningEngine, network: &mut RuleNetwork, condition: usize) -> Result<(), EngineError> {
+        network.dirty = None;
+        let last = network.conditions.len();
+        let dropped = std::mem::take(&mut network.memories[last]);
+        dropped.iter().for_each(|token| network.drop_match(token));
+        network.memories[condition + 1..].iter_mut().for_each(Vec::clear);
+
+        let tokens = network.activate(engine, &self.alphas, condition, &network.memories[condition])?;
+        network.extend(engine, &self.alphas, &self.working_memory, condition + 1, tokens)
+    }
+}
+
+impl RuleNetwork {
+    /// Adds new tokens to the memory at `position` and propagates them through the following conditions.
+    fn extend(
+        &mut self,
+        engine: &SymbolicReasoningEngine,
+        alphas: &[AlphaMemory],
+        time_tags: &HashMap<Fact, u64>,
+        position: usize,
+        mut tokens: Vec<Token>,
+    ) -> Result<(), EngineError> {
+        for condition in position..self.conditions.len() {
+            if tokens.is_empty() {
+                return Ok(());
+            }
+            self.memories[condition].extend(tokens.iter().cloned());
+            tokens = self.activate(engine, alphas, condition, &tokens)?;
+        }
+
+        for mut token in tokens {
+            token.key = token.solution.antecedents.iter().map(|fact| time_tags.get(fact).copied().unwrap_or(0)).collect();
+            self.add_match(token);
+        }
+        Ok(())
+    }
+
+    /// Extends tokens matching the conditions before `condition` with the ways `condition` holds for them.
+    fn activate(&self, engine: &SymbolicReasoningEngine, alphas: &[AlphaMemory], condition: usize, tokens: &[Token]) -> Result<Vec<Token>, EngineError> {
+        let mut extended = Vec::new();
+        for token in tokens {
+            match &self.conditions[condition] {
+                Condition::Pattern { pattern, alpha } => {
+                    extended.extend(alphas[*alpha].facts.iter().filter_map(|(tag, fact)| join(engine, token, pattern, *tag, fact)));
+                },
+                Condition::Evaluate { expression } => {
+                    let solutions = engine.evaluate_logical_expression(expression, &token.solution, None)?;
+                    extended.extend(solutions.into_iter().zip(0..).map(|(solution, position)| {
+                        let mut tags = token.tags.clone();
+                        tags.push(position);
+                        Token { solution, tags, key: Vec::new() }
+                    }));
+                },
+            }
+        }
+        Ok(extended)
+    }
+
+    /// Adds a token to the rule's matches, recording it as added unless it replaces an equal match dropped since the
+    /// agenda last took the changes.
+    fn add_match(&mut self, token: Token) {
+        *self.keys.entry(token.key.clone()).or_default() += 1;
+        if self.dropped.get(&token.tags) == Some(&token.key) {
+            self.dropped.remove(&token.tags);
+        } else {
+            self.added.insert(token.tags.clone(), token.clone());
+        }
+        self.memories[self.conditions.len()].push(token);
+    }
+
+    /// Records a token removed from the rule's matches as dropped, unless it was added since the agenda last took the
+    /// changes.
+    fn drop_match(&mut self, token: &Token) {
+        if let Some(count) = self.keys.get_mut(&token.key) {
+            *count -= 1;
+            if *count == 0 {
+                self.keys.remove(&token.key);
+            }
+        }
+        if self.added.remove(&token.tags).is_none() {
+            self.dropped.insert(token.tags.clone(), token.key.clone());
+        }
+    }
+}
+
+/// Joins a token with a fact matched by a pattern, if the fact agrees with the token's bindings.
+fn join(engine: &SymbolicReasoningEngine, token: &Token, pattern: &Fact, tag: u64, fact: &Fact) -> Option<Token> {
+    let bindings = engine.unify_fact(pattern, fact, &token.solution.bindings)?;
+    let mut tags = token.tags.clone();
+    tags.push(tag);
+    Some(Token { solution: Solution { bindings, ..token.solution.supported_by([fact.clone()]) }, tags, key: Vec::new() })
+}
+
+/// Collects the names of the symbols whose facts an expression reads.
+fn referenced_symbols(expression: &LogicalOperator, symbols: &mut Vec<String>) {
+    let mut add = |name: &str| {
+        if !symbols.iter().any(|symbol| symbol == name) {
+            symbols.push(name.to_string());
+        }
+    };
+    match expression {
+        LogicalOperator::And(expressions) | LogicalOperator::Or(expressions) => {
+            expressions.iter().for_each(|expression| referenced_symbols(expression, symbols));
+        },
+        LogicalOperator::Not(expression) => referenced_symbols(expression, symbols),
+        LogicalOperator::AtomicFact(fact) => add(&fact.symbol.name),
+        LogicalOperator::GreaterThan(left, right)
+        | LogicalOperator::LessThan(left, right)
+        | LogicalOperator::EqualTo(left, right)
+        | LogicalOperator::NotEqualTo(left, right)
+        | LogicalOperator::GreaterThanOrEqualTo(left, right)
+        | LogicalOperator::LessThanOrEqualTo(left, right) => {
+            for value in [left, right].into_iter().flat_map(|value| value.leaves()) {
+                match value {
+                    ComparableValue::Direct(_) | ComparableValue::Arithmetic(_) => {},
+                    ComparableValue::Symbol(symbol) => add(&symbol.name),
+                    ComparableValue::SymbolName(name) => add(name),
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactValue, Symbol, SymbolType};
+
+    #[test]
+    fn evaluated_conditions_depend_on_every_symbol_they_read() {
+        let rain = Symbol::new("Rain", SymbolType::Boolean);
+        let temperature = Symbol::new("Temperature", SymbolType::Integer);
+        let expression = LogicalOperator::Or(vec![
+            LogicalOperator::Not(Box::new(LogicalOperator::AtomicFact(Fact::new(rain, FactValue::Boolean(true))))),
+            LogicalOperator::GreaterThan(
+                Box::new(ComparableValue::Symbol(temperature)),
+                Box::new(ComparableValue::SymbolName("Threshold".into())),
+            ),
+        ]);
+
+        let mut symbols = Vec::new();
+        referenced_symbols(&expression, &mut symbols);
+        assert_eq!(symbols, vec!["Rain", "Temperature", "Threshold"]);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::{EngineError, FactValue};
+
+/// Represents the schema of the values a symbol may hold.
+///
+/// Every symbol is declared with a `SymbolType`, which the engine enforces whenever a fact is asserted, a rule is
+/// defined, or a comparison is evaluated. This ensures that schema violations, such as storing text under an integer
+/// symbol or comparing a boolean with a number, are rejected up front rather than silently producing wrong results.
+///
+/// Variants:
+/// - `Integer`: Accepts `FactValue::Integer` values.
+/// - `Float`: Accepts `FactValue::Float` values.
+/// - `Boolean`: Accepts `FactValue::Boolean` values.
+/// - `Text`: Accepts any `FactValue::Text` value.
+/// - `Enum`: Accepts `FactValue::Text` values that are one of the listed alternatives.
+/// - `IntegerRange`: Accepts `FactValue::Integer` values within the inclusive range `min..=max`.
+/// - `FloatRange`: Accepts `FactValue::Float` values within the inclusive range `min..=max`.
+#[derive(Debug, Clone)]
+pub enum SymbolType {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+    Enum(Vec<String>),
+    IntegerRange { min: i32, max: i32 },
+    FloatRange { min: f64, max: f64 },
+}
+
+impl SymbolType {
+    /// Checks whether values of this type can be compared numerically.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, SymbolType::Integer | SymbolType::Float | SymbolType::IntegerRange { .. } | SymbolType::FloatRange { .. })
+    }
+
+    /// Checks whether values of this type are represented as text.
+    pub fn is_textual(&self) -> bool {
+        matches!(self, SymbolType::Text | SymbolType::Enum(_))
+    }
+
+    /// Checks whether the given value satisfies this type's schema.
+    ///
+    /// # Arguments
+    /// * `value` - The `FactValue` to check.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the value is of the right variant and, for enumerations and ranges, within the allowed
+    ///   values; otherwise, `false`.
+    pub fn accepts(&self, value: &FactValue) -> bool {
+        match (self, value) {
+            (SymbolType::Integer, FactValue::Integer(_)) => true,
+            (SymbolType::Float, FactValue::Float(_)) => true,
+            (SymbolType::Boolean, FactValue::Boolean(_)) => true,
+            (SymbolType::Text, FactValue::Text(_)) => true,
+            (SymbolType::Enum(allowed), FactValue::Text(text)) => allowed.contains(text),
+            (SymbolType::IntegerRange { min, max }, FactValue::Integer(val)) => (*min..=*max).contains(val),
+            (SymbolType::FloatRange { min, max }, FactValue::Float(val)) => (*min..=*max).contains(val),
+            _ => false,
+        }
+    }
+
+    /// Converts text, typically the result of variable interpolation, into a value of this type.
+    ///
+    /// # Arguments
+    /// * `text` - The text to convert.
+    ///
+    /// # Returns
+    /// * `Option<FactValue>` - The converted value, or `None` if the text cannot be parsed as this type. The value is
+    ///   not checked against enumeration or range constraints; use `accepts` for that.
+    pub fn coerce(&self, text: &str) -> Option<FactValue> {
+        match self {
+            SymbolType::Integer | SymbolType::IntegerRange { .. } => text.trim().parse().ok().map(FactValue::Integer),
+            SymbolType::Float | SymbolType::FloatRange { .. } => text.trim().parse().ok().map(FactValue::Float),
+            SymbolType::Boolean => text.trim().parse().ok().map(FactValue::Boolean),
+            SymbolType::Text | SymbolType::Enum(_) => Some(FactValue::Text(text.to_string())),
+        }
+    }
+
+    /// Infers the type of a concrete value, as used when checking comparisons against direct values.
+    ///
+    /// Returns `None` for pattern variables, whose type is only known once they are bound.
+    pub(crate) fn of_value(value: &FactValue) -> Option<SymbolType> {
+        match value {
+            FactValue::Integer(_) => Some(SymbolType::Integer),
+            FactValue::Float(_) => Some(SymbolType::Float),
+            FactValue::Boolean(_) => Some(SymbolType::Boolean),
+            FactValue::Text(_) => Some(SymbolType::Text),
+            FactValue::Variable(_) => None,
+        }
+    }
+
+    /// Checks whether values of this type can be tested for equality against values of another type.
+    pub(crate) fn is_comparable_with(&self, other: &SymbolType) -> bool {
+        (self.is_numeric() && other.is_numeric())
+            || (self.is_textual() && other.is_textual())
+            || (matches!(self, SymbolType::Boolean) && matches!(other, SymbolType::Boolean))
+    }
+}
+
+impl PartialEq for SymbolType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SymbolType::Integer, SymbolType::Integer) => true,
+            (SymbolType::Float, SymbolType::Float) => true,
+            (SymbolType::Boolean, SymbolType::Boolean) => true,
+            (SymbolType::Text, SymbolType::Text) => true,
+            (SymbolType::Enum(l), SymbolType::Enum(r)) => l == r,
+            (SymbolType::IntegerRange { min: l_min, max: l_max }, SymbolType::IntegerRange { min: r_min, max: r_max }) => {
+                l_min == r_min && l_max == r_max
+            },
+            // Compare float bounds bitwise so that equality stays reflexive and consistent with `Hash`
+            (SymbolType::FloatRange { min: l_min, max: l_max }, SymbolType::FloatRange { min: r_min, max: r_max }) => {
+                l_min.to_bits() == r_min.to_bits() && l_max.to_bits() == r_max.to_bits()
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SymbolType {}
+
+impl Hash for SymbolType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            SymbolType::Enum(allowed) => allowed.hash(state),
+            SymbolType::IntegerRange { min, max } => {
+                min.hash(state);
+                max.hash(state);
+            },
+            SymbolType::FloatRange { min, max } => {
+                min.to_bits().hash(state);
+                max.to_bits().hash(state);
+            },
+            _ => {},
+        }
+    }
+}
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolType::Integer => write!(f, "Integer"),
+            SymbolType::Float => write!(f, "Float"),
+            SymbolType::Boolean => write!(f, "Boolean"),
+            SymbolType::Text => write!(f, "Text"),
+            SymbolType::Enum(allowed) => write!(f, "Enum({})", allowed.join(", ")),
+            SymbolType::IntegerRange { min, max } => write!(f, "Integer[{}..={}]", min, max),
+            SymbolType::FloatRange { min, max } => write!(f, "Float[{:?}..={:?}]", min, max),
+        }
+    }
+}
+
+/// Parses the name of a primitive symbol type.
+///
+/// Accepts "Integer", "Float", "Boolean" and "Text", as well as "String" as an alias of "Text". Enumerations and
+/// ranges carry parameters and must be constructed directly.
+impl FromStr for SymbolType {
+    type Err = EngineError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "Integer" => Ok(SymbolType::Integer),
+            "Float" => Ok(SymbolType::Float),
+            "Boolean" => Ok(SymbolType::Boolean),
+            "Text" | "String" => Ok(SymbolType::Text),
+            _ => Err(EngineError::UnknownSymbolType { name: name.to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_values_within_schema() {
+        let level = SymbolType::Enum(vec!["Low".into(), "High".into()]);
+        assert!(level.accepts(&FactValue::Text("Low".into())));
+        assert!(!level.accepts(&FactValue::Text("Medium".into())));
+
+        let percentage = SymbolType::IntegerRange { min: 0, max: 100 };
+        assert!(percentage.accepts(&FactValue::Integer(100)));
+        assert!(!percentage.accepts(&FactValue::Integer(101)));
+        assert!(!percentage.accepts(&FactValue::Float(50.0)));
+
+        let ratio = SymbolType::FloatRange { min: 0.0, max: 1.0 };
+        assert!(ratio.accepts(&FactValue::Float(0.5)));
+        assert!(!ratio.accepts(&FactValue::Float(1.5)));
+    }
+
+    #[test]
+    fn coerces_interpolated_text() {
+        assert_eq!(SymbolType::Integer.coerce("30"), Some(FactValue::Integer(30)));
+        assert_eq!(SymbolType::Float.coerce("2.5"), Some(FactValue::Float(2.5)));
+        assert_eq!(SymbolType::Boolean.coerce("true"), Some(FactValue::Boolean(true)));
+        assert_eq!(SymbolType::Integer.coerce("warm"), None);
+    }
+
+    #[test]
+    fn parses_primitive_type_names() {
+        assert_eq!("String".parse::<SymbolType>(), Ok(SymbolType::Text));
+        assert_eq!("Float".parse::<SymbolType>(), Ok(SymbolType::Float));
+        assert_eq!("Decimal".parse::<SymbolType>(), Err(EngineError::UnknownSymbolType { name: "Decimal".into() }));
+    }
+}
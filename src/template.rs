@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use crate::{Arithmetic, ComparableValue, EngineError, FactValue, SymbolicReasoningEngine, Variable, VariableState};
+use crate::arithmetic::Operation;
+
+/// Checks whether a text value contains at least one unescaped `${...}` placeholder.
+pub(crate) fn has_placeholders(text: &str) -> bool {
+    placeholders(text).next().is_some()
+}
+
+/// Verifies that every placeholder of a template holds a well-formed variable name or arithmetic expression.
+///
+/// # Errors
+/// Returns `EngineError::InvalidTemplate` for the first placeholder that cannot be parsed.
+pub(crate) fn validate(template: &str) -> Result<(), EngineError> {
+    for (_, source) in placeholders(template) {
+        Parser::new(template, source).parse()?;
+    }
+    Ok(())
+}
+
+/// Renders every `${...}` placeholder of a conclusion template against the bindings of a successful match.
+///
+/// A placeholder holding a single variable name, optionally prefixed with `?`, is replaced by the variable's value.
+/// References to variables that are unbound or `Unstable` are kept verbatim, so that they can be resolved later, as
+/// with facts asserted through `assert_fact`. Any other placeholder is an arithmetic expression over variables and
+/// numeric literals, using `+`, `-`, `*`, `/`, `%`, parentheses, `abs`, `min`, `max` and the functions registered
+/// through `SymbolicReasoningEngine::register_function`, and is replaced by its value, computed as for an `Arithmetic`
+/// comparison operand. The value is an integer if every operand is an integer and the result is a whole number, as
+/// for divisions that divide evenly. Escaped placeholders (`\${`) of the template are rendered as a literal `${`, while
+/// the values substituted for placeholders are inserted unchanged.
+///
+/// # Arguments
+/// * `engine` - The engine whose registered functions the expressions may call.
+/// * `template` - The text to render, typically a text value or argument of a rule's conclusion.
+/// * `bindings` - The bindings of the match, including both pattern variables and asserted variables.
+///
+/// # Returns
+/// * `Result<String, EngineError>` - The rendered text.
+///
+/// # Errors
+/// Returns `EngineError::InvalidTemplate` if a placeholder cannot be parsed or its integer result overflows,
+/// `EngineError::UnboundVariable` if an arithmetic expression references a variable without a stable binding, and the
+/// errors of `SymbolicReasoningEngine::evaluate_arithmetic`, such as `EngineError::DivisionByZero`, or
+/// `EngineError::NonNumericComparison` for an operand that is not a number.
+pub(crate) fn render(engine: &SymbolicReasoningEngine, template: &str, bindings: &HashMap<String, Variable>) -> Result<String, EngineError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut last_end = 0;
+
+    for (range, source) in placeholders(template) {
+        rendered.push_str(&unescape(&template[last_end..range.0]));
+        last_end = range.1;
+
+        match Parser::new(template, source).parse()? {
+            ComparableValue::Direct(FactValue::Variable(name)) => match stable_binding(&name, bindings) {
+                Some(value) => rendered.push_str(&value_to_string(value)),
+                None => rendered.push_str(&template[range.0..range.1]),
+            },
+            expression => rendered.push_str(&value_to_string(&evaluate(engine, template, &expression, bindings)?)),
+        }
+    }
+
+    rendered.push_str(&unescape(&template[last_end..]));
+    Ok(rendered)
+}
+
+/// Renders the escaped placeholders of a literal segment of a template as `${`.
+fn unescape(segment: &str) -> String {
+    segment.replace("\\${", "${")
+}
+
+/// Iterates over the unescaped placeholders of a template, yielding their byte range and their inner source.
+fn placeholders(template: &str) -> impl Iterator<Item = ((usize, usize), &str)> {
+    let mut search_from = 0;
+    std::iter::from_fn(move || {
+        loop {
+            let start = search_from + template[search_from..].find("${")?;
+            let end = start + template[start..].find('}')? + 1;
+            search_from = start + 2;
+            if start > 0 && template.as_bytes()[start - 1] == b'\\' {
+                continue;
+            }
+            search_from = end;
+            return Some(((start, end), &template[start + 2..end - 1]));
+        }
+    })
+}
+
+/// Looks up a variable that may be interpolated, ignoring unstable bindings.
+fn stable_binding<'a>(name: &str, bindings: &'a HashMap<String, Variable>) -> Option<&'a FactValue> {
+    bindings.get(name)
+        .filter(|variable| *variable.state() == VariableState::Stable)
+        .map(|variable| variable.value())
+}
+
+fn value_to_string(value: &FactValue) -> String {
+    match value {
+        FactValue::Integer(val) => val.to_string(),
+        FactValue::Float(val) => val.to_string(),
+        FactValue::Boolean(val) => val.to_string(),
+        FactValue::Text(val) => val.clone(),
+        FactValue::Variable(name) => format!("?{}", name),
+    }
+}
+
+/// Computes the value of a placeholder's expression, as an integer if every operand is an integer and the result is a
+/// whole number.
+fn evaluate(engine: &SymbolicReasoningEngine, template: &str, expression: &ComparableValue, bindings: &HashMap<String, Variable>) -> Result<FactValue, EngineError> {
+    let mut integral = true;
+    for leaf in expression.leaves() {
+        let ComparableValue::Direct(value) = leaf else {
+            continue;
+        };
+        let value = match value {
+            FactValue::Variable(name) => stable_binding(name, bindings).ok_or_else(|| EngineError::UnboundVariable { name: name.clone() })?,
+            value => value,
+        };
+        integral &= match value {
+            FactValue::Integer(_) => true,
+            FactValue::Text(text) => text.parse::<i32>().is_ok(),
+            _ => false,
+        };
+    }
+
+    let value = engine.resolve_comparable_value(expression, bindings)?;
+    if !integral || value.fract() != 0.0 {
+        return Ok(FactValue::Float(value));
+    }
+    if value < i32::MIN as f64 || value > i32::MAX as f64 {
+        return Err(EngineError::InvalidTemplate { template: template.to_string(), message: "integer overflow".to_string() });
+    }
+    Ok(FactValue::Integer(value as i32))
+}
+
+fn parse_number(text: &str) -> Option<FactValue> {
+    text.parse().ok().map(FactValue::Integer)
+        .or_else(|| f64::from_str(text).ok().map(FactValue::Float))
+}
+
+/// A recursive descent parser for placeholder expressions.
+struct Parser<'a> {
+    template: &'a str,
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(template: &'a str, source: &'a str) -> Self {
+        Self { template, source, chars: source.char_indices().peekable() }
+    }
+
+    fn parse(mut self) -> Result<ComparableValue, EngineError> {
+        let expression = self.parse_sum()?;
+        match self.peek() {
+            None => Ok(expression),
+            Some(c) => Err(self.error(format!("unexpected '{}'", c))),
+        }
+    }
+
+    fn error(&self, message: String) -> EngineError {
+        EngineError::InvalidTemplate {
+            template: self.template.to_string(),
+            message: format!("{} in placeholder '${{{}}}'", message, self.source),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    /// Consumes the next character if it is `expected`.
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.chars.next();
+        }
+        found
+    }
+
+    fn parse_sum(&mut self) -> Result<ComparableValue, EngineError> {
+        let mut expression = self.parse_product()?;
+        loop {
+            let operation: Operation = match self.peek() {
+                Some('+') => Arithmetic::Add,
+                Some('-') => Arithmetic::Subtract,
+                _ => return Ok(expression),
+            };
+            self.chars.next();
+            expression = ComparableValue::Arithmetic(Box::new(operation(Box::new(expression), Box::new(self.parse_product()?))));
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<ComparableValue, EngineError> {
+        let mut expression = self.parse_operand()?;
+        loop {
+            let operation: Operation = match self.peek() {
+                Some('*') => Arithmetic::Multiply,
+                Some('/') => Arithmetic::Divide,
+                Some('%') => Arithmetic::Modulo,
+                _ => return Ok(expression),
+            };
+            self.chars.next();
+            expression = ComparableValue::Arithmetic(Box::new(operation(Box::new(expression), Box::new(self.parse_operand()?))));
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<ComparableValue, EngineError> {
+        match self.peek() {
+            Some('-') => {
+                self.chars.next();
+                match self.parse_operand()? {
+                    ComparableValue::Direct(FactValue::Integer(value)) if value != i32::MIN => Ok(ComparableValue::Direct(FactValue::Integer(-value))),
+                    ComparableValue::Direct(FactValue::Float(value)) => Ok(ComparableValue::Direct(FactValue::Float(-value))),
+                    operand => {
+                        let zero = Box::new(ComparableValue::Direct(FactValue::Integer(0)));
+                        Ok(ComparableValue::Arithmetic(Box::new(Arithmetic::Subtract(zero, Box::new(operand)))))
+                    },
+                }
+            },
+            Some('(') => {
+                self.chars.next();
+                let expression = self.parse_sum()?;
+                match self.eat(')') {
+                    true => Ok(expression),
+                    false => Err(self.error("expected ')'".to_string())),
+                }
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                parse_number(number)
+                    .map(ComparableValue::Direct)
+                    .ok_or_else(|| self.error(format!("invalid number '{}'", number)))
+            },
+            Some(c) if c == '?' || c == '_' || c.is_alphabetic() => {
+                let variable = c == '?';
+                if variable {
+                    self.chars.next();
+                }
+                let name = self.take_while(|c| c == '_' || c.is_alphanumeric());
+                if name.is_empty() {
+                    return Err(self.error("expected a variable name after '?'".to_string()));
+                }
+                if !variable && self.eat('(') {
+                    return self.parse_call(name);
+                }
+                Ok(ComparableValue::Direct(FactValue::Variable(name.to_string())))
+            },
+            Some(c) => Err(self.error(format!("unexpected '{}'", c))),
+            None => Err(self.error("expected a variable or number".to_string())),
+        }
+    }
+
+    /// Parses the arguments of a call to `abs`, `min`, `max` or a registered function, up to the closing parenthesis.
+    fn parse_call(&mut self, function: &str) -> Result<ComparableValue, EngineError> {
+        let mut arguments = Vec::new();
+        if !self.eat(')') {
+            loop {
+                arguments.push(self.parse_sum()?);
+                if self.eat(')') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return Err(self.error("expected ',' or ')'".to_string()));
+                }
+            }
+        }
+
+        let expression = match function {
+            "abs" if arguments.len() == 1 => Arithmetic::Abs(Box::new(arguments.remove(0))),
+            "abs" => return Err(self.error(format!("abs expects 1 argument, found {}", arguments.len()))),
+            "min" | "max" if arguments.is_empty() => return Err(self.error(format!("{} expects at least 1 argument", function))),
+            "min" => Arithmetic::Min(arguments),
+            "max" => Arithmetic::Max(arguments),
+            _ => Arithmetic::Call { function: function.to_string(), arguments },
+        };
+        Ok(ComparableValue::Arithmetic(Box::new(expression)))
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.chars.peek().map_or(self.source.len(), |(index, _)| *index);
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+        let end = self.chars.peek().map_or(self.source.len(), |(index, _)| *index);
+        &self.source[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, bindings: &HashMap<String, Variable>) -> Result<String, EngineError> {
+        let mut engine = SymbolicReasoningEngine::new();
+        engine.register_function("half", |arguments| Ok(arguments[0] / 2.0));
+        super::render(&engine, template, bindings)
+    }
+
+    fn bindings(values: &[(&str, FactValue)]) -> HashMap<String, Variable> {
+        values.iter()
+            .map(|(name, value)| (name.to_string(), Variable::new(name, value.clone(), VariableState::Stable)))
+            .collect()
+    }
+
+    #[test]
+    fn renders_variables_and_computed_values() {
+        let bindings = bindings(&[
+            ("city", FactValue::Text("Oslo".into())),
+            ("t", FactValue::Integer(21)),
+            ("rate", FactValue::Float(1.5)),
+        ]);
+
+        assert_eq!(render("${city} is ${?t} degrees", &bindings), Ok("Oslo is 21 degrees".into()));
+        assert_eq!(render("${t * 9 / 5 + 32}", &bindings), Ok("69.8".into()));
+        assert_eq!(render("${(t - 1) * 2}", &bindings), Ok("40".into()));
+        assert_eq!(render("${t * rate}", &bindings), Ok("31.5".into()));
+        assert_eq!(render("${t % 4} ${-t} ${abs(1 - t)} ${max(t, 30) / 3} ${min(t, rate)}", &bindings), Ok("1 -21 20 10 1.5".into()));
+        assert_eq!(render("${half(t)} ${half(max)}", &bindings), Err(EngineError::UnboundVariable { name: "max".into() }));
+        assert_eq!(render("${half(t)}", &bindings), Ok("10.5".into()));
+        assert_eq!(render("\\${t} stays ${unknown}", &bindings), Ok("${t} stays ${unknown}".into()));
+    }
+
+    #[test]
+    fn substituted_values_keep_their_escapes() {
+        let bindings = bindings(&[("p", FactValue::Text("C:\\${HOME}".into()))]);
+        let rendered = render("${?p} and \\${p}", &bindings).unwrap();
+        assert_eq!(rendered, "C:\\${HOME} and ${p}");
+        assert!(!has_placeholders(&rendered[..rendered.find(" and").unwrap()]));
+    }
+
+    #[test]
+    fn reports_invalid_placeholders() {
+        let bindings = bindings(&[("t", FactValue::Integer(21)), ("city", FactValue::Text("Oslo".into()))]);
+
+        assert!(matches!(validate("${t +}"), Err(EngineError::InvalidTemplate { .. })));
+        assert!(matches!(validate("${min()}"), Err(EngineError::InvalidTemplate { message, .. }) if message.starts_with("min expects at least 1 argument")));
+        assert!(matches!(validate("${abs(t, 1)}"), Err(EngineError::InvalidTemplate { .. })));
+        assert_eq!(render("${t / 0}", &bindings), Err(EngineError::DivisionByZero { expression: "?t / 0".into() }));
+        assert_eq!(render("${t % (t - 21)}", &bindings), Err(EngineError::DivisionByZero { expression: "?t % (?t - 21)".into() }));
+        assert!(matches!(render("${city + 1}", &bindings), Err(EngineError::NonNumericComparison { .. })));
+        assert_eq!(render("${twice(t)}", &bindings), Err(EngineError::UnknownFunction { name: "twice".into() }));
+        assert_eq!(render("${missing + 1}", &bindings), Err(EngineError::UnboundVariable { name: "missing".into() }));
+    }
+
+    #[test]
+    fn reports_overflowing_integer_division() {
+        let bindings = bindings(&[("x", FactValue::Integer(i32::MIN))]);
+        let overflow = |template: &str| EngineError::InvalidTemplate { template: template.into(), message: "integer overflow".into() };
+
+        // Dividing `i32::MIN` by -1 overflows both the remainder `i32::MIN % -1` and the quotient
+        assert_eq!(render("${?x / -1}", &bindings), Err(overflow("${?x / -1}")));
+        assert_eq!(render("${x / -2}", &bindings), Ok("1073741824".into()));
+    }
+}
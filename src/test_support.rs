@@ -0,0 +1,8 @@
+//! Fixtures shared by the unit tests of several modules.
+
+use crate::{Fact, FactValue, LogicalOperator, Symbol, SymbolType};
+
+/// Returns a fact pattern requiring the boolean symbol `name` to be true.
+pub(crate) fn atom(name: &str) -> LogicalOperator {
+    LogicalOperator::AtomicFact(Fact::new(Symbol::new(name, SymbolType::Boolean), FactValue::Boolean(true)))
+}
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::Fact;
+use crate::working_memory::WorkingMemory;
+
+/// Records a reason for a fact to hold in the knowledge base.
+///
+/// A justification states that its `consequent` holds as long as every fact of its in-list, the `antecedents`, is in
+/// the knowledge base and no fact matching its out-list, the `exclusions`, is. Facts asserted through the engine's API
+/// are justified by a premise justification, with no rule and empty lists, while facts inferred by rules are justified
+/// by the rule at index `rule` and the facts its premise matched. Exclusions are the facts the premise required to be
+/// absent through `LogicalOperator::Not`, and may hold pattern variables that match any value.
+///
+/// A fact may have several justifications, one per match of a rule asserting it, and holds as long as any of them is
+/// valid.
+///
+/// # Examples
+/// ```
+/// use symbolic_reasoning_engine::{Fact, FactValue, LogicalOperator, SymbolType, SymbolicReasoningEngine};
+///
+/// let mut engine = SymbolicReasoningEngine::new();
+/// let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+/// let hiking = engine.define_symbol("Hiking", SymbolType::Boolean)?;
+///
+/// let sunny = Fact::new(weather.clone(), FactValue::Text("Sunny".into()));
+/// engine.assert_fact(weather, FactValue::Text("Sunny".into()))?;
+/// engine.define_rule(LogicalOperator::AtomicFact(sunny.clone()), Fact::new(hiking.clone(), FactValue::Boolean(true)))?;
+/// engine.forward_chaining_with_variables()?;
+///
+/// let justifications = engine.why(&Fact::new(hiking, FactValue::Boolean(true)));
+/// assert_eq!(justifications.len(), 1);
+/// assert_eq!(justifications[0].rule(), Some(0));
+/// assert_eq!(justifications[0].antecedents(), &[sunny.clone()]);
+/// assert!(engine.why(&sunny)[0].is_premise());
+/// # Ok::<(), symbolic_reasoning_engine::EngineError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Justification {
+    rule: Option<usize>,
+    antecedents: Vec<Fact>,
+    exclusions: Vec<Fact>,
+    consequent: Fact,
+}
+
+impl Justification {
+    /// Creates the justification of a fact asserted directly, which holds unconditionally.
+    pub(crate) fn premise(consequent: Fact) -> Self {
+        Self { rule: None, antecedents: Vec::new(), exclusions: Vec::new(), consequent }
+    }
+
+    /// Creates the justification of a fact inferred by a rule.
+    pub(crate) fn inferred(rule: usize, antecedents: Vec<Fact>, exclusions: Vec<Fact>, consequent: Fact) -> Self {
+        Self { rule: Some(rule), antecedents, exclusions, consequent }
+    }
+
+    /// Returns the index of the rule that inferred the consequent, or `None` if it was asserted directly.
+    pub fn rule(&self) -> Option<usize> {
+        self.rule
+    }
+
+    /// Checks whether the consequent was asserted directly rather than inferred.
+    pub fn is_premise(&self) -> bool {
+        self.rule.is_none()
+    }
+
+    /// Returns the facts that must be in the knowledge base for the justification to be valid.
+    pub fn antecedents(&self) -> &[Fact] {
+        &self.antecedents
+    }
+
+    /// Returns the fact patterns that no fact of the knowledge base may match for the justification to be valid.
+    pub fn exclusions(&self) -> &[Fact] {
+        &self.exclusions
+    }
+
+    /// Returns the fact the justification supports.
+    pub fn consequent(&self) -> &Fact {
+        &self.consequent
+    }
+
+    /// Checks whether the justification is valid, given whether a fact is in and whether an exclusion matches a fact
+    /// that is in.
+    fn is_valid(&self, is_in: impl Fn(&Fact) -> bool, is_excluded: impl Fn(&Fact) -> bool) -> bool {
+        self.antecedents.iter().all(is_in) && !self.exclusions.iter().any(is_excluded)
+    }
+}
+
+/// Represents whether a fact known to the truth maintenance system currently holds.
+///
+/// Variants:
+/// - `In`: The fact has a valid justification and is part of the knowledge base.
+/// - `Out`: The fact was asserted or inferred before, but none of its justifications is currently valid. It comes back
+///   in, without re-running the rules, as soon as one of them becomes valid again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Label {
+    In,
+    Out,
+}
+
+/// A justification-based truth maintenance system, tracking why every fact of the knowledge base holds.
+///
+/// Every fact ever asserted or inferred is a node, labelled in when one of its justifications is valid and out
+/// otherwise. Justifications are indexed by the fact they support, the facts they rely on and the symbols they
+/// exclude, so that when facts are asserted or retracted, only the labels of the facts depending on them, directly or
+/// not, are recomputed. Labels are recomputed from the facts whose label cannot change, so that facts only supported
+/// by each other, such as two facts inferred from one another, are labelled out once their premises go.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TruthMaintenance {
+    /// The justifications in the order they were recorded, with a hole in place of each forgotten one.
+    justifications: Vec<Option<Justification>>,
+    /// The number of justifications that were not forgotten.
+    len: usize,
+    by_consequent: HashMap<Fact, Vec<usize>>,
+    by_antecedent: HashMap<Fact, Vec<usize>>,
+    /// The justifications with an exclusion about each symbol, by symbol name.
+    by_exclusion: HashMap<String, Vec<usize>>,
+}
+
+impl TruthMaintenance {
+    /// Records a justification, unless an identical one is already known.
+    pub(crate) fn justify(&mut self, justification: Justification) {
+        if self.justifications_of(&justification.consequent).any(|known| *known == justification) {
+            return;
+        }
+
+        let index = self.justifications.len();
+        self.by_consequent.entry(justification.consequent.clone()).or_default().push(index);
+        for antecedent in &justification.antecedents {
+            Self::index(&mut self.by_antecedent, antecedent.clone(), index);
+        }
+        for exclusion in &justification.exclusions {
+            Self::index(&mut self.by_exclusion, exclusion.symbol.name.clone(), index);
+        }
+        self.justifications.push(Some(justification));
+        self.len += 1;
+    }
+
+    /// Forgets every justification of a fact, as done when the fact is retracted.
+    ///
+    /// Justifications of other facts relying on it are kept, so that they become valid again if it is re-asserted.
+    pub(crate) fn forget(&mut self, fact: &Fact) {
+        let Some(indexes) = self.by_consequent.remove(fact) else {
+            return;
+        };
+        for index in indexes {
+            let Some(justification) = self.justifications[index].take() else {
+                continue;
+            };
+            for antecedent in &justification.antecedents {
+                Self::unindex(&mut self.by_antecedent, antecedent, index);
+            }
+            for exclusion in &justification.exclusions {
+                Self::unindex(&mut self.by_exclusion, &exclusion.symbol.name, index);
+            }
+            self.len -= 1;
+        }
+
+        if self.justifications.len() > 2 * self.len + 16 {
+            self.compact();
+        }
+    }
+
+    /// Returns the justifications of a fact, whether or not they are currently valid, in the order they were recorded.
+    pub(crate) fn justifications_of(&self, fact: &Fact) -> impl Iterator<Item = &Justification> {
+        self.at(self.by_consequent.get(fact))
+    }
+
+    /// Checks whether a fact is a node of the system, that is whether it has any justification.
+    pub(crate) fn is_node(&self, fact: &Fact) -> bool {
+        self.by_consequent.contains_key(fact)
+    }
+
+    /// Checks whether asserting a fact may change the label of other nodes, because a justification relies on it or
+    /// excludes it.
+    pub(crate) fn has_dependents(&self, fact: &Fact, matches: impl Fn(&Fact, &Fact) -> bool) -> bool {
+        self.dependents(fact, &matches).next().is_some()
+    }
+
+    /// Checks whether a justification is valid given the facts currently in the knowledge base.
+    pub(crate) fn is_valid(&self, justification: &Justification, facts: &WorkingMemory, matches: impl Fn(&Fact, &Fact) -> bool) -> bool {
+        justification.is_valid(
+            |fact| facts.contains(fact),
+            |exclusion| facts.candidates(exclusion).any(|fact| matches(exclusion, fact)),
+        )
+    }
+
+    /// Recomputes the labels of the changed facts and of the nodes depending on them, directly or not.
+    ///
+    /// The other facts keep their label: in if they are in the knowledge base, and out otherwise. Labels are then
+    /// propagated from them: justifications without exclusions are applied until no new fact is labelled in, and only
+    /// then are justifications with exclusions checked against the facts labelled in so far, as for stratified
+    /// negation.
+    ///
+    /// # Arguments
+    /// * `facts` - The knowledge base, whose facts are labelled in before the change.
+    /// * `changed` - The facts asserted, retracted or justified since the knowledge base was last labelled.
+    /// * `matches` - Whether an exclusion pattern matches a fact.
+    ///
+    /// # Returns
+    /// * `(Vec<Fact>, Vec<Fact>)` - The facts of the knowledge base labelled out, and the facts labelled in that are
+    ///   not in it, in the order they were labelled in.
+    pub(crate) fn relabel(&self, facts: &WorkingMemory, changed: &[Fact], matches: impl Fn(&Fact, &Fact) -> bool) -> (Vec<Fact>, Vec<Fact>) {
+        let mut affected: HashSet<&Fact> = HashSet::new();
+        let mut pending: Vec<&Fact> = changed.iter().collect();
+        while let Some(fact) = pending.pop() {
+            if affected.insert(fact) {
+                pending.extend(self.dependents(fact, &matches).filter_map(|index| self.justifications[index].as_ref()).map(|justification| &justification.consequent));
+            }
+        }
+
+        let mut candidates: Vec<usize> = affected.iter().flat_map(|fact| self.by_consequent.get(*fact).into_iter().flatten().copied()).collect();
+        candidates.sort_unstable();
+        let monotonic = |index: &usize| self.justifications[*index].as_ref().is_some_and(|justification| justification.exclusions.is_empty());
+        let monotonic_candidates: HashSet<usize> = candidates.iter().copied().filter(monotonic).collect();
+
+        let mut labelling = Labelling { facts, affected, labelled_in: Vec::new(), is_in: HashSet::new(), by_symbol: HashMap::new(), matches };
+        loop {
+            let mut queue: VecDeque<usize> = candidates.iter().copied().filter(monotonic).collect();
+            while let Some(index) = queue.pop_front() {
+                let Some(justification) = self.justifications[index].as_ref() else {
+                    continue;
+                };
+                if labelling.is_in.contains(&justification.consequent) || !labelling.is_valid(justification) {
+                    continue;
+                }
+                labelling.label_in(&justification.consequent);
+                queue.extend(self.by_antecedent.get(&justification.consequent).into_iter().flatten().filter(|index| monotonic_candidates.contains(index)));
+            }
+
+            let newly_in: Vec<&Fact> = self.at(Some(&candidates))
+                .filter(|justification| !justification.exclusions.is_empty())
+                .filter(|justification| !labelling.is_in.contains(&justification.consequent) && labelling.is_valid(justification))
+                .map(|justification| &justification.consequent)
+                .collect();
+            if newly_in.is_empty() {
+                break;
+            }
+            for fact in newly_in {
+                labelling.label_in(fact);
+            }
+        }
+
+        let withdrawn = labelling.affected.iter()
+            .filter(|fact| facts.contains(fact) && !labelling.is_in.contains(*fact))
+            .map(|fact| (*fact).clone())
+            .collect();
+        let restored = labelling.labelled_in.iter().filter(|fact| !facts.contains(fact)).map(|fact| (*fact).clone()).collect();
+        (withdrawn, restored)
+    }
+
+    /// Returns the indexes of the justifications relying on a fact or excluding it.
+    fn dependents<'a>(&'a self, fact: &'a Fact, matches: &'a impl Fn(&Fact, &Fact) -> bool) -> impl Iterator<Item = usize> + 'a {
+        let relying = self.by_antecedent.get(fact).into_iter().flatten().copied();
+        let excluding = self.by_exclusion.get(&fact.symbol.name).into_iter().flatten().copied().filter(move |index| {
+            self.justifications[*index].as_ref().is_some_and(|justification| justification.exclusions.iter().any(|exclusion| matches(exclusion, fact)))
+        });
+        relying.chain(excluding)
+    }
+
+    fn at<'a>(&'a self, indexes: Option<&'a Vec<usize>>) -> impl Iterator<Item = &'a Justification> {
+        indexes.into_iter().flatten().filter_map(|index| self.justifications[*index].as_ref())
+    }
+
+    fn index<K: Hash + Eq>(index: &mut HashMap<K, Vec<usize>>, key: K, position: usize) {
+        let positions = index.entry(key).or_default();
+        // A justification may rely on a fact, or exclude a symbol, more than once
+        if positions.last() != Some(&position) {
+            positions.push(position);
+        }
+    }
+
+    fn unindex<K: Hash + Eq>(index: &mut HashMap<K, Vec<usize>>, key: &K, position: usize) {
+        if let Some(positions) = index.get_mut(key) {
+            positions.retain(|known| *known != position);
+            if positions.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+
+    /// Rebuilds the indexes without the holes left by forgotten justifications.
+    fn compact(&mut self) {
+        let justifications: Vec<Justification> = std::mem::take(&mut self.justifications).into_iter().flatten().collect();
+        *self = Self::default();
+        for justification in justifications {
+            self.justify(justification);
+        }
+    }
+}
+
+/// The labels being recomputed by `TruthMaintenance::relabel`.
+///
+/// Facts outside `affected` are in exactly when they are in the knowledge base, while the affected facts are in once
+/// they are labelled in.
+struct Labelling<'a, M> {
+    facts: &'a WorkingMemory,
+    affected: HashSet<&'a Fact>,
+    /// The affected facts labelled in, in the order they were labelled in.
+    labelled_in: Vec<&'a Fact>,
+    is_in: HashSet<&'a Fact>,
+    /// The affected facts labelled in, by symbol name, to match exclusions against.
+    by_symbol: HashMap<&'a str, Vec<&'a Fact>>,
+    matches: M,
+}
+
+impl<'a, M: Fn(&Fact, &Fact) -> bool> Labelling<'a, M> {
+    fn label_in(&mut self, fact: &'a Fact) {
+        if self.is_in.insert(fact) {
+            self.labelled_in.push(fact);
+            self.by_symbol.entry(fact.symbol.name.as_str()).or_default().push(fact);
+        }
+    }
+
+    fn holds(&self, fact: &Fact) -> bool {
+        match self.affected.contains(fact) {
+            true => self.is_in.contains(fact),
+            false => self.facts.contains(fact),
+        }
+    }
+
+    fn is_excluded(&self, exclusion: &Fact) -> bool {
+        self.facts.candidates(exclusion).any(|fact| !self.affected.contains(fact) && (self.matches)(exclusion, fact))
+            || self.by_symbol.get(exclusion.symbol.name.as_str()).into_iter().flatten().any(|fact| (self.matches)(exclusion, fact))
+    }
+
+    fn is_valid(&self, justification: &Justification) -> bool {
+        justification.is_valid(|fact| self.holds(fact), |exclusion| self.is_excluded(exclusion))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactValue, Symbol, SymbolType};
+
+    fn fact(name: &str) -> Fact {
+        Fact::new(Symbol::new(name, SymbolType::Boolean), FactValue::Boolean(true))
+    }
+
+    /// Relabels the changed facts, updating the knowledge base as the engine does and returning the withdrawn facts.
+    fn relabel(tms: &TruthMaintenance, facts: &mut WorkingMemory, changed: &[Fact]) -> Vec<Fact> {
+        let (withdrawn, restored) = tms.relabel(facts, changed, |pattern, known| pattern == known);
+        let withdrawn = facts.remove_all(&withdrawn);
+        for fact in restored {
+            facts.push(fact);
+        }
+        withdrawn
+    }
+
+    fn memory(facts: &[&str]) -> WorkingMemory {
+        let mut memory = WorkingMemory::default();
+        for name in facts {
+            memory.push(fact(name));
+        }
+        memory
+    }
+
+    #[test]
+    fn labels_out_facts_without_well_founded_support() {
+        let mut tms = TruthMaintenance::default();
+        let mut facts = memory(&["A", "B", "C", "D"]);
+        tms.justify(Justification::premise(fact("A")));
+        tms.justify(Justification::inferred(0, vec![fact("A")], Vec::new(), fact("B")));
+        tms.justify(Justification::inferred(1, vec![fact("D")], Vec::new(), fact("C")));
+        tms.justify(Justification::inferred(2, vec![fact("C")], Vec::new(), fact("D")));
+
+        // C and D only support each other
+        assert_eq!(relabel(&tms, &mut facts, &[fact("C")]), vec![fact("C"), fact("D")]);
+        assert_eq!(facts.iter().collect::<Vec<_>>(), vec![&fact("A"), &fact("B")]);
+
+        // B goes out with A, and comes back in with it
+        tms.forget(&fact("A"));
+        assert_eq!(relabel(&tms, &mut facts, &[fact("A")]), vec![fact("A"), fact("B")]);
+        assert!(tms.is_node(&fact("B")));
+        tms.justify(Justification::premise(fact("A")));
+        assert!(relabel(&tms, &mut facts, &[fact("A")]).is_empty());
+        assert_eq!(facts.iter().collect::<Vec<_>>(), vec![&fact("A"), &fact("B")]);
+    }
+
+    #[test]
+    fn exclusions_label_out_their_consequent() {
+        let mut tms = TruthMaintenance::default();
+        let mut facts = memory(&["A", "B"]);
+        tms.justify(Justification::premise(fact("A")));
+        tms.justify(Justification::inferred(0, vec![fact("A")], vec![fact("C")], fact("B")));
+        assert!(relabel(&tms, &mut facts, &[fact("A"), fact("B")]).is_empty());
+        assert!(tms.has_dependents(&fact("C"), |pattern, known| pattern == known));
+
+        tms.justify(Justification::premise(fact("C")));
+        facts.push(fact("C"));
+        assert_eq!(relabel(&tms, &mut facts, &[fact("C")]), vec![fact("B")]);
+        assert_eq!(facts.iter().collect::<Vec<_>>(), vec![&fact("A"), &fact("C")]);
+    }
+
+    #[test]
+    fn forgotten_justifications_are_compacted_away() {
+        let mut tms = TruthMaintenance::default();
+        for round in 0..100 {
+            tms.justify(Justification::inferred(round, vec![fact("A")], vec![fact("C")], fact("B")));
+            tms.forget(&fact("B"));
+        }
+        assert!(tms.justifications.len() <= 16);
+        assert!(!tms.has_dependents(&fact("A"), |pattern, known| pattern == known));
+        assert!(tms.by_exclusion.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::{Fact, FactValue};
+
+/// A float with a total order, so that `FactValue::Float` values can be compared for equality reflexively and hashed.
+///
+/// Zeros and NaNs are canonicalized on construction: `-0.0` equals `0.0`, as it does for `f64`, and every NaN equals
+/// every other NaN, unlike `f64`. Other values are ordered as by `f64::total_cmp`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TotalFloat(f64);
+
+impl TotalFloat {
+    pub(crate) fn new(value: f64) -> Self {
+        if value == 0.0 {
+            Self(0.0)
+        } else if value.is_nan() {
+            Self(f64::NAN)
+        } else {
+            Self(value)
+        }
+    }
+}
+
+impl PartialEq for TotalFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalFloat {}
+
+impl PartialOrd for TotalFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for TotalFloat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// The knowledge base's facts, indexed by symbol and by value.
+///
+/// Facts are kept in the order they were added, with at most one copy of each, and can be looked up by symbol name,
+/// or by symbol name and value for equality lookups, without scanning the other facts. Removed facts leave a hole in
+/// the insertion order, which is compacted once holes outnumber the facts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WorkingMemory {
+    slots: Vec<Option<Fact>>,
+    positions: HashMap<Fact, usize>,
+    by_symbol: HashMap<String, Vec<usize>>,
+    by_value: HashMap<(String, FactValue), Vec<usize>>,
+}
+
+impl WorkingMemory {
+    /// Returns the facts in the order they were added.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Fact> {
+        self.slots.iter().flatten()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub(crate) fn contains(&self, fact: &Fact) -> bool {
+        self.positions.contains_key(fact)
+    }
+
+    /// Returns the facts about a symbol, in the order they were added.
+    pub(crate) fn with_symbol(&self, name: &str) -> impl Iterator<Item = &Fact> {
+        self.facts_at(self.by_symbol.get(name))
+    }
+
+    /// Returns the facts that may unify with a pattern, in the order they were added: the facts with the pattern's
+    /// symbol and value if the value is ground, or with its symbol otherwise.
+    pub(crate) fn candidates(&self, pattern: &Fact) -> impl Iterator<Item = &Fact> {
+        let positions = match pattern.value {
+            FactValue::Variable(_) => self.by_symbol.get(&pattern.symbol.name),
+            _ => self.by_value.get(&(pattern.symbol.name.clone(), pattern.value.clone())),
+        };
+        self.facts_at(positions)
+    }
+
+    /// Adds a fact after the others, returning whether it was not already known.
+    pub(crate) fn push(&mut self, fact: Fact) -> bool {
+        if self.positions.contains_key(&fact) {
+            return false;
+        }
+
+        let position = self.slots.len();
+        self.by_symbol.entry(fact.symbol.name.clone()).or_default().push(position);
+        self.by_value.entry((fact.symbol.name.clone(), fact.value.clone())).or_default().push(position);
+        self.positions.insert(fact.clone(), position);
+        self.slots.push(Some(fact));
+        true
+    }
+
+    /// Removes the given facts, returning the ones that were known in the order they were added.
+    pub(crate) fn remove_all(&mut self, facts: &[Fact]) -> Vec<Fact> {
+        let mut positions: Vec<usize> = facts.iter().filter_map(|fact| self.positions.remove(fact)).collect();
+        positions.sort_unstable();
+        positions.dedup();
+
+        let mut removed = Vec::new();
+        for position in positions {
+            let Some(fact) = self.slots[position].take() else {
+                continue;
+            };
+            Self::unindex(&mut self.by_symbol, &fact.symbol.name, position);
+            Self::unindex(&mut self.by_value, &(fact.symbol.name.clone(), fact.value.clone()), position);
+            removed.push(fact);
+        }
+
+        if self.slots.len() > 2 * self.positions.len() + 16 {
+            self.compact();
+        }
+        removed
+    }
+
+    fn facts_at<'a>(&'a self, positions: Option<&'a Vec<usize>>) -> impl Iterator<Item = &'a Fact> {
+        positions.into_iter().flatten().filter_map(|position| self.slots[*position].as_ref())
+    }
+
+    fn unindex<K: Hash + Eq>(index: &mut HashMap<K, Vec<usize>>, key: &K, position: usize) {
+        if let Some(positions) = index.get_mut(key) {
+            if let Ok(found) = positions.binary_search(&position) {
+                positions.remove(found);
+            }
+            if positions.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+
+    /// Rebuilds the indexes without the holes left by removed facts.
+    fn compact(&mut self) {
+        let facts: Vec<Fact> = std::mem::take(&mut self.slots).into_iter().flatten().collect();
+        *self = Self::default();
+        for fact in facts {
+            self.push(fact);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbol, SymbolType};
+
+    #[test]
+    fn total_floats_are_reflexive_and_hash_consistently() {
+        let hash = |value: TotalFloat| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(TotalFloat::new(f64::NAN), TotalFloat::new(-f64::NAN));
+        assert_eq!(TotalFloat::new(-0.0), TotalFloat::new(0.0));
+        assert_eq!(hash(TotalFloat::new(-0.0)), hash(TotalFloat::new(0.0)));
+        assert!(TotalFloat::new(1.5) < TotalFloat::new(2.0));
+        assert!(TotalFloat::new(f64::NEG_INFINITY) < TotalFloat::new(-1e300));
+    }
+
+    #[test]
+    fn lookups_follow_insertion_order_across_removals() {
+        let mut memory = WorkingMemory::default();
+        let reading = Symbol::predicate("Reading", vec![SymbolType::Text], SymbolType::Float);
+        let fact = |sensor: &str, value: f64| Fact::with_arguments(reading.clone(), vec![FactValue::Text(sensor.into())], FactValue::Float(value));
+
+        for (index, value) in [1.0, 2.0, 1.0, 3.0].into_iter().enumerate() {
+            assert!(memory.push(fact(&format!("s{}", index), value)));
+        }
+        assert!(!memory.push(fact("s0", 1.0)));
+        assert_eq!(memory.len(), 4);
+
+        let pattern = Fact::with_arguments(reading.clone(), vec![FactValue::Variable("sensor".into())], FactValue::Float(1.0));
+        assert_eq!(memory.candidates(&pattern).collect::<Vec<_>>(), vec![&fact("s0", 1.0), &fact("s2", 1.0)]);
+
+        assert_eq!(memory.remove_all(&[fact("s2", 1.0), fact("s1", 2.0), fact("s9", 9.0)]), vec![fact("s1", 2.0), fact("s2", 1.0)]);
+        assert_eq!(memory.candidates(&pattern).collect::<Vec<_>>(), vec![&fact("s0", 1.0)]);
+        assert_eq!(memory.with_symbol("Reading").collect::<Vec<_>>(), vec![&fact("s0", 1.0), &fact("s3", 3.0)]);
+        assert!(!memory.contains(&fact("s1", 2.0)));
+    }
+}
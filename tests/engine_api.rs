@@ -0,0 +1,856 @@
+use std::collections::HashMap;
+
+use symbolic_reasoning_engine::{
+    Action, Arithmetic, ComparableValue, ConflictStrategy, EngineError, Fact, Failure, FactValue, Label, LogicalOperator, Proof, Program, Rule, Symbol, SymbolType, SymbolicReasoningEngine, Variable, VariableState,
+};
+
+/// Returns a pattern variable term.
+fn var(name: &str) -> FactValue {
+    FactValue::Variable(name.into())
+}
+
+/// Returns a fact pattern requiring the predicate to hold for the given terms.
+fn atom(symbol: &Symbol, terms: Vec<FactValue>) -> LogicalOperator {
+    LogicalOperator::AtomicFact(Fact::with_arguments(symbol.clone(), terms, FactValue::Boolean(true)))
+}
+
+#[test]
+fn symbol_and_fact_accessors() {
+    let symbol = Symbol::new("Temperature", SymbolType::Integer);
+    assert_eq!(symbol.name(), "Temperature");
+    assert_eq!(symbol.symbol_type(), &SymbolType::Integer);
+
+    let fact = Fact::new(symbol.clone(), FactValue::Integer(21));
+    assert_eq!(fact.symbol(), &symbol);
+    assert_eq!(fact.value(), &FactValue::Integer(21));
+}
+
+#[test]
+fn engine_exposes_defined_symbols_and_variables() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+
+    assert_eq!(engine.symbol("Weather"), Some(&weather));
+    assert!(engine.symbol("Humidity").is_none());
+    assert_eq!(engine.symbols().count(), 1);
+
+    engine.assert_variable(&Variable::new("temp", FactValue::Integer(30), VariableState::Stable));
+    let variable = engine.variable("temp").expect("variable should be bound");
+    assert_eq!(variable.name(), "temp");
+    assert_eq!(variable.value(), &FactValue::Integer(30));
+    assert_eq!(variable.state(), &VariableState::Stable);
+    Ok(())
+}
+
+#[test]
+fn forward_chaining_through_public_api() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+
+    let weather = engine.define_symbol("Weather", SymbolType::Text)?;
+    let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+    let activity = engine.define_symbol("Activity", SymbolType::Text)?;
+
+    engine.assert_fact(weather.clone(), FactValue::Text("Sunny".into()))?;
+    engine.assert_fact(temperature.clone(), FactValue::Integer(24))?;
+
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            LogicalOperator::AtomicFact(Fact::new(weather, FactValue::Text("Sunny".into()))),
+            LogicalOperator::GreaterThanOrEqualTo(
+                Box::new(ComparableValue::Symbol(temperature)),
+                Box::new(ComparableValue::Direct(FactValue::Integer(20))),
+            ),
+        ]),
+        Fact::new(activity.clone(), FactValue::Text("Hiking".into())),
+    )?;
+
+    let rule: &Rule = &engine.rules()[0];
+    assert_eq!(rule.conclusions().next().map(Fact::symbol), Some(&activity));
+    assert!(engine.is_premise_true(rule.premise())?);
+
+    engine.forward_chaining_with_variables()?;
+
+    assert!(engine.contains_fact(&Fact::new(activity, FactValue::Text("Hiking".into()))));
+    assert_eq!(engine.facts().count(), 3);
+    Ok(())
+}
+
+#[test]
+fn backward_chaining_through_public_api() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::default();
+
+    let a = engine.define_symbol("A", SymbolType::Boolean)?;
+    let b = engine.define_symbol("B", SymbolType::Boolean)?;
+    let c = engine.define_symbol("C", SymbolType::Boolean)?;
+
+    engine.assert_fact(c.clone(), FactValue::Boolean(true))?;
+    engine.define_rule(
+        LogicalOperator::AtomicFact(Fact::new(c, FactValue::Boolean(true))),
+        Fact::new(b.clone(), FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(
+        LogicalOperator::AtomicFact(Fact::new(b, FactValue::Boolean(true))),
+        Fact::new(a.clone(), FactValue::Boolean(true)),
+    )?;
+
+    assert!(engine.specify_goal(&Fact::new(a.clone(), FactValue::Boolean(true)))?);
+    assert!(!engine.specify_goal(&Fact::new(a.clone(), FactValue::Boolean(false)))?);
+
+    // Backward chaining answers the query without extending the knowledge base.
+    assert!(!engine.contains_fact(&Fact::new(a, FactValue::Boolean(true))));
+    Ok(())
+}
+
+#[test]
+fn errors_are_reported_instead_of_panicking() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let temperature = engine.define_symbol("Temperature", SymbolType::Integer)?;
+    let advice = engine.define_symbol("Advice", SymbolType::Text)?;
+
+    assert_eq!(
+        engine.define_symbol("Temperature", SymbolType::Integer),
+        Err(EngineError::DuplicateSymbol { symbol: "Temperature".into() })
+    );
+
+    engine.define_rule(
+        LogicalOperator::LessThan(
+            Box::new(ComparableValue::Symbol(temperature)),
+            Box::new(ComparableValue::Direct(FactValue::Integer(5))),
+        ),
+        Fact::new(advice, FactValue::Text("Coat".into())),
+    )?;
+
+    let error = engine.forward_chaining().unwrap_err();
+    assert_eq!(error.to_string(), "error evaluating rule 0: no fact is known for symbol 'Temperature'");
+    Ok(())
+}
+
+#[test]
+fn symbol_schemas_are_enforced() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let humidity = engine.define_symbol("Humidity", SymbolType::IntegerRange { min: 0, max: 100 })?;
+    let level = engine.define_symbol("Level", SymbolType::Enum(vec!["Low".into(), "High".into()]))?;
+
+    assert!(matches!(
+        engine.assert_fact(humidity.clone(), FactValue::Integer(120)),
+        Err(EngineError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        engine.assert_fact(level.clone(), FactValue::Text("Medium".into())),
+        Err(EngineError::TypeMismatch { .. })
+    ));
+
+    engine.assert_fact(humidity.clone(), FactValue::Integer(80))?;
+    engine.define_rule(
+        LogicalOperator::GreaterThan(
+            Box::new(ComparableValue::Symbol(humidity.clone())),
+            Box::new(ComparableValue::Direct(FactValue::Integer(70))),
+        ),
+        Fact::new(level.clone(), FactValue::Text("High".into())),
+    )?;
+    assert_eq!(
+        engine.define_rule(
+            LogicalOperator::LessThan(
+                Box::new(ComparableValue::Symbol(level.clone())),
+                Box::new(ComparableValue::Symbol(humidity)),
+            ),
+            Fact::new(level.clone(), FactValue::Text("Low".into())),
+        ),
+        Err(EngineError::IncompatibleComparison {
+            operator: "<".into(),
+            left: SymbolType::Enum(vec!["Low".into(), "High".into()]),
+            right: SymbolType::IntegerRange { min: 0, max: 100 },
+        })
+    );
+
+    engine.forward_chaining()?;
+    assert!(engine.contains_fact(&Fact::new(level, FactValue::Text("High".into()))));
+    Ok(())
+}
+
+#[test]
+fn predicate_facts_through_public_api() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let grandparent = engine.define_predicate("Grandparent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let people = |a: &str, b: &str| vec![FactValue::Text(a.into()), FactValue::Text(b.into())];
+
+    assert_eq!(parent.arity(), 2);
+    assert_eq!(parent.signature(), &[SymbolType::Text, SymbolType::Text]);
+
+    engine.assert_predicate(parent.clone(), people("alice", "bob"), FactValue::Boolean(true))?;
+    engine.assert_predicate(parent.clone(), people("bob", "carol"), FactValue::Boolean(true))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            LogicalOperator::AtomicFact(Fact::with_arguments(parent.clone(), people("alice", "bob"), FactValue::Boolean(true))),
+            LogicalOperator::AtomicFact(Fact::with_arguments(parent, people("bob", "carol"), FactValue::Boolean(true))),
+        ]),
+        Fact::with_arguments(grandparent.clone(), people("alice", "carol"), FactValue::Boolean(true)),
+    )?;
+
+    let goal = Fact::with_arguments(grandparent.clone(), people("alice", "carol"), FactValue::Boolean(true));
+    assert_eq!(goal.arguments(), people("alice", "carol").as_slice());
+    assert!(engine.specify_goal(&goal)?);
+    assert!(!engine.specify_goal(&Fact::with_arguments(grandparent, people("carol", "alice"), FactValue::Boolean(true)))?);
+
+    engine.forward_chaining()?;
+    assert!(engine.contains_fact(&goal));
+    Ok(())
+}
+
+#[test]
+fn one_rule_fires_for_every_matching_binding() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let sunny = engine.define_predicate("Sunny", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let hiking = engine.define_predicate("GoodForHiking", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let city = |name: &str| vec![FactValue::Text(name.into())];
+    let any_city = || vec![FactValue::Variable("city".into())];
+
+    for name in ["Oslo", "Lyon", "Porto"] {
+        engine.assert_predicate(sunny.clone(), city(name), FactValue::Boolean(true))?;
+    }
+    engine.define_rule(
+        LogicalOperator::AtomicFact(Fact::with_arguments(sunny, any_city(), FactValue::Boolean(true))),
+        Fact::with_arguments(hiking.clone(), any_city(), FactValue::Boolean(true)),
+    )?;
+
+    assert!(engine.specify_goal(&Fact::with_arguments(hiking.clone(), city("Lyon"), FactValue::Boolean(true)))?);
+    assert!(!engine.specify_goal(&Fact::with_arguments(hiking.clone(), city("Bergen"), FactValue::Boolean(true)))?);
+
+    engine.forward_chaining()?;
+    for name in ["Oslo", "Lyon", "Porto"] {
+        assert!(engine.contains_fact(&Fact::with_arguments(hiking.clone(), city(name), FactValue::Boolean(true))));
+    }
+    assert!(!Fact::with_arguments(hiking, any_city(), FactValue::Boolean(true)).is_ground());
+    Ok(())
+}
+
+#[test]
+fn rules_execute_several_actions() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let door = engine.define_symbol("Door", SymbolType::Text)?;
+    let light = engine.define_symbol("Light", SymbolType::Boolean)?;
+    let heating = engine.define_symbol("Heating", SymbolType::Boolean)?;
+    let log = engine.define_symbol("Log", SymbolType::Text)?;
+
+    engine.assert_fact(door.clone(), FactValue::Text("Open".into()))?;
+    engine.assert_fact(heating.clone(), FactValue::Boolean(true))?;
+    engine.define_rule_with_actions(
+        LogicalOperator::AtomicFact(Fact::new(door, FactValue::Text("Open".into()))),
+        vec![
+            Action::Assert(Fact::new(light.clone(), FactValue::Boolean(true))),
+            Action::Assert(Fact::new(log.clone(), FactValue::Text("door opened".into()))),
+            Action::Modify(Fact::new(heating.clone(), FactValue::Boolean(false))),
+        ],
+    )?;
+
+    let rule: &Rule = &engine.rules()[0];
+    assert_eq!(rule.actions().len(), 3);
+    assert!(engine.specify_goal(&Fact::new(log.clone(), FactValue::Text("door opened".into())))?);
+
+    engine.forward_chaining_with_variables()?;
+    assert!(engine.contains_fact(&Fact::new(light, FactValue::Boolean(true))));
+    assert!(engine.contains_fact(&Fact::new(log, FactValue::Text("door opened".into()))));
+    assert!(engine.contains_fact(&Fact::new(heating, FactValue::Boolean(false))));
+    assert_eq!(engine.facts().count(), 4);
+    Ok(())
+}
+
+#[test]
+fn modifying_a_fact_withdraws_stale_conclusions() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let temperature = engine.define_predicate("Temperature", vec![SymbolType::Text], SymbolType::Integer)?;
+    let heating = engine.define_predicate("Heating", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let room = |name: &str| vec![FactValue::Text(name.into())];
+    let any_room = || vec![FactValue::Variable("room".into())];
+
+    engine.assert_predicate(temperature.clone(), room("kitchen"), FactValue::Integer(15))?;
+    engine.assert_predicate(temperature.clone(), room("office"), FactValue::Integer(16))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            LogicalOperator::AtomicFact(Fact::with_arguments(temperature.clone(), any_room(), FactValue::Variable("t".into()))),
+            LogicalOperator::LessThan(
+                Box::new(ComparableValue::Direct(FactValue::Variable("t".into()))),
+                Box::new(ComparableValue::Direct(FactValue::Integer(18))),
+            ),
+        ]),
+        Fact::with_arguments(heating.clone(), any_room(), FactValue::Boolean(true)),
+    )?;
+    engine.forward_chaining_with_variables()?;
+    assert_eq!(engine.facts().count(), 4);
+
+    let removed = engine.modify_fact(Fact::with_arguments(temperature.clone(), room("kitchen"), FactValue::Integer(21)))?;
+    assert_eq!(removed, vec![
+        Fact::with_arguments(temperature.clone(), room("kitchen"), FactValue::Integer(15)),
+        Fact::with_arguments(heating.clone(), room("kitchen"), FactValue::Boolean(true)),
+    ]);
+    assert!(engine.contains_fact(&Fact::with_arguments(temperature, room("kitchen"), FactValue::Integer(21))));
+    assert!(engine.contains_fact(&Fact::with_arguments(heating, room("office"), FactValue::Boolean(true))));
+    assert_eq!(engine.facts().count(), 3);
+    Ok(())
+}
+
+#[test]
+fn why_explains_inferred_facts() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let ancestor = engine.define_predicate("Ancestor", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let pair = |a: &str, b: &str| vec![FactValue::Text(a.into()), FactValue::Text(b.into())];
+
+    engine.assert_predicate(parent.clone(), pair("alice", "bob"), FactValue::Boolean(true))?;
+    engine.assert_predicate(parent.clone(), pair("bob", "carol"), FactValue::Boolean(true))?;
+    engine.define_rule(
+        atom(&parent, vec![var("x"), var("y")]),
+        Fact::with_arguments(ancestor.clone(), vec![var("x"), var("y")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            atom(&parent, vec![var("x"), var("y")]),
+            atom(&ancestor, vec![var("y"), var("z")]),
+        ]),
+        Fact::with_arguments(ancestor.clone(), vec![var("x"), var("z")], FactValue::Boolean(true)),
+    )?;
+    engine.forward_chaining_with_variables()?;
+
+    let grandparent = Fact::with_arguments(ancestor.clone(), pair("alice", "carol"), FactValue::Boolean(true));
+    let justifications = engine.why(&grandparent);
+    assert_eq!(justifications.len(), 1);
+    assert_eq!(justifications[0].rule(), Some(1));
+    assert_eq!(justifications[0].consequent(), &grandparent);
+    assert_eq!(justifications[0].antecedents(), &[
+        Fact::with_arguments(parent.clone(), pair("alice", "bob"), FactValue::Boolean(true)),
+        Fact::with_arguments(ancestor, pair("bob", "carol"), FactValue::Boolean(true)),
+    ]);
+
+    let bob_carol = Fact::with_arguments(parent, pair("bob", "carol"), FactValue::Boolean(true));
+    engine.retract_fact(&bob_carol)?;
+    assert_eq!(engine.label(&grandparent), Some(Label::Out));
+    engine.assert_predicate(bob_carol.symbol().clone(), bob_carol.arguments().to_vec(), bob_carol.value().clone())?;
+    assert_eq!(engine.label(&grandparent), Some(Label::In));
+    Ok(())
+}
+
+#[test]
+fn proof_trees_show_why_a_goal_holds() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let grandparent = engine.define_predicate("Grandparent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let pair = |a: &str, b: &str| vec![FactValue::Text(a.into()), FactValue::Text(b.into())];
+
+    engine.assert_predicate(parent.clone(), pair("alice", "bob"), FactValue::Boolean(true))?;
+    engine.assert_predicate(parent.clone(), pair("bob", "carol"), FactValue::Boolean(true))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            atom(&parent, vec![var("x"), var("y")]),
+            atom(&parent, vec![var("y"), var("z")]),
+        ]),
+        Fact::with_arguments(grandparent.clone(), vec![var("x"), var("z")], FactValue::Boolean(true)),
+    )?;
+
+    let goal = Fact::with_arguments(grandparent.clone(), pair("alice", "carol"), FactValue::Boolean(true));
+    let Some(Proof::Rule { goal: proven, rule, premises }) = engine.prove_goal(&goal)? else {
+        panic!("the goal should be proven by a rule");
+    };
+    assert_eq!((proven, rule), (goal, 0));
+    assert_eq!(premises, vec![
+        Proof::Fact(Fact::with_arguments(parent.clone(), pair("alice", "bob"), FactValue::Boolean(true))),
+        Proof::Fact(Fact::with_arguments(parent, pair("bob", "carol"), FactValue::Boolean(true))),
+    ]);
+
+    assert_eq!(engine.prove_goal(&Fact::with_arguments(grandparent, pair("bob", "alice"), FactValue::Boolean(true)))?, None);
+    Ok(())
+}
+
+#[test]
+fn failure_reports_explain_why_a_goal_fails() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let stock = engine.define_predicate("Stock", vec![SymbolType::Text], SymbolType::Integer)?;
+    let orderable = engine.define_predicate("Orderable", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let discontinued = engine.define_predicate("Discontinued", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let item = |name: &str| vec![FactValue::Text(name.into())];
+
+    engine.assert_predicate(stock.clone(), item("widget"), FactValue::Integer(0))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            LogicalOperator::AtomicFact(Fact::with_arguments(stock, vec![var("item")], var("count"))),
+            LogicalOperator::GreaterThan(
+                Box::new(ComparableValue::Direct(var("count"))),
+                Box::new(ComparableValue::Direct(FactValue::Integer(0))),
+            ),
+        ]),
+        Fact::with_arguments(orderable.clone(), vec![var("item")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(
+        LogicalOperator::AtomicFact(Fact::with_arguments(discontinued.clone(), vec![var("item")], FactValue::Boolean(false))),
+        Fact::with_arguments(orderable.clone(), vec![var("item")], FactValue::Boolean(true)),
+    )?;
+
+    let goal = Fact::with_arguments(orderable.clone(), item("widget"), FactValue::Boolean(true));
+    assert!(!engine.specify_goal(&goal)?);
+    let Some(failure @ Failure::Goal { .. }) = engine.explain_goal(&goal)? else {
+        panic!("the goal should fail after trying the rules");
+    };
+    assert_eq!(failure.attempted_rules(), vec![0, 1]);
+    assert_eq!(failure.missing_facts(), vec![&Fact::with_arguments(discontinued, item("widget"), FactValue::Boolean(false))]);
+    let Failure::Goal { attempts, .. } = &failure else { unreachable!() };
+    assert_eq!(attempts[0].failure(), &Failure::Comparison { operator: ">".into(), left: 0.0, right: 0.0 });
+
+    // Goals no rule concludes are reported as missing
+    let goal = Fact::with_arguments(orderable, item("widget"), FactValue::Boolean(false));
+    assert_eq!(engine.explain_goal(&goal)?, Some(Failure::MissingFact(goal)));
+    Ok(())
+}
+
+#[test]
+fn queries_enumerate_derivable_values() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let weather = engine.define_predicate("Weather", vec![SymbolType::Text], SymbolType::Text)?;
+    let activity = engine.define_predicate("Activity", vec![SymbolType::Text], SymbolType::Text)?;
+    let city = |name: &str| vec![FactValue::Text(name.into())];
+
+    engine.assert_predicate(weather.clone(), city("Oslo"), FactValue::Text("Sunny".into()))?;
+    engine.assert_predicate(weather.clone(), city("Bergen"), FactValue::Text("Rainy".into()))?;
+    engine.assert_predicate(activity.clone(), city("Bergen"), FactValue::Text("Museum".into()))?;
+    engine.define_rule(
+        LogicalOperator::AtomicFact(Fact::with_arguments(weather, vec![var("city")], FactValue::Text("Sunny".into()))),
+        Fact::with_arguments(activity.clone(), vec![var("city")], FactValue::Text("Hiking".into())),
+    )?;
+
+    let solutions = engine.query(&Fact::with_arguments(activity.clone(), vec![var("where")], var("what")))?.collect::<Result<Vec<_>, _>>()?;
+    let solution = |place: &str, what: &str| HashMap::from([
+        ("where".to_string(), FactValue::Text(place.into())),
+        ("what".to_string(), FactValue::Text(what.into())),
+    ]);
+    assert_eq!(solutions, vec![solution("Bergen", "Museum"), solution("Oslo", "Hiking")]);
+
+    let unknown = Symbol::new("Unknown", SymbolType::Text);
+    assert!(matches!(engine.query(&Fact::new(unknown, var("x"))), Err(EngineError::UnknownSymbol { .. })));
+    Ok(())
+}
+
+#[test]
+fn forward_chaining_picks_up_facts_asserted_between_runs() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let edge = engine.define_predicate("Edge", vec![SymbolType::Integer, SymbolType::Integer], SymbolType::Boolean)?;
+    let path = engine.define_predicate("Path", vec![SymbolType::Integer, SymbolType::Integer], SymbolType::Boolean)?;
+    let pair = |from: i32, to: i32| vec![FactValue::Integer(from), FactValue::Integer(to)];
+    let connected = |engine: &SymbolicReasoningEngine, from: i32, to: i32| {
+        engine.contains_fact(&Fact::with_arguments(path.clone(), pair(from, to), FactValue::Boolean(true)))
+    };
+
+    engine.define_rule(
+        atom(&edge, vec![var("a"), var("b")]),
+        Fact::with_arguments(path.clone(), vec![var("a"), var("b")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(
+        LogicalOperator::And(vec![
+            atom(&path, vec![var("a"), var("b")]),
+            atom(&edge, vec![var("b"), var("c")]),
+        ]),
+        Fact::with_arguments(path.clone(), vec![var("a"), var("c")], FactValue::Boolean(true)),
+    )?;
+
+    for node in 0..10 {
+        engine.assert_predicate(edge.clone(), pair(node, node + 1), FactValue::Boolean(true))?;
+    }
+    engine.forward_chaining_with_variables()?;
+    assert!(connected(&engine, 0, 10));
+    assert!(!connected(&engine, 10, 0));
+
+    // Only the new edge has to be joined with the paths found so far
+    engine.assert_predicate(edge.clone(), pair(10, 0), FactValue::Boolean(true))?;
+    engine.forward_chaining_with_variables()?;
+    assert!(connected(&engine, 10, 0) && connected(&engine, 5, 5));
+
+    engine.retract_fact(&Fact::with_arguments(edge, pair(10, 0), FactValue::Boolean(true)))?;
+    assert!(!connected(&engine, 5, 5));
+    assert!(connected(&engine, 0, 10));
+    Ok(())
+}
+
+#[test]
+fn facts_can_key_hash_maps() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let price = engine.define_predicate("Price", vec![SymbolType::Text], SymbolType::Float)?;
+    let item = |name: &str| vec![FactValue::Text(name.into())];
+
+    engine.assert_predicate(price.clone(), item("tea"), FactValue::Float(2.5))?;
+    engine.assert_predicate(price.clone(), item("tea"), FactValue::Float(2.5))?;
+    engine.assert_predicate(price.clone(), item("void"), FactValue::Float(f64::NAN))?;
+    engine.assert_predicate(price.clone(), item("void"), FactValue::Float(f64::NAN))?;
+    assert_eq!(engine.facts().count(), 2);
+
+    let mut seen: HashMap<Fact, usize> = HashMap::new();
+    for fact in engine.facts() {
+        *seen.entry(fact.clone()).or_default() += 1;
+    }
+    assert_eq!(seen.get(&Fact::with_arguments(price.clone(), item("void"), FactValue::Float(f64::NAN))), Some(&1));
+    assert_eq!(FactValue::Float(-0.0), FactValue::Float(0.0));
+    assert_ne!(FactValue::Float(1.0), FactValue::Integer(1));
+    Ok(())
+}
+
+#[test]
+fn conflict_strategies_order_the_agenda() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let task = engine.define_predicate("Task", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let urgent = engine.define_predicate("Urgent", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let done = engine.define_predicate("Done", vec![SymbolType::Text], SymbolType::Text)?;
+    let text = |value: &str| FactValue::Text(value.into());
+    let pattern = |symbol: &Symbol| atom(symbol, vec![var("t")]);
+
+    engine.define_rule(pattern(&task), Fact::with_arguments(done.clone(), vec![var("t")], text("later")))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![pattern(&task), pattern(&urgent)]),
+        Fact::with_arguments(done.clone(), vec![var("t")], text("now")),
+    )?;
+    for name in ["write", "review", "deploy"] {
+        engine.assert_predicate(task.clone(), vec![text(name)], FactValue::Boolean(true))?;
+    }
+    engine.assert_predicate(urgent.clone(), vec![text("write")], FactValue::Boolean(true))?;
+
+    let mut order = |strategy| -> Result<Vec<(usize, Fact)>, EngineError> {
+        engine.set_conflict_strategy(strategy);
+        Ok(engine.agenda()?.iter().map(|activation| (activation.rule(), activation.antecedents()[0].clone())).collect())
+    };
+    let entry = |rule, name: &str| (rule, Fact::with_arguments(task.clone(), vec![text(name)], FactValue::Boolean(true)));
+
+    assert_eq!(order(ConflictStrategy::Definition)?, vec![entry(0, "write"), entry(0, "review"), entry(0, "deploy"), entry(1, "write")]);
+    assert_eq!(order(ConflictStrategy::Recency)?, vec![entry(1, "write"), entry(0, "deploy"), entry(0, "review"), entry(0, "write")]);
+    assert_eq!(order(ConflictStrategy::Specificity)?, vec![entry(1, "write"), entry(0, "write"), entry(0, "review"), entry(0, "deploy")]);
+    assert_eq!(order(ConflictStrategy::Mea)?, vec![entry(0, "deploy"), entry(0, "review"), entry(1, "write"), entry(0, "write")]);
+
+    engine.set_conflict_strategy(ConflictStrategy::Lex);
+    engine.forward_chaining_with_variables()?;
+    assert!(engine.contains_fact(&Fact::with_arguments(done.clone(), vec![text("write")], text("now"))));
+    assert!(engine.contains_fact(&Fact::with_arguments(done, vec![text("write")], text("later"))));
+    assert_eq!(engine.conflict_strategy(), ConflictStrategy::Lex);
+    Ok(())
+}
+
+#[test]
+fn datalog_evaluation_derives_recursive_relations_in_strata() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let parent = engine.define_predicate("Parent", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let ancestor = engine.define_predicate("Ancestor", vec![SymbolType::Text, SymbolType::Text], SymbolType::Boolean)?;
+    let person = engine.define_predicate("Person", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let founder = engine.define_predicate("Founder", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let text = |value: &str| FactValue::Text(value.into());
+
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&person, vec![var("x")]), LogicalOperator::Not(Box::new(atom(&ancestor, vec![var("anyone"), var("x")])))]),
+        Fact::with_arguments(founder.clone(), vec![var("x")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(atom(&parent, vec![var("x"), var("y")]), Fact::with_arguments(ancestor.clone(), vec![var("x"), var("y")], FactValue::Boolean(true)))?;
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&ancestor, vec![var("x"), var("y")]), atom(&parent, vec![var("y"), var("z")])]),
+        Fact::with_arguments(ancestor.clone(), vec![var("x"), var("z")], FactValue::Boolean(true)),
+    )?;
+    for name in ["ada", "bea", "cy", "dan"] {
+        engine.assert_predicate(person.clone(), vec![text(name)], FactValue::Boolean(true))?;
+    }
+    for (older, younger) in [("ada", "bea"), ("bea", "cy")] {
+        engine.assert_predicate(parent.clone(), vec![text(older), text(younger)], FactValue::Boolean(true))?;
+    }
+
+    let derived = engine.evaluate_datalog()?;
+    let founders: Vec<&Fact> = derived.iter().filter(|fact| fact.symbol() == &founder).collect();
+    assert_eq!(founders, vec![
+        &Fact::with_arguments(founder.clone(), vec![text("ada")], FactValue::Boolean(true)),
+        &Fact::with_arguments(founder.clone(), vec![text("dan")], FactValue::Boolean(true)),
+    ]);
+    assert!(engine.contains_fact(&Fact::with_arguments(ancestor.clone(), vec![text("ada"), text("cy")], FactValue::Boolean(true))));
+
+    // A founder that is also a parent of someone closes a cycle through negation
+    engine.define_rule(
+        atom(&founder, vec![var("x")]),
+        Fact::with_arguments(ancestor, vec![var("x"), var("x")], FactValue::Boolean(true)),
+    )?;
+    assert!(matches!(engine.evaluate_datalog(), Err(EngineError::Unstratifiable { .. })));
+    Ok(())
+}
+
+#[test]
+fn forward_chaining_evaluates_negation_by_strata() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let bird = engine.define_predicate("Bird", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let penguin = engine.define_predicate("Penguin", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let abnormal = engine.define_predicate("Abnormal", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let flies = engine.define_predicate("Flies", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let text = |value: &str| FactValue::Text(value.into());
+
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&bird, vec![var("x")]), LogicalOperator::Not(Box::new(atom(&abnormal, vec![var("x")])))]),
+        Fact::with_arguments(flies.clone(), vec![var("x")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(atom(&penguin, vec![var("x")]), Fact::with_arguments(abnormal.clone(), vec![var("x")], FactValue::Boolean(true)))?;
+    assert_eq!(engine.strata()?, vec![vec![1], vec![0]]);
+
+    for name in ["tweety", "pingu"] {
+        engine.assert_predicate(bird.clone(), vec![text(name)], FactValue::Boolean(true))?;
+    }
+    engine.assert_predicate(penguin, vec![text("pingu")], FactValue::Boolean(true))?;
+    engine.forward_chaining()?;
+    assert!(engine.contains_fact(&Fact::with_arguments(flies.clone(), vec![text("tweety")], FactValue::Boolean(true))));
+    assert!(!engine.contains_fact(&Fact::with_arguments(flies.clone(), vec![text("pingu")], FactValue::Boolean(true))));
+
+    // Birds that fly being abnormal would make flying depend on its own negation
+    engine.define_rule(atom(&flies, vec![var("x")]), Fact::with_arguments(abnormal, vec![var("x")], FactValue::Boolean(true)))?;
+    assert!(matches!(engine.forward_chaining(), Err(EngineError::Unstratifiable { .. })));
+    assert!(matches!(engine.agenda(), Err(EngineError::Unstratifiable { .. })));
+    Ok(())
+}
+
+#[test]
+fn stable_models_choose_between_mutually_negated_options() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let feature = engine.define_predicate("Feature", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let local = engine.define_predicate("LocalCache", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let remote = engine.define_predicate("RemoteCache", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let rejected = engine.define_predicate("Rejected", vec![SymbolType::Text], SymbolType::Boolean)?;
+    let text = |value: &str| FactValue::Text(value.into());
+
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&feature, vec![var("f")]), LogicalOperator::Not(Box::new(atom(&remote, vec![var("f")])))]),
+        Fact::with_arguments(local.clone(), vec![var("f")], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&feature, vec![var("f")]), LogicalOperator::Not(Box::new(atom(&local, vec![var("f")])))]),
+        Fact::with_arguments(remote.clone(), vec![var("f")], FactValue::Boolean(true)),
+    )?;
+    assert!(matches!(engine.forward_chaining(), Err(EngineError::Unstratifiable { .. })));
+
+    engine.assert_predicate(feature, vec![text("search")], FactValue::Boolean(true))?;
+    let models = engine.stable_models()?;
+    assert_eq!(models, vec![
+        vec![Fact::with_arguments(local.clone(), vec![text("search")], FactValue::Boolean(true))],
+        vec![Fact::with_arguments(remote, vec![text("search")], FactValue::Boolean(true))],
+    ]);
+    assert!(!engine.contains_fact(&models[0][0]));
+
+    // A local cache rejected unless it is rejected rules out every model choosing it
+    engine.define_rule(
+        LogicalOperator::And(vec![atom(&local, vec![var("f")]), LogicalOperator::Not(Box::new(atom(&rejected, vec![var("f")])))]),
+        Fact::with_arguments(rejected, vec![var("f")], FactValue::Boolean(true)),
+    )?;
+    assert_eq!(engine.stable_models()?, vec![models[1].clone()]);
+    Ok(())
+}
+
+#[test]
+fn rule_language_programs_parse_and_load() -> Result<(), EngineError> {
+    let source = r#"
+        symbol Weather: Enum(Sunny, "Partly cloudy", Rainy)
+        symbol Temperature: Float
+        symbol Activity: Text
+        symbol Suggested(Text): Boolean
+
+        rule hiking: Weather == "Sunny" and Temperature >= 20 => Activity = "Hiking"
+        rule salience 5: (Weather = "Rainy" or Temperature < 5) and not Weather = "Sunny"
+            => Activity = "Reading", call log("indoors")
+        rule: Activity = ?a => Suggested(?a)
+    "#;
+    let program: Program = source.parse()?;
+    let rules: Vec<&Rule> = program.rules().collect();
+    assert_eq!(rules.iter().map(|rule| rule.name()).collect::<Vec<_>>(), vec![Some("hiking"), None, None]);
+    assert_eq!(rules[1].salience(), 5);
+    assert_eq!(rules[1].actions()[1], Action::Call { callback: "log".into(), arguments: vec![FactValue::Text("indoors".into())] });
+    let weather = program.symbols().next().unwrap();
+    assert_eq!(weather.symbol_type(), &SymbolType::Enum(vec!["Sunny".into(), "Partly cloudy".into(), "Rainy".into()]));
+
+    let mut engine = SymbolicReasoningEngine::new();
+    engine.load_program(source)?;
+    engine.register_callback("log", |_| {});
+    engine.load_program("fact Weather = \"Sunny\"\nfact Temperature = 22.5")?;
+    engine.forward_chaining_with_variables()?;
+    let suggested = engine.symbol("Suggested").unwrap().clone();
+    assert!(engine.contains_fact(&Fact::with_arguments(suggested, vec![FactValue::Text("Hiking".into())], FactValue::Boolean(true))));
+
+    // Programs parsed on their own only know the symbols they define
+    let error = "rule: Weather == \"Sunny\" => Activity = \"Hiking\"".parse::<Program>().unwrap_err();
+    assert_eq!(error, EngineError::Located { line: 1, column: 7, source: Box::new(EngineError::UnknownSymbol { symbol: "Weather".into() }) });
+    let error = engine.load_program("rule: Temperature >> 3 => Activity = \"Hot\"").unwrap_err();
+    assert_eq!(error.to_string(), "syntax error at line 1, column 20: expected a value, found '>'");
+    Ok(())
+}
+
+#[test]
+fn displayed_rules_and_facts_reparse_to_equal_structures() -> Result<(), EngineError> {
+    let declarations = "symbol Sensor(Text): Float\nsymbol Status(Text): Enum(Ok, Hot)\n";
+    let mut engine = SymbolicReasoningEngine::new();
+    engine.load_program(declarations)?;
+    engine.load_program(r#"
+        fact Sensor("boiler") = 97.5
+        rule overheating salience 3: Sensor(?s) = ?t and ?t > 90 and not Status(?s) = "Hot" => Status(?s) = "Hot"
+        rule: (Sensor(?s) = ?t or Sensor(?s) = ?t and ?t < 0.5) and all() => modify Sensor(?s) = 0.0, call log(?s, "reset")
+    "#)?;
+
+    let facts: Vec<String> = engine.facts().map(ToString::to_string).collect();
+    assert_eq!(facts, vec!["Sensor(\"boiler\") = 97.5"]);
+    let text: Vec<String> = engine.rules().iter().map(ToString::to_string).collect();
+    assert_eq!(text[0], "rule overheating salience 3: Sensor(?s) = ?t and ?t > 90 and not Status(?s) = \"Hot\" => Status(?s) = \"Hot\"");
+
+    let program: Program = format!("{}{}\nfact {}", declarations, text.join("\n"), facts[0]).parse()?;
+    assert_eq!(program.rules().collect::<Vec<_>>(), engine.rules().iter().collect::<Vec<_>>());
+    assert_eq!(program.facts().collect::<Vec<_>>(), engine.facts().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn saved_knowledge_bases_load_into_equal_engines() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    let level = engine.define_symbol("Level", SymbolType::IntegerRange { min: 0, max: 10 })?;
+    let alert = engine.define_predicate("Alert", vec![SymbolType::Text], SymbolType::Boolean)?;
+    engine.assert_variable(&Variable::new("limit", FactValue::Float(2.5), VariableState::Unstable));
+    engine.assert_fact(level.clone(), FactValue::Integer(7))?;
+    // Rules without actions are saved before the next statement and at the end of the file
+    let pattern = LogicalOperator::AtomicFact(Fact::new(level.clone(), FactValue::Variable("level".into())));
+    engine.define_rule_with_actions(pattern.clone(), Vec::new())?;
+    engine.define_rule(
+        LogicalOperator::GreaterThan(Box::new(ComparableValue::Symbol(level)), Box::new(ComparableValue::Direct(FactValue::Integer(5)))),
+        Fact::with_arguments(alert, vec![FactValue::Text("high".into())], FactValue::Boolean(true)),
+    )?;
+    engine.define_rule_with_actions(pattern, Vec::new())?;
+
+    let path = std::env::temp_dir().join(format!("engine_api_{}.kb", std::process::id()));
+    engine.save_to(&path)?;
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with(&format!("# symbolic_reasoning_engine knowledge base, format {}\n", symbolic_reasoning_engine::FORMAT_VERSION)));
+
+    let mut loaded = SymbolicReasoningEngine::load_from(&path)?;
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.program().to_string(), engine.program().to_string());
+    assert_eq!(loaded.rules(), engine.rules());
+    assert_eq!(loaded.variable("limit").map(Variable::state), Some(&VariableState::Unstable));
+    loaded.forward_chaining()?;
+    assert_eq!(loaded.facts().map(ToString::to_string).collect::<Vec<_>>(), vec!["Level = 7", "Alert(\"high\")"]);
+
+    let error = SymbolicReasoningEngine::load_from(&path).err().unwrap();
+    assert!(matches!(error, EngineError::Io { .. }));
+    Ok(())
+}
+
+#[test]
+fn json_knowledge_bases_load_and_derived_facts_dump() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    engine.load_json(r#"{
+        "symbols": [
+            { "name": "Reading", "signature": ["Text"], "symbol_type": "Float" },
+            { "name": "Alarm", "signature": ["Text"], "symbol_type": "Boolean" }
+        ],
+        "rules": [{
+            "name": "overheat",
+            "premise": { "And": [
+                { "AtomicFact": { "symbol": "Reading", "arguments": [{ "Variable": "s" }], "value": { "Variable": "t" } } },
+                { "GreaterThan": [{ "Direct": { "Variable": "t" } }, { "Direct": 90 }] }
+            ] },
+            "actions": [{ "Assert": { "symbol": "Alarm", "arguments": [{ "Variable": "s" }] } }]
+        }]
+    }"#)?;
+    engine.load_json(r#"{ "facts": [
+        { "symbol": "Reading", "arguments": ["boiler"], "value": 97 },
+        { "symbol": "Reading", "arguments": ["pump"], "value": 40.5 }
+    ] }"#)?;
+    engine.forward_chaining()?;
+
+    let alarm = engine.symbol("Alarm").unwrap().clone();
+    let expected = Fact::with_arguments(alarm, vec![FactValue::Text("boiler".into())], FactValue::Boolean(true));
+    assert_eq!(engine.derived_facts_to_json(), serde_json::json!([expected.to_json()]));
+    assert_eq!(engine.fact_from_json(&expected.to_json())?, expected);
+    assert_eq!(&engine.rule_from_json(&engine.rules()[0].to_json())?, &engine.rules()[0]);
+
+    let error = engine.load_json(r#"{ "facts": [{ "symbol": "Reading", "value": 1 }] }"#).unwrap_err();
+    assert_eq!(error.to_string(), "at facts[0]: symbol 'Reading' takes 1 argument(s), found 0");
+    Ok(())
+}
+
+#[test]
+fn json_logic_conditions_become_rule_premises() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    engine.load_program("symbol Age: Integer\nsymbol Country: Text\nsymbol Eligible: Boolean\nfact Age = 34\nfact Country = \"NL\"")?;
+
+    // A condition built by a front-end, with every comparison operand reading a symbol by name
+    let condition = serde_json::json!({ "and": [
+        { "<=": [18, { "var": "Age" }, 65] },
+        { "or": [{ "==": [{ "var": "Country" }, "NL"] }, { "==": [{ "var": "Country" }, "BE"] }] }
+    ] });
+    let premise = LogicalOperator::from_json_logic(&condition)?;
+    let eligible = engine.symbol("Eligible").unwrap().clone();
+    engine.add_rule(Rule::new(premise.clone(), Fact::new(eligible.clone(), FactValue::Boolean(true))))?;
+    engine.forward_chaining()?;
+    assert!(engine.contains_fact(&Fact::new(eligible, FactValue::Boolean(true))));
+
+    assert_eq!(LogicalOperator::from_json_logic(&premise.to_json_logic()?)?, premise);
+    let error = LogicalOperator::from_json_logic(&serde_json::json!({ "some": [{ "var": "Tags" }, { "==": [{ "var": "" }, "x"] }] })).unwrap_err();
+    assert_eq!(error, EngineError::UnsupportedJsonLogic { operation: "some".into() });
+    Ok(())
+}
+
+#[test]
+fn sre_shell_explores_and_saves_a_knowledge_base() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let path = std::env::temp_dir().join(format!("sre_shell_{}.kb", std::process::id()));
+    let input = format!(
+        "symbol Rain: Boolean\nsymbol Wet: Boolean\nrule wet: \\\n  Rain => Wet\nfact Rain\nask Wet\nrun\nfacts\nask Snow\nsave {}\nquit\nfacts\n",
+        path.display(),
+    );
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sre")).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("yes\nWet\nRain\nWet\nsaved {}\n", path.display()));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "error: at line 1, column 1: symbol 'Snow' is not defined\n");
+
+    // Knowledge base files given as arguments are loaded before the first prompt
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sre")).arg(&path).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(b"rules\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[0] rule wet: Rain => Wet\n");
+
+    // Cycles met while proving a goal are only reported as debug output
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sre")).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(b"symbol A: Boolean\nsymbol B: Boolean\nrule: B => A\nrule: A => B\nask A\n").unwrap();
+    assert_eq!(String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap(), "no\n");
+}
+
+#[test]
+fn arithmetic_operands_compare_derived_quantities() -> Result<(), EngineError> {
+    let mut engine = SymbolicReasoningEngine::new();
+    engine.load_program("
+        symbol Temperature: Float
+        symbol Dewpoint: Float
+        symbol Price: Float
+        symbol Quantity: Integer
+        symbol Fog: Boolean
+        symbol LargeOrder: Boolean
+        fact Temperature = 12.5
+        fact Dewpoint = 10.0
+        fact Price = 12.5
+        fact Quantity = 100
+        rule fog: Temperature - Dewpoint < 3 => Fog
+        rule large: Price * Quantity > 1000 => LargeOrder
+    ")?;
+    engine.forward_chaining()?;
+    assert!(engine.contains_fact(&engine.parse_fact("Fog")?));
+    assert!(engine.contains_fact(&engine.parse_fact("LargeOrder")?));
+
+    let spread = Arithmetic::Subtract(
+        Box::new(ComparableValue::Symbol(engine.symbol("Temperature").unwrap().clone())),
+        Box::new(ComparableValue::Symbol(engine.symbol("Dewpoint").unwrap().clone())),
+    );
+    let premise = LogicalOperator::LessThan(
+        Box::new(ComparableValue::Arithmetic(Box::new(spread))),
+        Box::new(ComparableValue::Direct(FactValue::Integer(3))),
+    );
+    assert_eq!(engine.rules()[0].premise(), &premise);
+    assert_eq!(engine.rules()[1].to_string(), "rule large: Price * Quantity > 1000 => LargeOrder");
+
+    engine.load_program("symbol Ratio: Boolean\nrule: Price / (Quantity - 100) > 1 => Ratio")?;
+    let error = engine.forward_chaining().unwrap_err();
+    assert_eq!(error.to_string(), "error evaluating rule 2: division by zero in 'Price / (Quantity - 100)'");
+    Ok(())
+}